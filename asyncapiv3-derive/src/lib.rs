@@ -0,0 +1,355 @@
+//! Derive macros of the `asyncapiv3` crate, re-exported by its `derive` feature.
+//!
+//! - `#[derive(AsyncApiMessage)]` describes a message whose payload is the deriving type
+//! - `#[asyncapi_operation(...)]` describes the operation implemented by a handler function
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::parse::Parser;
+use syn::spanned::Spanned;
+use syn::{
+    parse_macro_input, Attribute, DeriveInput, FnArg, GenericArgument, ItemFn, LitStr,
+    PathArguments, ReturnType, Type,
+};
+
+/// Implements `asyncapiv3::builder::describe::AsyncApiMessage` for a type that also implements
+/// `schemars::JsonSchema`.
+///
+/// Optional attributes, in `#[asyncapi(...)]`:
+/// - `name = "..."`: key of the message in its channels, the type name by default
+/// - `title`, `summary`, `description` (the doc comment by default) and `content_type`
+/// - `example = "..."`: JSON example of the payload, can be repeated
+/// - `headers = Type`: type whose schema describes the message headers
+#[proc_macro_derive(AsyncApiMessage, attributes(asyncapi))]
+pub fn derive_asyncapi_message(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_message(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Describes the operation implemented by a handler function, generating next to it a
+/// `<function>_operation()` function returning its
+/// `asyncapiv3::builder::describe::OperationDescriptor`.
+///
+/// The message of the operation is the type of the argument marked with `#[payload]`, or of the
+/// last argument, and must implement `AsyncApiMessage`. A return type other than `()` (or the
+/// success type of a `Result`) is the reply of a request/reply operation.
+///
+/// Attributes:
+/// - `channel = "..."` (required): key of the channel
+/// - `address = "..."`: address of the channel
+/// - `action = "send" | "receive"`: `receive` by default
+/// - `name = "..."`: key of the operation, the function name by default
+/// - `reply_channel = "..."` and `reply_address = "..."`: channel of the reply, `<channel>Reply`
+///   by default
+#[proc_macro_attribute]
+pub fn asyncapi_operation(args: TokenStream, item: TokenStream) -> TokenStream {
+    let mut function = parse_macro_input!(item as ItemFn);
+    let mut operation = OperationArgs::default();
+    let parser = syn::meta::parser(|meta| operation.parse(meta));
+    if let Err(err) = parser.parse(args) {
+        return err.into_compile_error().into();
+    }
+    match expand_operation(operation, &mut function) {
+        Ok(descriptor) => quote!(#function #descriptor).into(),
+        Err(err) => {
+            let err = err.into_compile_error();
+            quote!(#function #err).into()
+        }
+    }
+}
+
+#[derive(Default)]
+struct MessageArgs {
+    name: Option<LitStr>,
+    title: Option<LitStr>,
+    summary: Option<LitStr>,
+    description: Option<LitStr>,
+    content_type: Option<LitStr>,
+    examples: Vec<LitStr>,
+    headers: Option<Type>,
+}
+
+fn expand_message(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let mut args = MessageArgs::default();
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("asyncapi"))
+    {
+        attr.parse_nested_meta(|meta| {
+            let string = || -> syn::Result<LitStr> { meta.value()?.parse() };
+            if meta.path.is_ident("name") {
+                args.name = Some(string()?);
+            } else if meta.path.is_ident("title") {
+                args.title = Some(string()?);
+            } else if meta.path.is_ident("summary") {
+                args.summary = Some(string()?);
+            } else if meta.path.is_ident("description") {
+                args.description = Some(string()?);
+            } else if meta.path.is_ident("content_type") {
+                args.content_type = Some(string()?);
+            } else if meta.path.is_ident("example") {
+                let example = string()?;
+                if let Err(err) = serde_json::from_str::<serde_json::Value>(&example.value()) {
+                    return Err(syn::Error::new(
+                        example.span(),
+                        format!("invalid JSON example: {err}"),
+                    ));
+                }
+                args.examples.push(example);
+            } else if meta.path.is_ident("headers") {
+                args.headers = Some(meta.value()?.parse()?);
+            } else {
+                return Err(meta.error("unsupported asyncapi attribute"));
+            }
+            Ok(())
+        })?;
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let message_name = match &args.name {
+        Some(name) => quote!(#name),
+        None => {
+            let name = ident.to_string();
+            quote!(#name)
+        }
+    };
+    let description = args
+        .description
+        .map(|description| description.value())
+        .or_else(|| doc_comment(&input.attrs));
+    let name = optional_string(args.name.map(|name| name.value()));
+    let title = optional_string(args.title.map(|title| title.value()));
+    let summary = optional_string(args.summary.map(|summary| summary.value()));
+    let description = optional_string(description);
+    let content_type = optional_string(args.content_type.map(|content_type| content_type.value()));
+    let examples = args.examples;
+    let headers = args
+        .headers
+        .map(|headers| quote!(.with_headers::<#headers>()));
+
+    Ok(quote! {
+        impl #impl_generics ::asyncapiv3::builder::describe::AsyncApiMessage for #ident #ty_generics #where_clause {
+            fn message_name() -> ::std::string::String {
+                ::std::string::String::from(#message_name)
+            }
+
+            fn message_spec() -> ::asyncapiv3::builder::operation::MessageFullSpec {
+                ::asyncapiv3::builder::operation::MessageFullSpec::for_type::<Self>(
+                    ::asyncapiv3::builder::describe::MessageMetadata {
+                        name: #name,
+                        title: #title,
+                        summary: #summary,
+                        description: #description,
+                        content_type: #content_type,
+                        examples: ::std::vec![
+                            #(::asyncapiv3::builder::describe::example_from_json(#examples)),*
+                        ],
+                    },
+                )
+                #headers
+            }
+        }
+    })
+}
+
+#[derive(Default)]
+struct OperationArgs {
+    channel: Option<LitStr>,
+    address: Option<LitStr>,
+    action: Option<LitStr>,
+    name: Option<LitStr>,
+    reply_channel: Option<LitStr>,
+    reply_address: Option<LitStr>,
+}
+
+impl OperationArgs {
+    fn parse(&mut self, meta: syn::meta::ParseNestedMeta) -> syn::Result<()> {
+        let value = Some(meta.value()?.parse::<LitStr>()?);
+        if meta.path.is_ident("channel") {
+            self.channel = value;
+        } else if meta.path.is_ident("address") {
+            self.address = value;
+        } else if meta.path.is_ident("action") {
+            self.action = value;
+        } else if meta.path.is_ident("name") {
+            self.name = value;
+        } else if meta.path.is_ident("reply_channel") {
+            self.reply_channel = value;
+        } else if meta.path.is_ident("reply_address") {
+            self.reply_address = value;
+        } else {
+            return Err(meta.error("unsupported asyncapi_operation attribute"));
+        }
+        Ok(())
+    }
+}
+
+fn expand_operation(args: OperationArgs, function: &mut ItemFn) -> syn::Result<TokenStream2> {
+    let signature = &function.sig;
+    let Some(channel) = args.channel else {
+        return Err(syn::Error::new(
+            signature.ident.span(),
+            "missing `channel` attribute",
+        ));
+    };
+    let action = match args.action.as_ref().map(LitStr::value).as_deref() {
+        None | Some("receive") => quote!(::asyncapiv3::spec::operation::OperationAction::Receive),
+        Some("send") => quote!(::asyncapiv3::spec::operation::OperationAction::Send),
+        Some(_) => {
+            return Err(syn::Error::new(
+                args.action.span(),
+                "`action` must be \"send\" or \"receive\"",
+            ))
+        }
+    };
+
+    let payload = payload_type(function)?;
+    let reply = reply_type(&function.sig.output);
+
+    let function_ident = &function.sig.ident;
+    let name = args
+        .name
+        .map(|name| name.value())
+        .unwrap_or_else(|| function_ident.to_string());
+    let address = optional_string(args.address.map(|address| address.value()));
+    let description = doc_comment(&function.attrs);
+    let summary = optional_string(
+        description
+            .as_deref()
+            .and_then(|description| description.lines().next())
+            .map(String::from),
+    );
+    let description = optional_string(description);
+    let reply = match reply {
+        Some(reply) => {
+            let reply_channel = args
+                .reply_channel
+                .map(|channel| channel.value())
+                .unwrap_or_else(|| format!("{}Reply", channel.value()));
+            let reply_address = optional_string(args.reply_address.map(|address| address.value()));
+            quote! {
+                ::std::option::Option::Some(::asyncapiv3::builder::describe::ReplyDescriptor {
+                    channel: ::std::string::String::from(#reply_channel),
+                    address: #reply_address,
+                    message_name: <#reply as ::asyncapiv3::builder::describe::AsyncApiMessage>::message_name(),
+                    message: <#reply as ::asyncapiv3::builder::describe::AsyncApiMessage>::message_spec(),
+                })
+            }
+        }
+        None => quote!(::std::option::Option::None),
+    };
+
+    let visibility = &function.vis;
+    let descriptor_ident = format_ident!("{}_operation", function_ident);
+    Ok(quote! {
+        #[doc = concat!("AsyncAPI description of the operation implemented by [`", stringify!(#function_ident), "`].")]
+        #visibility fn #descriptor_ident() -> ::asyncapiv3::builder::describe::OperationDescriptor {
+            ::asyncapiv3::builder::describe::OperationDescriptor {
+                name: ::std::string::String::from(#name),
+                action: #action,
+                channel: ::std::string::String::from(#channel),
+                address: #address,
+                summary: #summary,
+                description: #description,
+                message_name: <#payload as ::asyncapiv3::builder::describe::AsyncApiMessage>::message_name(),
+                message: <#payload as ::asyncapiv3::builder::describe::AsyncApiMessage>::message_spec(),
+                reply: #reply,
+            }
+        }
+    })
+}
+
+/// Type of the argument marked with `#[payload]`, or of the last argument. The marker attribute
+/// is removed from the function.
+fn payload_type(function: &mut ItemFn) -> syn::Result<Type> {
+    let mut marked = None;
+    let mut last = None;
+    for input in function.sig.inputs.iter_mut() {
+        let FnArg::Typed(argument) = input else {
+            continue;
+        };
+        let is_marked = argument
+            .attrs
+            .iter()
+            .any(|attr| attr.path().is_ident("payload"));
+        argument
+            .attrs
+            .retain(|attr| !attr.path().is_ident("payload"));
+        let ty = strip_reference(&argument.ty).clone();
+        if is_marked {
+            marked = Some(ty);
+        } else {
+            last = Some(ty);
+        }
+    }
+    marked.or(last).ok_or_else(|| {
+        syn::Error::new(
+            function.sig.ident.span(),
+            "the handler must have a payload argument",
+        )
+    })
+}
+
+/// Reply type of a handler: its return type, or the success type of a returned `Result`.
+fn reply_type(output: &ReturnType) -> Option<Type> {
+    let ReturnType::Type(_, ty) = output else {
+        return None;
+    };
+    let ty = strip_reference(ty);
+    if let Type::Path(path) = ty {
+        let last = path.path.segments.last()?;
+        if last.ident == "Result" {
+            if let PathArguments::AngleBracketed(arguments) = &last.arguments {
+                if let Some(GenericArgument::Type(ok)) = arguments.args.first() {
+                    return (!is_unit(ok)).then(|| ok.clone());
+                }
+            }
+        }
+    }
+    (!is_unit(ty)).then(|| ty.clone())
+}
+
+fn strip_reference(ty: &Type) -> &Type {
+    match ty {
+        Type::Reference(reference) => strip_reference(&reference.elem),
+        Type::Paren(paren) => strip_reference(&paren.elem),
+        other => other,
+    }
+}
+
+fn is_unit(ty: &Type) -> bool {
+    matches!(ty, Type::Tuple(tuple) if tuple.elems.is_empty())
+}
+
+/// Text of the doc comments, one line per `///` line.
+fn doc_comment(attrs: &[Attribute]) -> Option<String> {
+    let lines = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(name_value) => match &name_value.value {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(line),
+                    ..
+                }) => Some(line.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .map(|line| String::from(line.strip_prefix(' ').unwrap_or(&line)))
+        .collect::<Vec<_>>();
+    let text = lines.join("\n");
+    let text = text.trim();
+    (!text.is_empty()).then(|| String::from(text))
+}
+
+fn optional_string(value: Option<String>) -> TokenStream2 {
+    match value {
+        Some(value) => quote!(::std::option::Option::Some(::std::string::String::from(#value))),
+        None => quote!(::std::option::Option::None),
+    }
+}
@@ -0,0 +1,328 @@
+//! Command-line tool to validate, lint, bundle, compare and convert AsyncAPI specifications.
+//!
+//! The exit code is 0 on success, 1 when problems are found in the specifications, and 2 when
+//! the command cannot run, e.g. when a file cannot be read.
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use asyncapiv3::diff::{diff, Change};
+use asyncapiv3::lint::{LintConfig, Linter, Severity};
+use asyncapiv3::loader::{self, lenient};
+use asyncapiv3::report::Report;
+use asyncapiv3::spec::format::Format;
+use asyncapiv3::spec::{AsyncApiSpec, AsyncApiV3Spec};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde_json::Value;
+
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    /// Format of the reports.
+    #[arg(long, global = true, value_enum, default_value_t = ReportFormat::Human)]
+    format: ReportFormat,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Checks that specifications are valid, reporting all their errors.
+    Validate {
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+    /// Checks specifications against the lint rules.
+    Lint {
+        /// TOML or YAML file setting the severities of the rules.
+        #[arg(long)]
+        config: Option<PathBuf>,
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+    /// Merges a specification split across files into a single document.
+    Bundle {
+        file: PathBuf,
+        #[command(flatten)]
+        output: Output,
+    },
+    /// Lists the changes between two versions of a specification.
+    Diff {
+        old: PathBuf,
+        new: PathBuf,
+        /// Changes making the command fail.
+        #[arg(long, value_enum, default_value_t = FailOn::Never)]
+        fail_on: FailOn,
+    },
+    /// Converts a specification between YAML and JSON, or from AsyncAPI 2 to AsyncAPI 3.
+    Convert {
+        file: PathBuf,
+        #[command(flatten)]
+        output: Output,
+    },
+}
+
+#[derive(clap::Args)]
+struct Output {
+    /// File to write, instead of the standard output.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// Format of the written document, by default the one of the output file or of the input.
+    #[arg(long, value_enum)]
+    to: Option<DocumentFormat>,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum ReportFormat {
+    Human,
+    Json,
+    /// SARIF 2.1.0, for the validation and lint findings.
+    Sarif,
+    /// JUnit XML, for the validation and lint findings.
+    Junit,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum DocumentFormat {
+    Yaml,
+    Json,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum FailOn {
+    Never,
+    Breaking,
+    Any,
+}
+
+/// Outcome of a command that ran: whether problems were found.
+type Outcome = Result<bool, String>;
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let outcome = match cli.command {
+        Command::Validate { files } => validate(&files, cli.format),
+        Command::Lint { config, files } => lint(config.as_deref(), &files, cli.format),
+        Command::Bundle { file, output } => {
+            human_only("bundle", cli.format).and_then(|()| bundle(&file, &output))
+        }
+        Command::Diff { old, new, fail_on } => compare(&old, &new, fail_on, cli.format),
+        Command::Convert { file, output } => {
+            human_only("convert", cli.format).and_then(|()| convert(&file, &output))
+        }
+    };
+    match outcome {
+        Ok(false) => ExitCode::SUCCESS,
+        Ok(true) => ExitCode::from(1),
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn validate(files: &[PathBuf], format: ReportFormat) -> Outcome {
+    let mut report = Report::new();
+    for file in files {
+        match lenient::load_file(file) {
+            Ok(loaded) => report.add_validation(file, &loaded),
+            Err(err) => add_load_error(&mut report, file, err)?,
+        }
+    }
+    print_report(&report, format)
+}
+
+fn lint(config: Option<&Path>, files: &[PathBuf], format: ReportFormat) -> Outcome {
+    let mut linter = Linter::new();
+    if let Some(config) = config {
+        let content = read(config)?;
+        let config = match Format::from_extension(config) {
+            Some(Format::Json) | Some(Format::Yaml) => LintConfig::from_yaml(&content),
+            None => LintConfig::from_toml(&content),
+        };
+        linter = linter
+            .configure(config.map_err(|err| err.to_string())?)
+            .map_err(|err| err.to_string())?;
+    }
+
+    let mut report = Report::new().with_linter(&linter);
+    for file in files {
+        match loader::load_file(file) {
+            Ok(loaded) => {
+                let AsyncApiSpec::V3_0_0(spec) = &loaded.spec;
+                report.add_lint(file, &loaded, linter.lint(spec));
+            }
+            Err(err) => add_load_error(&mut report, file, err)?,
+        }
+    }
+    print_report(&report, format)
+}
+
+fn bundle(file: &Path, output: &Output) -> Outcome {
+    let document = match loader::bundle::bundle_file(file) {
+        Ok(document) => document,
+        Err(loader::Error::Io { path, source }) => {
+            return Err(format!("cannot read {}: {source}", path.display()))
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            return Ok(true);
+        }
+    };
+    write_spec(file, &document, output)
+}
+
+fn compare(old: &Path, new: &Path, fail_on: FailOn, format: ReportFormat) -> Outcome {
+    let mut report = Report::new();
+    let (old, new) = match (load_v3(old, &mut report)?, load_v3(new, &mut report)?) {
+        (Some(old), Some(new)) => (old, new),
+        _ => {
+            print_report(&report, format)?;
+            return Ok(true);
+        }
+    };
+    let changes = diff(&old, &new);
+    match format {
+        ReportFormat::Human => {
+            changes.iter().for_each(|change| println!("{change}"));
+            let breaking = changes.iter().filter(|change| change.is_breaking()).count();
+            eprintln!("{} changes, {breaking} breaking", changes.len());
+        }
+        ReportFormat::Json => print_json(&changes)?,
+        ReportFormat::Sarif | ReportFormat::Junit => {
+            return Err(String::from(
+                "changes are reported in the human or JSON format",
+            ))
+        }
+    }
+    Ok(match fail_on {
+        FailOn::Never => false,
+        FailOn::Breaking => changes.iter().any(Change::is_breaking),
+        FailOn::Any => !changes.is_empty(),
+    })
+}
+
+fn convert(file: &Path, output: &Output) -> Outcome {
+    let content = read(file)?;
+    let document = match serde_yaml::from_str::<Value>(&content) {
+        Ok(document) => document,
+        Err(err) => {
+            eprintln!("{}: {err}", file.display());
+            return Ok(true);
+        }
+    };
+    match asyncapiv3::convert::v2_to_v3(&document) {
+        Ok(document) => write_spec(file, &document, output),
+        Err(err) => {
+            eprintln!("{}: {err}", file.display());
+            Ok(true)
+        }
+    }
+}
+
+/// Fails for the commands that print their errors without a report.
+fn human_only(command: &str, format: ReportFormat) -> Result<(), String> {
+    match format {
+        ReportFormat::Human => Ok(()),
+        _ => Err(format!(
+            "{command} reports its errors in the human format only"
+        )),
+    }
+}
+
+/// Loads an AsyncAPI 3 specification, or adds its problem to the report.
+fn load_v3(file: &Path, report: &mut Report) -> Result<Option<AsyncApiV3Spec>, String> {
+    match loader::load_file(file) {
+        Ok(loaded) => {
+            let AsyncApiSpec::V3_0_0(spec) = loaded.spec;
+            Ok(Some(spec))
+        }
+        Err(err) => {
+            add_load_error(report, file, err)?;
+            Ok(None)
+        }
+    }
+}
+
+/// Adds the error of a specification that cannot be loaded, or fails when the file cannot be
+/// read.
+fn add_load_error(report: &mut Report, file: &Path, err: loader::Error) -> Result<(), String> {
+    match err {
+        loader::Error::Io { .. } => Err(err.to_string()),
+        err => {
+            report.add_load_error(file, &err);
+            Ok(())
+        }
+    }
+}
+
+/// Prints the findings of the report, returning whether there are errors.
+fn print_report(report: &Report, format: ReportFormat) -> Outcome {
+    match format {
+        ReportFormat::Human => {
+            for finding in report.findings() {
+                let mut location = finding.file.display().to_string();
+                if let Some(position) = finding.position {
+                    location.push_str(&format!(":{position}"));
+                }
+                let pointer = match finding.pointer.as_str() {
+                    "" => String::new(),
+                    pointer => format!("{pointer}: "),
+                };
+                println!(
+                    "{location}: {}[{}]: {pointer}{}",
+                    finding.severity, finding.rule, finding.message
+                );
+            }
+            eprintln!(
+                "{} errors, {} warnings in {} files",
+                report.count(Severity::Error),
+                report.count(Severity::Warning),
+                report.files().len()
+            );
+        }
+        ReportFormat::Json => print_json(&report.findings())?,
+        ReportFormat::Sarif => print_json(&report.to_sarif())?,
+        ReportFormat::Junit => print!("{}", report.to_junit()),
+    }
+    Ok(report.count(Severity::Error) > 0)
+}
+
+fn print_json(value: &impl serde::Serialize) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value).map_err(|err| err.to_string())?;
+    println!("{json}");
+    Ok(())
+}
+
+fn read(file: &Path) -> Result<String, String> {
+    std::fs::read_to_string(file).map_err(|err| format!("cannot read {}: {err}", file.display()))
+}
+
+/// Writes a specification read from `input`, if it is a valid AsyncAPI 3 specification.
+fn write_spec(input: &Path, document: &Value, output: &Output) -> Outcome {
+    if let Err(err) = serde_json::from_value::<AsyncApiSpec>(document.clone()) {
+        eprintln!("{}: invalid specification: {err}", input.display());
+        return Ok(true);
+    }
+    let format = output
+        .to
+        .or_else(|| {
+            let path = output.output.as_deref().unwrap_or(input);
+            match Format::from_extension(path)? {
+                Format::Json => Some(DocumentFormat::Json),
+                Format::Yaml => Some(DocumentFormat::Yaml),
+            }
+        })
+        .unwrap_or(DocumentFormat::Yaml);
+    let content = match format {
+        DocumentFormat::Json => serde_json::to_string_pretty(document)
+            .map(|json| json + "\n")
+            .map_err(|err| err.to_string())?,
+        DocumentFormat::Yaml => serde_yaml::to_string(document).map_err(|err| err.to_string())?,
+    };
+    match &output.output {
+        Some(path) => std::fs::write(path, content)
+            .map_err(|err| format!("cannot write {}: {err}", path.display()))?,
+        None => print!("{content}"),
+    }
+    Ok(false)
+}
@@ -0,0 +1,289 @@
+//! Fluent builder of the [`Channel`] object, and registration of channel parameters and servers.
+use crate::builder::AsyncApiV3Builder;
+use crate::error::Error;
+use crate::spec::channel::{Channel, ChannelBindings, Parameter, Parameters};
+use crate::spec::common::{ExternalDocumentation, RefOr, ReferenceObject, Tag};
+use crate::spec::message::{Message, Messages};
+
+/// Builder of [`Channel`]. A channel has no required field.
+#[derive(Clone, Debug, Default)]
+pub struct ChannelBuilder {
+    address: Option<String>,
+    messages: Messages,
+    title: Option<String>,
+    summary: Option<String>,
+    description: Option<String>,
+    servers: Vec<ReferenceObject>,
+    parameters: Parameters,
+    tags: Vec<Tag>,
+    external_docs: Option<RefOr<ExternalDocumentation>>,
+    bindings: Option<RefOr<ChannelBindings>>,
+}
+
+impl Channel {
+    pub fn builder() -> ChannelBuilder {
+        ChannelBuilder::new()
+    }
+}
+
+impl ChannelBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn address(mut self, address: impl Into<String>) -> Self {
+        self.address = Some(address.into());
+        self
+    }
+
+    pub fn message(mut self, name: impl Into<String>, message: Message) -> Self {
+        self.messages.insert(name.into(), RefOr::Right(message));
+        self
+    }
+
+    pub fn message_ref(mut self, name: impl Into<String>, reference: ReferenceObject) -> Self {
+        self.messages.insert(name.into(), RefOr::Left(reference));
+        self
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn summary(mut self, summary: impl Into<String>) -> Self {
+        self.summary = Some(summary.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Restricts the channel to a server, referenced by `#/servers/{name}`.
+    pub fn server(mut self, reference: ReferenceObject) -> Self {
+        self.servers.push(reference);
+        self
+    }
+
+    /// Adds a parameter used in the address of the channel.
+    pub fn parameter(mut self, name: impl Into<String>, parameter: Parameter) -> Self {
+        self.parameters.insert(name.into(), RefOr::Right(parameter));
+        self
+    }
+
+    pub fn parameter_ref(mut self, name: impl Into<String>, reference: ReferenceObject) -> Self {
+        self.parameters.insert(name.into(), RefOr::Left(reference));
+        self
+    }
+
+    pub fn tag(mut self, tag: Tag) -> Self {
+        self.tags.push(tag);
+        self
+    }
+
+    pub fn external_docs(mut self, external_docs: ExternalDocumentation) -> Self {
+        self.external_docs = Some(RefOr::Right(external_docs));
+        self
+    }
+
+    pub fn external_docs_ref(mut self, reference: ReferenceObject) -> Self {
+        self.external_docs = Some(RefOr::Left(reference));
+        self
+    }
+
+    pub fn bindings(mut self, bindings: ChannelBindings) -> Self {
+        self.bindings = Some(RefOr::Right(bindings));
+        self
+    }
+
+    pub fn bindings_ref(mut self, reference: ReferenceObject) -> Self {
+        self.bindings = Some(RefOr::Left(reference));
+        self
+    }
+
+    pub fn build(self) -> Channel {
+        Channel {
+            address: self.address,
+            messages: self.messages,
+            title: self.title,
+            summary: self.summary,
+            description: self.description,
+            servers: self.servers,
+            parameters: self.parameters,
+            tags: self.tags,
+            external_docs: self.external_docs,
+            bindings: self.bindings,
+        }
+    }
+}
+
+impl AsyncApiV3Builder {
+    /// Registers a parameter in the components, to be referenced by channels with
+    /// [`register_channel_parameter_ref`](Self::register_channel_parameter_ref).
+    pub fn register_parameter(&mut self, name: &str, parameter: Parameter) {
+        self.spec
+            .components
+            .parameters
+            .insert(String::from(name), RefOr::Right(parameter));
+    }
+
+    /// Adds a parameter to the channel `channel_name`, whose address must contain `{name}`.
+    pub fn register_channel_parameter(
+        &mut self,
+        channel_name: &str,
+        name: &str,
+        parameter: Parameter,
+    ) -> Result<(), Error> {
+        self.channel_with_parameter(channel_name, name)?
+            .parameters
+            .insert(String::from(name), RefOr::Right(parameter));
+        Ok(())
+    }
+
+    /// Adds a parameter of the components to the channel `channel_name`, whose address must
+    /// contain `{name}`.
+    pub fn register_channel_parameter_ref(
+        &mut self,
+        channel_name: &str,
+        name: &str,
+        component_name: &str,
+    ) -> Result<(), Error> {
+        if !self.spec.components.parameters.contains_key(component_name) {
+            return Err(Error::ParameterNotFound {
+                name: String::from(component_name),
+            });
+        }
+        self.channel_with_parameter(channel_name, name)?
+            .parameters
+            .insert(
+                String::from(name),
+                RefOr::Left(ReferenceObject::new_parameter(component_name)),
+            );
+        Ok(())
+    }
+
+    /// Restricts the channel `channel_name` to the server `server_name`. Channels without servers
+    /// are available on all servers.
+    pub fn register_channel_server(
+        &mut self,
+        channel_name: &str,
+        server_name: &str,
+    ) -> Result<(), Error> {
+        if !self.spec.servers.contains_key(server_name) {
+            return Err(Error::ServerNotFound {
+                name: String::from(server_name),
+            });
+        }
+        let channel = self.channel_mut(channel_name)?;
+        let reference = ReferenceObject::new_server(server_name);
+        if !channel.servers.contains(&reference) {
+            channel.servers.push(reference);
+        }
+        Ok(())
+    }
+
+    fn channel_with_parameter(
+        &mut self,
+        channel_name: &str,
+        name: &str,
+    ) -> Result<&mut Channel, Error> {
+        let channel = self.channel_mut(channel_name)?;
+        let expression = format!("{{{name}}}");
+        if !channel
+            .address
+            .as_ref()
+            .is_some_and(|address| address.contains(&expression))
+        {
+            return Err(Error::ParameterNotInAddress {
+                channel: String::from(channel_name),
+                name: String::from(name),
+            });
+        }
+        Ok(channel)
+    }
+
+    fn channel_mut(&mut self, channel_name: &str) -> Result<&mut Channel, Error> {
+        match self.spec.channels.get_mut(channel_name) {
+            Some(RefOr::Right(channel)) => Ok(channel),
+            _ => Err(Error::ChannelNotFound {
+                name: String::from(channel_name),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::builder::fixtures;
+    use crate::spec::server::Server;
+    use crate::spec::AsyncApiSpec;
+
+    fn parameter(description: &str) -> Parameter {
+        Parameter {
+            enum_values: None,
+            default: None,
+            description: Some(String::from(description)),
+            examples: vec![],
+            location: None,
+        }
+    }
+
+    #[test]
+    fn wire_parameters_and_servers() {
+        let mut builder = fixtures::builder();
+        let server = Server::builder()
+            .host("test.mosquitto.org")
+            .protocol("mqtt")
+            .build()
+            .unwrap();
+        builder.register_server("production", server);
+        let channel = Channel::builder()
+            .address("lights/{streetlightId}/{zone}")
+            .build();
+        builder.register_channel("lightMeasured", channel);
+
+        builder
+            .register_channel_parameter("lightMeasured", "streetlightId", parameter("Light"))
+            .unwrap();
+        assert!(matches!(
+            builder.register_channel_parameter("lightMeasured", "lightId", parameter("Light")),
+            Err(Error::ParameterNotInAddress { .. })
+        ));
+        assert!(matches!(
+            builder.register_channel_parameter_ref("lightMeasured", "zone", "zone"),
+            Err(Error::ParameterNotFound { .. })
+        ));
+        builder.register_parameter("zone", parameter("Zone"));
+        builder
+            .register_channel_parameter_ref("lightMeasured", "zone", "zone")
+            .unwrap();
+        builder
+            .register_channel_server("lightMeasured", "production")
+            .unwrap();
+        assert!(matches!(
+            builder.register_channel_server("lightMeasured", "staging"),
+            Err(Error::ServerNotFound { .. })
+        ));
+        assert!(matches!(
+            builder.register_channel_server("lightsDimmed", "production"),
+            Err(Error::ChannelNotFound { .. })
+        ));
+
+        let AsyncApiSpec::V3_0_0(spec) = builder.build().spec;
+        let Some(RefOr::Right(channel)) = spec.channels.get("lightMeasured") else {
+            panic!("missing channel");
+        };
+        assert_eq!(channel.servers, [ReferenceObject::new_server("production")]);
+        assert_eq!(
+            channel.parameters.get("streetlightId"),
+            Some(&RefOr::Right(parameter("Light")))
+        );
+        assert_eq!(
+            channel.parameters.get("zone"),
+            Some(&RefOr::Left(ReferenceObject::new_parameter("zone")))
+        );
+    }
+}
@@ -0,0 +1,276 @@
+//! Description of messages and operations from Rust types, used by the `AsyncApiMessage` derive
+//! macro and the `asyncapi_operation` attribute macro (behind the `derive` feature).
+use indexmap::IndexMap;
+
+use schemars::{JsonSchema, Schema};
+
+use crate::builder::operation::MessageFullSpec;
+use crate::builder::AsyncApiV3Builder;
+use crate::error::Error;
+use crate::spec::channel::Channel;
+use crate::spec::common::{Either, RefOr, ReferenceObject};
+use crate::spec::message::Message;
+use crate::spec::operation::{Operation, OperationAction, OperationReply};
+
+#[cfg(feature = "derive")]
+#[cfg_attr(docsrs, doc(cfg(feature = "derive")))]
+pub use asyncapiv3_derive::{asyncapi_operation, AsyncApiMessage};
+
+/// A Rust type describing the payload of a message.
+pub trait AsyncApiMessage {
+    /// Key of the message in the messages of its channels.
+    fn message_name() -> String;
+
+    /// Message, with its payload and headers schemas and the definitions they reference.
+    fn message_spec() -> MessageFullSpec;
+}
+
+/// Message fields that cannot be derived from the payload type.
+#[derive(Clone, Debug, Default)]
+pub struct MessageMetadata {
+    pub name: Option<String>,
+    pub title: Option<String>,
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub content_type: Option<String>,
+    /// Example payloads.
+    pub examples: Vec<serde_json::Value>,
+}
+
+impl MessageFullSpec {
+    /// Message whose payload is the schema of `T`. The definitions referenced by the schema are
+    /// collected to be registered as component schemas.
+    pub fn for_type<T: JsonSchema>(metadata: MessageMetadata) -> Self {
+        let (payload, definitions) = schema_with_definitions::<T>();
+        let mut message = Message::builder().payload(payload);
+        for example in metadata.examples {
+            message = message.example_payload(example);
+        }
+        Self {
+            message: Message {
+                content_type: metadata.content_type,
+                name: metadata.name,
+                title: metadata.title,
+                summary: metadata.summary,
+                description: metadata.description,
+                ..message.build()
+            },
+            definitions,
+        }
+    }
+
+    /// Sets the headers of the message to the schema of `H`.
+    pub fn with_headers<H: JsonSchema>(mut self) -> Self {
+        let (headers, definitions) = schema_with_definitions::<H>();
+        self.message.headers = Some(RefOr::Right(Either::Left(headers)));
+        self.definitions.extend(definitions);
+        self
+    }
+}
+
+fn schema_with_definitions<T: JsonSchema>() -> (Schema, IndexMap<String, Schema>) {
+    let mut settings = schemars::generate::SchemaSettings::default();
+    settings.definitions_path = "#/components/schemas/".into();
+    let mut generator = schemars::generate::SchemaGenerator::new(settings);
+    let mut schema = generator.subschema_for::<T>();
+    // the root type is inlined, unless it is recursive and then referenced by the definitions
+    let mut inlined = None;
+    if let Some(reference) = schema.get("$ref").and_then(|reference| reference.as_str()) {
+        let key = String::from(reference.trim_start_matches("#/components/schemas/"));
+        let definitions = generator.definitions();
+        if let Some(definition) = definitions
+            .get(&key)
+            .filter(|_| !is_referenced(&key, definitions))
+        {
+            if let Ok(definition) = Schema::try_from(definition.clone()) {
+                schema = definition;
+                inlined = Some(key);
+            }
+        }
+    }
+    let definitions = generator
+        .take_definitions(false)
+        .into_iter()
+        .filter(|(key, _)| Some(key) != inlined.as_ref())
+        .filter_map(|(key, value)| Some((key, Schema::try_from(value).ok()?)))
+        .collect::<IndexMap<_, _>>();
+    (schema, definitions)
+}
+
+/// Whether any of the definitions, including the one of `key` itself, references `key`.
+fn is_referenced(key: &str, definitions: &serde_json::Map<String, serde_json::Value>) -> bool {
+    let reference = format!("\"#/components/schemas/{key}\"");
+    definitions
+        .values()
+        .any(|definition| definition.to_string().contains(&reference))
+}
+
+/// Operation described by a handler annotated with `asyncapi_operation`.
+pub struct OperationDescriptor {
+    /// Key of the operation.
+    pub name: String,
+    pub action: OperationAction,
+    /// Key of the channel the operation is performed on, created if missing.
+    pub channel: String,
+    /// Address of the channel.
+    pub address: Option<String>,
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub message_name: String,
+    pub message: MessageFullSpec,
+    pub reply: Option<ReplyDescriptor>,
+}
+
+/// Reply of a request/reply operation.
+pub struct ReplyDescriptor {
+    /// Key of the channel of the reply, created if missing.
+    pub channel: String,
+    /// Address of the reply channel.
+    pub address: Option<String>,
+    pub message_name: String,
+    pub message: MessageFullSpec,
+}
+
+impl AsyncApiV3Builder {
+    /// Registers the message described by `M` in the channel `channel_name`, that must already
+    /// be registered, and its schemas in the components. Fails if the channel already has a
+    /// different message with the same key.
+    pub fn register_message<M: AsyncApiMessage>(
+        &mut self,
+        channel_name: &str,
+    ) -> Result<ReferenceObject, Error> {
+        self.register_channel_message(channel_name, M::message_name(), M::message_spec())
+    }
+
+    /// Registers an operation described by a handler, together with its channels, messages and
+    /// schemas.
+    pub fn register_operation_descriptor(
+        &mut self,
+        operation: OperationDescriptor,
+    ) -> Result<(), Error> {
+        let name = operation.name.clone();
+        self.register_in_operation(&name, |builder| {
+            builder.ensure_channel(&operation.channel, operation.address)?;
+            let message = builder.register_channel_message(
+                &operation.channel,
+                operation.message_name,
+                operation.message,
+            )?;
+            let reply = match operation.reply {
+                Some(reply) => {
+                    builder.ensure_channel(&reply.channel, reply.address)?;
+                    let message = builder.register_channel_message(
+                        &reply.channel,
+                        reply.message_name,
+                        reply.message,
+                    )?;
+                    Some(RefOr::Right(OperationReply {
+                        address: None,
+                        channel: Some(ReferenceObject::new_channel(&reply.channel)),
+                        messages: vec![message],
+                    }))
+                }
+                None => None,
+            };
+
+            builder.spec.operations.insert(
+                operation.name,
+                RefOr::Right(Operation {
+                    summary: operation.summary,
+                    description: operation.description,
+                    reply,
+                    ..Operation::builder()
+                        .action(operation.action)
+                        .channel(ReferenceObject::new_channel(&operation.channel))
+                        .message(message)
+                        .build()?
+                }),
+            );
+            Ok(())
+        })
+    }
+
+    pub(super) fn ensure_channel(
+        &mut self,
+        name: &str,
+        address: Option<String>,
+    ) -> Result<(), Error> {
+        match self.spec.channels.get_mut(name) {
+            Some(RefOr::Right(channel)) => match (&channel.address, address) {
+                (Some(existing), Some(address)) if *existing != address => {
+                    Err(Error::ChannelAddressMismatch {
+                        name: String::from(name),
+                        existing: existing.clone(),
+                        conflicting: address,
+                    })
+                }
+                (None, address) => {
+                    channel.address = address;
+                    Ok(())
+                }
+                (Some(_), _) => Ok(()),
+            },
+            Some(RefOr::Left(_)) => Err(Error::ChannelIsReference {
+                name: String::from(name),
+            }),
+            None => {
+                self.register_channel(
+                    name,
+                    Channel {
+                        address,
+                        ..Channel::builder().build()
+                    },
+                );
+                Ok(())
+            }
+        }
+    }
+
+    pub(super) fn register_channel_message(
+        &mut self,
+        channel_name: &str,
+        message_name: String,
+        message: MessageFullSpec,
+    ) -> Result<ReferenceObject, Error> {
+        let channel = match self.spec.channels.get(channel_name) {
+            Some(RefOr::Right(channel)) => channel,
+            Some(RefOr::Left(_)) => {
+                return Err(Error::ChannelIsReference {
+                    name: String::from(channel_name),
+                })
+            }
+            None => {
+                return Err(Error::ChannelNotFound {
+                    name: String::from(channel_name),
+                })
+            }
+        };
+        let wrapped = RefOr::Right(message.message);
+        if let Some(existing) = channel.messages.get(&message_name) {
+            if *existing != wrapped {
+                return Err(Error::DuplicateMessage {
+                    channel: String::from(channel_name),
+                    name: message_name,
+                    existing: Box::new(serde_json::to_value(existing).unwrap_or_default()),
+                    conflicting: Box::new(serde_json::to_value(wrapped).unwrap_or_default()),
+                });
+            }
+        }
+        self.merge_schema_components(message.definitions)?;
+        let reference = ReferenceObject::new_channel_message(channel_name, &message_name);
+        if let Some(RefOr::Right(channel)) = self.spec.channels.get_mut(channel_name) {
+            channel.messages.insert(message_name, wrapped);
+        }
+        Ok(reference)
+    }
+}
+
+/// Parses a JSON example. Used by the `AsyncApiMessage` derive macro, that validates examples at
+/// compile time.
+///
+/// # Panics
+///
+/// Panics if `json` is not valid JSON.
+pub fn example_from_json(json: &str) -> serde_json::Value {
+    serde_json::from_str(json).expect("invalid JSON example")
+}
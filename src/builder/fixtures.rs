@@ -0,0 +1,33 @@
+//! Fixtures shared by the tests of the builder.
+use indexmap::IndexMap;
+use schemars::Schema;
+
+use crate::builder::operation::MessageFullSpec;
+use crate::builder::AsyncApiV3Builder;
+use crate::spec::info::Info;
+use crate::spec::message::Message;
+use crate::spec::security::SecurityScheme;
+
+/// Builder of a specification without any channel or operation.
+pub(super) fn builder() -> AsyncApiV3Builder {
+    AsyncApiV3Builder::new(
+        Info::builder()
+            .title("TEST")
+            .version("1.0.0")
+            .build()
+            .unwrap(),
+    )
+}
+
+/// Message whose payload is `schema`, without definitions.
+pub(super) fn message_for(schema: Schema) -> MessageFullSpec {
+    MessageFullSpec {
+        message: Message::builder().payload(schema).build(),
+        definitions: IndexMap::new(),
+    }
+}
+
+/// `userPassword` security scheme.
+pub(super) fn user_password_scheme() -> SecurityScheme {
+    serde_json::from_value(serde_json::json!({ "userPassword": {} })).unwrap()
+}
@@ -0,0 +1,292 @@
+//! Moves objects defined identically in several places of a specification into its components.
+use indexmap::IndexMap;
+
+use crate::spec::channel::{Channel, Channels};
+use crate::spec::common::{Either, RefOr, ReferenceObject};
+use crate::spec::message::{Message, Messages};
+use crate::spec::AsyncApiV3Spec;
+
+/// Inline object of the specification that may be replaced by a reference.
+struct Slot<'a, T> {
+    /// Location of the object, used to process objects in a deterministic order.
+    path: String,
+    /// Name of the component, when the object is moved to the components.
+    hint: String,
+    value: &'a mut RefOr<T>,
+}
+
+/// Replaces the messages, parameters, correlation IDs and bindings that are defined identically
+/// in several places, or that are identical to a component, by references to components.
+pub(super) fn hoist_components(spec: &mut AsyncApiV3Spec) {
+    let components = &mut spec.components;
+
+    let slots = channels(&mut spec.channels)
+        .flat_map(|(channel_name, channel)| {
+            channel.messages.iter_mut().map(move |(name, value)| Slot {
+                path: format!("channels/{channel_name}/messages/{name}"),
+                hint: String::from(name),
+                value,
+            })
+        })
+        .collect();
+    hoist(
+        slots,
+        &mut components.messages,
+        ReferenceObject::new_message,
+    );
+
+    let slots = channels(&mut spec.channels)
+        .flat_map(|(channel_name, channel)| {
+            channel
+                .parameters
+                .iter_mut()
+                .map(move |(name, value)| Slot {
+                    path: format!("channels/{channel_name}/parameters/{name}"),
+                    hint: String::from(name),
+                    value,
+                })
+        })
+        .collect();
+    hoist(
+        slots,
+        &mut components.parameters,
+        ReferenceObject::new_parameter,
+    );
+
+    let slots = messages(&mut spec.channels, &mut components.messages)
+        .filter_map(|(path, name, message)| {
+            Some(Slot {
+                path: format!("{path}/correlationId"),
+                hint: String::from(name),
+                value: message.correlation_id.as_mut()?,
+            })
+        })
+        .collect();
+    hoist(slots, &mut components.correlation_ids, |name| {
+        ReferenceObject::new_component("correlationIds", name)
+    });
+
+    let slots = messages(&mut spec.channels, &mut components.messages)
+        .filter_map(|(path, name, message)| {
+            Some(Slot {
+                path: format!("{path}/bindings"),
+                hint: String::from(name),
+                value: message.bindings.as_mut()?,
+            })
+        })
+        .collect();
+    hoist(slots, &mut components.message_bindings, |name| {
+        ReferenceObject::new_component("messageBindings", name)
+    });
+
+    let slots = channels(&mut spec.channels)
+        .filter_map(|(name, channel)| {
+            Some(Slot {
+                path: format!("channels/{name}/bindings"),
+                hint: String::from(name),
+                value: channel.bindings.as_mut()?,
+            })
+        })
+        .collect();
+    hoist(slots, &mut components.channel_bindings, |name| {
+        ReferenceObject::new_component("channelBindings", name)
+    });
+
+    let slots = spec
+        .operations
+        .iter_mut()
+        .filter_map(|(name, operation)| {
+            let Either::Right(operation) = operation else {
+                return None;
+            };
+            Some(Slot {
+                path: format!("operations/{name}/bindings"),
+                hint: String::from(name),
+                value: operation.bindings.as_mut()?,
+            })
+        })
+        .collect();
+    hoist(slots, &mut components.operation_bindings, |name| {
+        ReferenceObject::new_component("operationBindings", name)
+    });
+
+    let slots = spec
+        .servers
+        .iter_mut()
+        .filter_map(|(name, server)| {
+            let Either::Right(server) = server else {
+                return None;
+            };
+            Some(Slot {
+                path: format!("servers/{name}/bindings"),
+                hint: String::from(name),
+                value: server.bindings.as_mut()?,
+            })
+        })
+        .collect();
+    hoist(slots, &mut components.server_bindings, |name| {
+        ReferenceObject::new_component("serverBindings", name)
+    });
+}
+
+/// Inline channels with their names.
+fn channels(channels: &mut Channels) -> impl Iterator<Item = (&String, &mut Channel)> {
+    channels
+        .iter_mut()
+        .filter_map(|(name, channel)| match channel {
+            Either::Left(_) => None,
+            Either::Right(channel) => Some((name, channel)),
+        })
+}
+
+/// Inline messages of the channels and of the components, with their parent path and name.
+fn messages<'a>(
+    inline_channels: &'a mut Channels,
+    components: &'a mut Messages,
+) -> impl Iterator<Item = (String, &'a String, &'a mut Message)> {
+    channels(inline_channels)
+        .flat_map(|(channel_name, channel)| {
+            let path = format!("channels/{channel_name}/messages");
+            channel
+                .messages
+                .iter_mut()
+                .map(move |(name, message)| (path.clone(), name, message))
+        })
+        .chain(
+            components
+                .iter_mut()
+                .map(|(name, message)| (String::from("components/messages"), name, message)),
+        )
+        .filter_map(|(path, name, message)| match message {
+            Either::Left(_) => None,
+            Either::Right(message) => Some((format!("{path}/{name}"), name, message)),
+        })
+}
+
+/// Moves the inline values of `slots` that are defined several times, or that are identical to
+/// a component, to the `components` and replaces them by references.
+fn hoist<T: Clone + PartialEq>(
+    mut slots: Vec<Slot<T>>,
+    components: &mut IndexMap<String, RefOr<T>>,
+    reference: impl Fn(&str) -> ReferenceObject,
+) {
+    slots.sort_by(|a, b| a.path.cmp(&b.path));
+    let mut groups: Vec<(T, Vec<usize>)> = vec![];
+    for (idx, slot) in slots.iter().enumerate() {
+        let Either::Right(value) = &*slot.value else {
+            continue;
+        };
+        match groups.iter_mut().find(|(group, _)| group == value) {
+            Some((_, indices)) => indices.push(idx),
+            None => groups.push((value.clone(), vec![idx])),
+        }
+    }
+
+    for (value, indices) in groups {
+        let existing = components
+            .iter()
+            .filter(|(_, component)| matches!(component, Either::Right(component) if *component == value))
+            .map(|(name, _)| name)
+            .min()
+            .cloned();
+        let name = match existing {
+            Some(name) => name,
+            None if indices.len() > 1 => {
+                let name = unique_name(components, &slots[indices[0]].hint);
+                components.insert(name.clone(), RefOr::Right(value));
+                name
+            }
+            None => continue,
+        };
+        for idx in indices {
+            *slots[idx].value = RefOr::Left(reference(&name));
+        }
+    }
+}
+
+/// `hint`, suffixed by a number if a component already has this name.
+fn unique_name<T>(components: &IndexMap<String, T>, hint: &str) -> String {
+    if !components.contains_key(hint) {
+        return String::from(hint);
+    }
+    (2..)
+        .map(|idx| format!("{hint}{idx}"))
+        .find(|name| !components.contains_key(name))
+        .expect("unbounded range")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::builder::{fixtures, BuildOptions};
+    use crate::spec::channel::Parameter;
+    use crate::spec::message::CorrelationId;
+    use crate::spec::AsyncApiSpec;
+
+    #[test]
+    fn hoist_repeated_objects() {
+        let mut builder = fixtures::builder();
+        let correlation_id = CorrelationId {
+            description: None,
+            location: String::from("$message.header#/correlation_id"),
+        };
+        let message = Message::builder()
+            .payload(schemars::json_schema!({ "type": "number" }))
+            .correlation_id(correlation_id.clone())
+            .build();
+        let other = Message::builder()
+            .payload(schemars::json_schema!({ "type": "string" }))
+            .correlation_id(correlation_id)
+            .build();
+        let parameter = Parameter {
+            enum_values: None,
+            default: None,
+            description: Some(String::from("Identifier of the light")),
+            examples: vec![],
+            location: None,
+        };
+        for channel_name in ["lightOn", "lightOff"] {
+            let channel = Channel::builder()
+                .address(format!("lights/{{lightId}}/{channel_name}"))
+                .parameter("lightId", parameter.clone())
+                .message("measure", message.clone())
+                .build();
+            builder.register_channel(channel_name, channel);
+        }
+        builder.register_channel(
+            "lightName",
+            Channel::builder().message("name", other).build(),
+        );
+
+        let built = builder.build_with(BuildOptions {
+            hoist_components: true,
+        });
+        assert_eq!(built.warnings, []);
+        let AsyncApiSpec::V3_0_0(spec) = built.spec;
+        let components = &spec.components;
+        assert_eq!(components.messages.len(), 1);
+        assert_eq!(components.parameters.len(), 1);
+        assert_eq!(components.correlation_ids.len(), 1);
+        let Some(RefOr::Right(channel)) = spec.channels.get("lightOff") else {
+            panic!("missing channel");
+        };
+        assert_eq!(
+            channel.messages.get("measure"),
+            Some(&RefOr::Left(ReferenceObject::new_message("measure")))
+        );
+        assert_eq!(
+            channel.parameters.get("lightId"),
+            Some(&RefOr::Left(ReferenceObject::new_parameter("lightId")))
+        );
+        let Some(RefOr::Right(measure)) = components.messages.get("measure") else {
+            panic!("missing message");
+        };
+        assert_eq!(
+            measure.correlation_id,
+            Some(RefOr::Left(ReferenceObject::new_component(
+                "correlationIds",
+                "name"
+            )))
+        );
+    }
+}
@@ -0,0 +1,94 @@
+//! Fluent builder of the [`Info`] object.
+use crate::error::Error;
+use crate::spec::common::{ExternalDocumentation, RefOr, ReferenceObject, Tag};
+use crate::spec::info::{Contact, Info, License};
+
+/// Builder of [`Info`], whose title and version are required.
+#[derive(Clone, Debug, Default)]
+pub struct InfoBuilder {
+    title: Option<String>,
+    version: Option<String>,
+    description: Option<String>,
+    terms_of_service: Option<String>,
+    contact: Option<Contact>,
+    license: Option<License>,
+    tags: Vec<Tag>,
+    external_docs: Option<RefOr<ExternalDocumentation>>,
+}
+
+impl Info {
+    pub fn builder() -> InfoBuilder {
+        InfoBuilder::new()
+    }
+}
+
+impl InfoBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn terms_of_service(mut self, terms_of_service: impl Into<String>) -> Self {
+        self.terms_of_service = Some(terms_of_service.into());
+        self
+    }
+
+    pub fn contact(mut self, contact: Contact) -> Self {
+        self.contact = Some(contact);
+        self
+    }
+
+    pub fn license(mut self, license: License) -> Self {
+        self.license = Some(license);
+        self
+    }
+
+    pub fn tag(mut self, tag: Tag) -> Self {
+        self.tags.push(tag);
+        self
+    }
+
+    pub fn external_docs(mut self, external_docs: ExternalDocumentation) -> Self {
+        self.external_docs = Some(RefOr::Right(external_docs));
+        self
+    }
+
+    pub fn external_docs_ref(mut self, reference: ReferenceObject) -> Self {
+        self.external_docs = Some(RefOr::Left(reference));
+        self
+    }
+
+    /// Builds the info object, failing if its title or version is missing.
+    pub fn build(self) -> Result<Info, Error> {
+        Ok(Info {
+            title: self.title.ok_or(Error::MissingField {
+                object: "info",
+                field: "title",
+            })?,
+            version: self.version.ok_or(Error::MissingField {
+                object: "info",
+                field: "version",
+            })?,
+            description: self.description,
+            terms_of_service: self.terms_of_service,
+            contact: self.contact,
+            license: self.license,
+            tags: self.tags,
+            external_docs: self.external_docs,
+        })
+    }
+}
@@ -0,0 +1,174 @@
+//! Fluent builder of the [`Message`] object.
+use schemars::Schema;
+
+use crate::spec::common::{Either, ExternalDocumentation, RefOr, ReferenceObject, Tag};
+use crate::spec::message::{
+    CorrelationId, Message, MessageBindings, MessageExample, MessageTrait, MultiFormatSchema,
+};
+
+/// Builder of [`Message`]. A message has no required field.
+#[derive(Clone, Debug, Default)]
+pub struct MessageBuilder {
+    headers: Option<RefOr<Either<Schema, MultiFormatSchema>>>,
+    payload: Option<RefOr<Either<Schema, MultiFormatSchema>>>,
+    correlation_id: Option<RefOr<CorrelationId>>,
+    content_type: Option<String>,
+    name: Option<String>,
+    title: Option<String>,
+    summary: Option<String>,
+    description: Option<String>,
+    tags: Vec<Tag>,
+    external_docs: Option<RefOr<ExternalDocumentation>>,
+    bindings: Option<RefOr<MessageBindings>>,
+    examples: Vec<MessageExample>,
+    traits: Vec<RefOr<MessageTrait>>,
+}
+
+impl Message {
+    pub fn builder() -> MessageBuilder {
+        MessageBuilder::new()
+    }
+}
+
+impl MessageBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the JSON schema of the headers.
+    pub fn headers(mut self, headers: Schema) -> Self {
+        self.headers = Some(RefOr::Right(Either::Left(headers)));
+        self
+    }
+
+    /// Sets the headers schema in another format than JSON schema.
+    pub fn headers_multi_format(mut self, headers: MultiFormatSchema) -> Self {
+        self.headers = Some(RefOr::Right(Either::Right(headers)));
+        self
+    }
+
+    pub fn headers_ref(mut self, reference: ReferenceObject) -> Self {
+        self.headers = Some(RefOr::Left(reference));
+        self
+    }
+
+    /// Sets the JSON schema of the payload.
+    pub fn payload(mut self, payload: Schema) -> Self {
+        self.payload = Some(RefOr::Right(Either::Left(payload)));
+        self
+    }
+
+    /// Sets the payload schema in another format than JSON schema.
+    pub fn payload_multi_format(mut self, payload: MultiFormatSchema) -> Self {
+        self.payload = Some(RefOr::Right(Either::Right(payload)));
+        self
+    }
+
+    pub fn payload_ref(mut self, reference: ReferenceObject) -> Self {
+        self.payload = Some(RefOr::Left(reference));
+        self
+    }
+
+    pub fn correlation_id(mut self, correlation_id: CorrelationId) -> Self {
+        self.correlation_id = Some(RefOr::Right(correlation_id));
+        self
+    }
+
+    pub fn correlation_id_ref(mut self, reference: ReferenceObject) -> Self {
+        self.correlation_id = Some(RefOr::Left(reference));
+        self
+    }
+
+    pub fn content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn summary(mut self, summary: impl Into<String>) -> Self {
+        self.summary = Some(summary.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn tag(mut self, tag: Tag) -> Self {
+        self.tags.push(tag);
+        self
+    }
+
+    pub fn external_docs(mut self, external_docs: ExternalDocumentation) -> Self {
+        self.external_docs = Some(RefOr::Right(external_docs));
+        self
+    }
+
+    pub fn external_docs_ref(mut self, reference: ReferenceObject) -> Self {
+        self.external_docs = Some(RefOr::Left(reference));
+        self
+    }
+
+    pub fn bindings(mut self, bindings: MessageBindings) -> Self {
+        self.bindings = Some(RefOr::Right(bindings));
+        self
+    }
+
+    pub fn bindings_ref(mut self, reference: ReferenceObject) -> Self {
+        self.bindings = Some(RefOr::Left(reference));
+        self
+    }
+
+    pub fn example(mut self, example: MessageExample) -> Self {
+        self.examples.push(example);
+        self
+    }
+
+    /// Adds an example made of a payload only.
+    pub fn example_payload(self, payload: serde_json::Value) -> Self {
+        self.example(MessageExample {
+            headers: Default::default(),
+            payload: Some(payload),
+            name: None,
+            summary: None,
+        })
+    }
+
+    pub fn message_trait(mut self, message_trait: MessageTrait) -> Self {
+        self.traits.push(RefOr::Right(message_trait));
+        self
+    }
+
+    pub fn message_trait_ref(mut self, reference: ReferenceObject) -> Self {
+        self.traits.push(RefOr::Left(reference));
+        self
+    }
+
+    pub fn build(self) -> Message {
+        Message {
+            headers: self.headers,
+            payload: self.payload,
+            correlation_id: self.correlation_id,
+            content_type: self.content_type,
+            name: self.name,
+            title: self.title,
+            summary: self.summary,
+            description: self.description,
+            tags: self.tags,
+            external_docs: self.external_docs,
+            bindings: self.bindings,
+            examples: self.examples,
+            traits: self.traits,
+        }
+    }
+}
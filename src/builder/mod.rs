@@ -1,3 +1,4 @@
+use crate::error::Warning;
 use crate::spec::channel::{Channel, Channels};
 use crate::spec::common::{RefOr, ReferenceObject};
 use crate::spec::component::Components;
@@ -6,15 +7,56 @@ use crate::spec::operation::{Operation, Operations};
 use crate::spec::server::{Server, Servers};
 use crate::spec::{AsyncApiSpec, AsyncApiV3Spec};
 
+pub mod channel;
+pub mod describe;
+#[cfg(test)]
+mod fixtures;
+mod hoist;
+pub mod info;
+pub mod message;
 pub mod operation;
+pub mod security;
+pub mod server;
+mod warnings;
+
+pub use channel::ChannelBuilder;
+pub use info::InfoBuilder;
+pub use message::MessageBuilder;
+pub use operation::OperationBuilder;
+pub use server::ServerBuilder;
 
 pub struct AsyncApiV3Builder {
     spec: AsyncApiV3Spec,
 }
 
+/// Options of [`AsyncApiV3Builder::build_with`].
+#[derive(Clone, Debug, Default)]
+pub struct BuildOptions {
+    /// Moves the messages, parameters, correlation IDs and bindings defined identically in
+    /// several places to the components, and references them instead.
+    pub hoist_components: bool,
+}
+
+/// Specification built by [`AsyncApiV3Builder`], with the non-fatal issues found in it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BuiltSpec {
+    pub spec: AsyncApiSpec,
+    pub warnings: Vec<Warning>,
+}
+
 impl AsyncApiV3Builder {
-    pub fn build(self) -> AsyncApiSpec {
-        AsyncApiSpec::V3_0_0(self.spec)
+    pub fn build(self) -> BuiltSpec {
+        self.build_with(BuildOptions::default())
+    }
+
+    pub fn build_with(mut self, options: BuildOptions) -> BuiltSpec {
+        if options.hoist_components {
+            hoist::hoist_components(&mut self.spec);
+        }
+        BuiltSpec {
+            warnings: warnings::collect_warnings(&self.spec),
+            spec: AsyncApiSpec::V3_0_0(self.spec),
+        }
     }
 
     pub fn new(info: Info) -> Self {
@@ -1,18 +1,21 @@
-use std::collections::HashMap;
+use indexmap::IndexMap;
 
 use schemars::Schema;
 
 use crate::error::Error;
-use crate::spec::channel::Channel;
-use crate::spec::common::{Either, RefOr, ReferenceObject};
+use crate::spec::common::{Either, ExternalDocumentation, RefOr, ReferenceObject, Tag};
 use crate::spec::message::Message;
-use crate::spec::operation::{Operation, OperationAction, OperationReply, OperationReplyAddress};
+use crate::spec::operation::{
+    Operation, OperationAction, OperationBindings, OperationReply, OperationReplyAddress,
+    OperationTrait,
+};
+use crate::spec::security::SecurityScheme;
 
 use super::AsyncApiV3Builder;
 
 pub struct MessageFullSpec {
     pub message: Message,
-    pub definitions: HashMap<String, Schema>,
+    pub definitions: IndexMap<String, Schema>,
 }
 
 pub struct OperationInfo {
@@ -24,6 +27,31 @@ pub struct ReplyInfo {
     pub channel_name: String,
     pub reply_address_location: String,
 }
+
+/// Operation answering requests received on a channel, possibly shared with the requester.
+pub struct ResponderInfo {
+    pub name: String,
+    /// Key of the channel of the requests, created at `address` if missing.
+    pub channel_name: String,
+    pub address: Option<String>,
+}
+
+/// Destination of the replies of a responder.
+pub enum ReplyTarget {
+    /// Replies are sent on a channel, created at `address` if missing.
+    Channel {
+        channel_name: String,
+        address: Option<String>,
+    },
+    /// Replies are sent to an address read from the request at runtime, e.g.
+    /// `$message.header#/replyTo`. The reply message is registered on the channel `channel_name`,
+    /// created without address if missing.
+    Dynamic {
+        channel_name: String,
+        location: String,
+    },
+}
+
 impl AsyncApiV3Builder {
     pub fn register_simple_req_rep_operation(
         &mut self,
@@ -32,106 +60,182 @@ impl AsyncApiV3Builder {
         req: MessageFullSpec,
         res: MessageFullSpec,
     ) -> Result<(), Error> {
-        if self.spec.operations.contains_key(&operation.name) {
-            return Err(Error::DuplicateOperation {
-                name: String::from(&operation.name),
-            });
-        }
+        let name = String::from(&operation.name);
+        self.register_in_operation(&name, |builder| {
+            let req_name = message_name(&req.message, || format!("{}.req", operation.name));
+            let res_name = message_name(&res.message, || format!("{}.res", operation.name));
 
-        self.merge_schema_components(req.definitions)?;
-        self.merge_schema_components(res.definitions)?;
+            builder.ensure_channel(&operation.name, Some(String::from(&operation.address)))?;
+            builder.register_channel_message(&operation.name, req_name.clone(), req)?;
+            builder.register_channel_message(&reply_info.channel_name, res_name.clone(), res)?;
 
-        let req_name = if let Some(RefOr::Right(Either::Left(ref schema))) = req.message.payload {
-            schema
-                .as_object()
-                .and_then(|x| x.get("title"))
-                .map(ToString::to_string)
-        } else {
-            None
-        }
-        .unwrap_or_else(|| format!("{}.req", operation.name));
+            builder.spec.operations.insert(
+                String::from(&operation.name),
+                RefOr::Right(
+                    Operation::builder()
+                        .action(OperationAction::Send)
+                        .channel(ReferenceObject::new_channel(&operation.name))
+                        .message(ReferenceObject::new_channel_message(
+                            &operation.name,
+                            &req_name,
+                        ))
+                        .reply(OperationReply {
+                            channel: Some(ReferenceObject::new_channel(&reply_info.channel_name)),
+                            address: Some(RefOr::Right(OperationReplyAddress {
+                                description: None,
+                                location: reply_info.reply_address_location,
+                            })),
+                            messages: vec![ReferenceObject::new_channel_message(
+                                &reply_info.channel_name,
+                                &res_name,
+                            )],
+                        })
+                        .build()?,
+                ),
+            );
 
-        let res_name = if let Some(RefOr::Right(Either::Left(ref schema))) = res.message.payload {
-            schema
-                .as_object()
-                .and_then(|x| x.get("title"))
-                .map(ToString::to_string)
-        } else {
-            None
-        }
-        .unwrap_or_else(|| format!("{}.res", operation.name));
-
-        self.spec.channels.insert(
-            String::from(&operation.name),
-            RefOr::Right(Channel {
-                address: Some(String::from(&operation.address)),
-                messages: [(String::from(&req_name), RefOr::Right(req.message))]
-                    .into_iter()
-                    .collect(),
-                title: None,
-                summary: None,
-                description: None,
-                servers: vec![],
-                parameters: Default::default(),
-                tags: vec![],
-                external_docs: None,
-                bindings: None,
-            }),
-        );
+            Ok(())
+        })
+    }
+
+    /// Registers the responder side of a request/reply: an operation receiving `req` on the
+    /// channel of `operation` and replying with `res` to `reply`.
+    pub fn register_simple_responder_operation(
+        &mut self,
+        operation: ResponderInfo,
+        reply: ReplyTarget,
+        req: MessageFullSpec,
+        res: MessageFullSpec,
+    ) -> Result<(), Error> {
+        let name = String::from(&operation.name);
+        self.register_in_operation(&name, |builder| {
+            let req_name = message_name(&req.message, || format!("{}.req", operation.name));
+            let res_name = message_name(&res.message, || format!("{}.res", operation.name));
 
-        self.register_response(&reply_info.channel_name, &res_name, res.message)?;
+            builder.ensure_channel(&operation.channel_name, operation.address)?;
+            let request =
+                builder.register_channel_message(&operation.channel_name, req_name, req)?;
 
-        self.spec.operations.insert(
-            String::from(&operation.name),
-            RefOr::Right(Operation {
-                action: OperationAction::Send,
-                channel: ReferenceObject::new_channel(&operation.name),
-                title: None,
-                summary: None,
-                description: None,
-                security: vec![],
-                tags: vec![],
-                external_docs: None,
-                bindings: None,
-                traits: vec![],
-                messages: Some(vec![ReferenceObject::new_channel_message(
-                    &operation.name,
-                    &req_name,
-                )]),
-                reply: Some(RefOr::Right(OperationReply {
-                    channel: Some(ReferenceObject::new_channel(&reply_info.channel_name)),
-                    address: Some(RefOr::Right(OperationReplyAddress {
+            let (reply_channel, reply_address) = match reply {
+                ReplyTarget::Channel {
+                    channel_name,
+                    address,
+                } => {
+                    builder.ensure_channel(&channel_name, address)?;
+                    (channel_name, None)
+                }
+                ReplyTarget::Dynamic {
+                    channel_name,
+                    location,
+                } => {
+                    builder.ensure_channel(&channel_name, None)?;
+                    let address = OperationReplyAddress {
                         description: None,
-                        location: reply_info.reply_address_location,
-                    })),
-                    messages: vec![ReferenceObject::new_channel_message(
-                        &reply_info.channel_name,
-                        &res_name,
-                    )],
-                })),
-            }),
-        );
+                        location,
+                    };
+                    (channel_name, Some(RefOr::Right(address)))
+                }
+            };
+            let response = builder.register_channel_message(&reply_channel, res_name, res)?;
 
-        Ok(())
+            let operation_spec = Operation::builder()
+                .action(OperationAction::Receive)
+                .channel(ReferenceObject::new_channel(&operation.channel_name))
+                .message(request)
+                .reply(OperationReply {
+                    address: reply_address,
+                    channel: Some(ReferenceObject::new_channel(&reply_channel)),
+                    messages: vec![response],
+                })
+                .build()?;
+            builder
+                .spec
+                .operations
+                .insert(operation.name, RefOr::Right(operation_spec));
+            Ok(())
+        })
+    }
+
+    /// Registers a one-way operation sending `messages` on the channel named after the operation,
+    /// created at `operation.address` if missing.
+    pub fn register_send_operation(
+        &mut self,
+        operation: OperationInfo,
+        messages: Vec<MessageFullSpec>,
+    ) -> Result<(), Error> {
+        self.register_one_way_operation(OperationAction::Send, operation, messages)
+    }
+
+    /// Registers a one-way operation receiving `messages` on the channel named after the
+    /// operation, created at `operation.address` if missing.
+    pub fn register_receive_operation(
+        &mut self,
+        operation: OperationInfo,
+        messages: Vec<MessageFullSpec>,
+    ) -> Result<(), Error> {
+        self.register_one_way_operation(OperationAction::Receive, operation, messages)
+    }
+
+    fn register_one_way_operation(
+        &mut self,
+        action: OperationAction,
+        operation: OperationInfo,
+        messages: Vec<MessageFullSpec>,
+    ) -> Result<(), Error> {
+        let name = String::from(&operation.name);
+        self.register_in_operation(&name, |builder| {
+            builder.ensure_channel(&operation.name, Some(operation.address))?;
+            let count = messages.len();
+            let mut operation_spec = Operation::builder()
+                .action(action)
+                .channel(ReferenceObject::new_channel(&operation.name));
+            for (idx, message) in messages.into_iter().enumerate() {
+                let name = message_name(&message.message, || match count {
+                    1 => format!("{}.msg", operation.name),
+                    _ => format!("{}.msg{idx}", operation.name),
+                });
+                operation_spec = operation_spec.message(builder.register_channel_message(
+                    &operation.name,
+                    name,
+                    message,
+                )?);
+            }
+
+            builder
+                .spec
+                .operations
+                .insert(operation.name, RefOr::Right(operation_spec.build()?));
+            Ok(())
+        })
     }
 
-    fn register_response(
+    /// Runs `register` unless the operation `name` is already registered, and adds the name of
+    /// the operation to its errors. On error, the specification is restored to its state before
+    /// the registration.
+    pub(super) fn register_in_operation(
         &mut self,
-        reply_channel_name: &str,
-        res_name: &str,
-        message: Message,
+        name: &str,
+        register: impl FnOnce(&mut Self) -> Result<(), Error>,
     ) -> Result<(), Error> {
-        let Some(RefOr::Right(channel)) = self.spec.channels.get_mut(reply_channel_name) else {
-            return Err(Error::ChannelNotFound {
-                name: String::from(reply_channel_name),
+        if self.spec.operations.contains_key(name) {
+            return Err(Error::DuplicateOperation {
+                name: String::from(name),
             });
-        };
-        channel
-            .messages
-            .insert(String::from(res_name), RefOr::Right(message));
-        Ok(())
+        }
+        let snapshot = self.spec.clone();
+        register(self).map_err(|source| {
+            self.spec = snapshot;
+            Error::InOperation {
+                name: String::from(name),
+                source: Box::new(source),
+            }
+        })
     }
-    fn merge_schema_components(&mut self, partial: HashMap<String, Schema>) -> Result<(), Error> {
+
+    pub(super) fn merge_schema_components(
+        &mut self,
+        partial: IndexMap<String, Schema>,
+    ) -> Result<(), Error> {
         for (key, schema) in partial {
             self.insert_schema_component(key, schema)?;
         }
@@ -142,8 +246,11 @@ impl AsyncApiV3Builder {
         let wrapped = RefOr::Right(Either::Left(schema));
         if let Some(current_schema) = self.spec.components.schemas.get(&key) {
             if !current_schema.eq(&wrapped) {
-                log::warn!("Trying to add two schema components with the same name but different content {current_schema:?} - {wrapped:?}");
-                return Err(Error::DuplicateDefinition { key });
+                return Err(Error::DuplicateDefinition {
+                    key,
+                    existing: Box::new(serde_json::to_value(current_schema).unwrap_or_default()),
+                    conflicting: Box::new(serde_json::to_value(wrapped).unwrap_or_default()),
+                });
             }
         } else {
             self.spec.components.schemas.insert(key, wrapped);
@@ -152,23 +259,184 @@ impl AsyncApiV3Builder {
     }
 }
 
+/// Key of a message in its channel: the title of its payload schema, or `fallback`.
+fn message_name(message: &Message, fallback: impl FnOnce() -> String) -> String {
+    if let Some(RefOr::Right(Either::Left(ref schema))) = message.payload {
+        schema
+            .as_object()
+            .and_then(|x| x.get("title"))
+            .and_then(serde_json::Value::as_str)
+            .map(String::from)
+    } else {
+        None
+    }
+    .unwrap_or_else(fallback)
+}
+
+/// Builder of [`Operation`], whose action and channel are required.
+#[derive(Clone, Debug, Default)]
+pub struct OperationBuilder {
+    action: Option<OperationAction>,
+    channel: Option<ReferenceObject>,
+    title: Option<String>,
+    summary: Option<String>,
+    description: Option<String>,
+    security: Vec<RefOr<SecurityScheme>>,
+    tags: Vec<Tag>,
+    external_docs: Option<RefOr<ExternalDocumentation>>,
+    bindings: Option<RefOr<OperationBindings>>,
+    traits: Vec<RefOr<OperationTrait>>,
+    messages: Option<Vec<ReferenceObject>>,
+    reply: Option<RefOr<OperationReply>>,
+}
+
+impl Operation {
+    pub fn builder() -> OperationBuilder {
+        OperationBuilder::new()
+    }
+}
+
+impl OperationBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn action(mut self, action: OperationAction) -> Self {
+        self.action = Some(action);
+        self
+    }
+
+    /// Sets the channel of the operation, referenced by `#/channels/{name}`.
+    pub fn channel(mut self, reference: ReferenceObject) -> Self {
+        self.channel = Some(reference);
+        self
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn summary(mut self, summary: impl Into<String>) -> Self {
+        self.summary = Some(summary.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn security(mut self, security: SecurityScheme) -> Self {
+        self.security.push(RefOr::Right(security));
+        self
+    }
+
+    pub fn security_ref(mut self, reference: ReferenceObject) -> Self {
+        self.security.push(RefOr::Left(reference));
+        self
+    }
+
+    pub fn tag(mut self, tag: Tag) -> Self {
+        self.tags.push(tag);
+        self
+    }
+
+    pub fn external_docs(mut self, external_docs: ExternalDocumentation) -> Self {
+        self.external_docs = Some(RefOr::Right(external_docs));
+        self
+    }
+
+    pub fn external_docs_ref(mut self, reference: ReferenceObject) -> Self {
+        self.external_docs = Some(RefOr::Left(reference));
+        self
+    }
+
+    pub fn bindings(mut self, bindings: OperationBindings) -> Self {
+        self.bindings = Some(RefOr::Right(bindings));
+        self
+    }
+
+    pub fn bindings_ref(mut self, reference: ReferenceObject) -> Self {
+        self.bindings = Some(RefOr::Left(reference));
+        self
+    }
+
+    pub fn operation_trait(mut self, operation_trait: OperationTrait) -> Self {
+        self.traits.push(RefOr::Right(operation_trait));
+        self
+    }
+
+    pub fn operation_trait_ref(mut self, reference: ReferenceObject) -> Self {
+        self.traits.push(RefOr::Left(reference));
+        self
+    }
+
+    /// Adds a message of the channel, referenced by `#/channels/{channel}/messages/{name}`.
+    pub fn message(mut self, reference: ReferenceObject) -> Self {
+        self.messages.get_or_insert_with(Vec::new).push(reference);
+        self
+    }
+
+    pub fn reply(mut self, reply: OperationReply) -> Self {
+        self.reply = Some(RefOr::Right(reply));
+        self
+    }
+
+    pub fn reply_ref(mut self, reference: ReferenceObject) -> Self {
+        self.reply = Some(RefOr::Left(reference));
+        self
+    }
+
+    /// Builds the operation, failing if its action or channel is missing.
+    pub fn build(self) -> Result<Operation, Error> {
+        Ok(Operation {
+            action: self.action.ok_or(Error::MissingField {
+                object: "operation",
+                field: "action",
+            })?,
+            channel: self.channel.ok_or(Error::MissingField {
+                object: "operation",
+                field: "channel",
+            })?,
+            title: self.title,
+            summary: self.summary,
+            description: self.description,
+            security: self.security,
+            tags: self.tags,
+            external_docs: self.external_docs,
+            bindings: self.bindings,
+            traits: self.traits,
+            messages: self.messages,
+            reply: self.reply,
+        })
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::builder::fixtures::{self, message_for};
+    use crate::spec::channel::Channel;
     use crate::spec::info::Info;
+    use crate::spec::AsyncApiSpec;
     use schemars::JsonSchema;
 
+    // payload types, only their schemas are used
+    #[allow(dead_code)]
     #[derive(JsonSchema)]
     struct CommonData {
         number: u32,
         text: String,
     }
 
+    #[allow(dead_code)]
     #[derive(JsonSchema)]
     struct Req {
         data: CommonData,
     }
 
+    #[allow(dead_code)]
     #[derive(JsonSchema)]
     struct Res {
         data: CommonData,
@@ -269,6 +537,370 @@ mod test {
         )
         .unwrap();
 
-        println!("{}", serde_json::to_string(&spec.build()).unwrap())
+        let AsyncApiSpec::V3_0_0(spec) = spec.build().spec;
+        let Some(RefOr::Right(channel)) = spec.channels.get("DemoOp") else {
+            panic!("missing channel");
+        };
+        assert_eq!(channel.address.as_deref(), Some("demo.operation"));
+        assert!(channel.messages.contains_key("Req"));
+        let Some(RefOr::Right(reply_channel)) = spec.channels.get("DemoRepl") else {
+            panic!("missing reply channel");
+        };
+        assert!(reply_channel.address.is_none());
+        assert!(reply_channel.messages.contains_key("Res"));
+
+        let Some(RefOr::Right(operation)) = spec.operations.get("DemoOp") else {
+            panic!("missing operation");
+        };
+        assert_eq!(operation.action, OperationAction::Send);
+        assert_eq!(operation.channel, ReferenceObject::new_channel("DemoOp"));
+        assert_eq!(
+            operation.messages,
+            Some(vec![ReferenceObject::new_channel_message("DemoOp", "Req")])
+        );
+        let Some(RefOr::Right(reply)) = &operation.reply else {
+            panic!("missing reply");
+        };
+        assert_eq!(
+            reply.channel,
+            Some(ReferenceObject::new_channel("DemoRepl"))
+        );
+        let Some(RefOr::Right(address)) = &reply.address else {
+            panic!("missing reply address");
+        };
+        assert_eq!(address.location, "$message.payload#/reply");
+        assert_eq!(
+            reply.messages,
+            vec![ReferenceObject::new_channel_message("DemoRepl", "Res")]
+        );
+    }
+
+    #[test]
+    fn register_one_way_operations() {
+        let mut spec = fixtures::builder();
+        let mut generator = schemars::generate::SchemaGenerator::default();
+        let req_schema = generator.root_schema_for::<Req>();
+        let res_schema = generator.root_schema_for::<Res>();
+        let untitled = schemars::json_schema!({ "type": "string" });
+
+        spec.register_send_operation(
+            OperationInfo {
+                name: String::from("events"),
+                address: String::from("demo.events"),
+            },
+            vec![message_for(req_schema), message_for(untitled.clone())],
+        )
+        .unwrap();
+        spec.register_receive_operation(
+            OperationInfo {
+                name: String::from("commands"),
+                address: String::from("demo.commands"),
+            },
+            vec![message_for(res_schema), message_for(untitled.clone())],
+        )
+        .unwrap();
+        assert!(matches!(
+            spec.register_send_operation(
+                OperationInfo {
+                    name: String::from("events"),
+                    address: String::from("demo.events"),
+                },
+                vec![message_for(untitled)],
+            ),
+            Err(Error::DuplicateOperation { .. })
+        ));
+
+        let AsyncApiSpec::V3_0_0(spec) = spec.build().spec;
+        let Some(RefOr::Right(channel)) = spec.channels.get("events") else {
+            panic!("missing channel");
+        };
+        assert_eq!(channel.address.as_deref(), Some("demo.events"));
+        let mut names = channel.messages.keys().cloned().collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, ["Req", "events.msg1"]);
+        let Some(RefOr::Right(operation)) = spec.operations.get("commands") else {
+            panic!("missing operation");
+        };
+        assert_eq!(operation.action, OperationAction::Receive);
+        assert_eq!(
+            operation.messages,
+            Some(vec![
+                ReferenceObject::new_channel_message("commands", "Res"),
+                ReferenceObject::new_channel_message("commands", "commands.msg1"),
+            ])
+        );
+    }
+
+    #[test]
+    fn register_both_sides_of_request_reply() {
+        let mut spec = fixtures::builder();
+        let mut generator = schemars::generate::SchemaGenerator::default();
+        let req_schema = generator.root_schema_for::<Req>();
+        let res_schema = generator.root_schema_for::<Res>();
+
+        spec.register_simple_responder_operation(
+            ResponderInfo {
+                name: String::from("sum"),
+                channel_name: String::from("rpc_queue"),
+                address: Some(String::from("rpc_queue")),
+            },
+            ReplyTarget::Dynamic {
+                channel_name: String::from("queue"),
+                location: String::from("$message.header#/replyTo"),
+            },
+            message_for(req_schema.clone()),
+            message_for(res_schema.clone()),
+        )
+        .unwrap();
+        // the requester shares the channels of the responder
+        spec.register_simple_req_rep_operation(
+            OperationInfo {
+                name: String::from("rpc_queue"),
+                address: String::from("rpc_queue"),
+            },
+            ReplyInfo {
+                channel_name: String::from("queue"),
+                reply_address_location: String::from("$message.header#/replyTo"),
+            },
+            message_for(req_schema),
+            message_for(res_schema),
+        )
+        .unwrap();
+
+        let AsyncApiSpec::V3_0_0(spec) = spec.build().spec;
+        assert_eq!(spec.channels.len(), 2);
+        let Some(RefOr::Right(channel)) = spec.channels.get("queue") else {
+            panic!("missing channel");
+        };
+        assert!(channel.address.is_none());
+        assert_eq!(channel.messages.len(), 1);
+        let Some(RefOr::Right(responder)) = spec.operations.get("sum") else {
+            panic!("missing operation");
+        };
+        let Some(RefOr::Right(requester)) = spec.operations.get("rpc_queue") else {
+            panic!("missing operation");
+        };
+        assert_eq!(responder.action, OperationAction::Receive);
+        assert_eq!(requester.action, OperationAction::Send);
+        assert_eq!(responder.channel, requester.channel);
+        assert_eq!(responder.messages, requester.messages);
+        assert_eq!(responder.reply, requester.reply);
+    }
+
+    #[test]
+    fn report_conflicting_definitions() {
+        let mut spec = fixtures::builder();
+        let message_with = |definition: Schema| MessageFullSpec {
+            message: Message::builder().build(),
+            definitions: IndexMap::from([(String::from("CommonData"), definition)]),
+        };
+        let operation = |name: &str| OperationInfo {
+            name: String::from(name),
+            address: String::from(name),
+        };
+        let string = schemars::json_schema!({ "type": "string" });
+        let number = schemars::json_schema!({ "type": "number" });
+
+        spec.register_send_operation(operation("first"), vec![message_with(string)])
+            .unwrap();
+        let error = spec
+            .register_send_operation(operation("second"), vec![message_with(number)])
+            .unwrap_err();
+        assert_eq!(error.operation(), Some("second"));
+        assert_eq!(
+            error.pointer().as_deref(),
+            Some("/components/schemas/CommonData")
+        );
+        let Error::InOperation { source, .. } = error else {
+            panic!("missing operation context");
+        };
+        let Error::DuplicateDefinition {
+            existing,
+            conflicting,
+            ..
+        } = *source
+        else {
+            panic!("unexpected error {source}");
+        };
+        assert_eq!(*existing, serde_json::json!({ "type": "string" }));
+        assert_eq!(*conflicting, serde_json::json!({ "type": "number" }));
+    }
+
+    #[test]
+    fn failed_registrations_leave_the_spec_unchanged() {
+        let mut spec = fixtures::builder();
+        let message_with = |key: &str, payload: Schema| MessageFullSpec {
+            message: Message::builder().payload(payload).build(),
+            definitions: IndexMap::from([(String::from(key), schemars::json_schema!(true))]),
+        };
+        let string = schemars::json_schema!({ "title": "Reading", "type": "string" });
+        let number = schemars::json_schema!({ "title": "Reading", "type": "number" });
+
+        spec.register_send_operation(
+            OperationInfo {
+                name: String::from("readings"),
+                address: String::from("readings"),
+            },
+            vec![
+                message_with("Celsius", string),
+                message_with("Fahrenheit", number),
+            ],
+        )
+        .unwrap_err();
+
+        let AsyncApiSpec::V3_0_0(spec) = spec.build().spec;
+        assert!(spec.channels.is_empty());
+        assert!(spec.operations.is_empty());
+        assert!(spec.components.schemas.is_empty());
+    }
+
+    #[test]
+    fn report_conflicting_channels_and_messages() {
+        let mut spec = fixtures::builder();
+        let operation = |name: &str, address: &str| OperationInfo {
+            name: String::from(name),
+            address: String::from(address),
+        };
+        let string = schemars::json_schema!({ "title": "Reading", "type": "string" });
+        let number = schemars::json_schema!({ "title": "Reading", "type": "number" });
+
+        let error = spec
+            .register_send_operation(
+                operation("readings", "readings"),
+                vec![message_for(string.clone()), message_for(number)],
+            )
+            .unwrap_err();
+        assert_eq!(
+            error.pointer().as_deref(),
+            Some("/channels/readings/messages/Reading")
+        );
+        let Error::InOperation { source, .. } = error else {
+            panic!("missing operation context");
+        };
+        assert!(matches!(*source, Error::DuplicateMessage { .. }));
+
+        spec.register_send_operation(operation("events", "demo.events"), vec![])
+            .unwrap();
+        let error = spec
+            .register_simple_responder_operation(
+                ResponderInfo {
+                    name: String::from("onEvent"),
+                    channel_name: String::from("events"),
+                    address: Some(String::from("demo.other")),
+                },
+                ReplyTarget::Channel {
+                    channel_name: String::from("acks"),
+                    address: None,
+                },
+                message_for(string.clone()),
+                message_for(string.clone()),
+            )
+            .unwrap_err();
+        let Error::InOperation { source, .. } = error else {
+            panic!("missing operation context");
+        };
+        assert!(matches!(
+            *source,
+            Error::ChannelAddressMismatch { ref existing, ref conflicting, .. }
+                if existing == "demo.events" && conflicting == "demo.other"
+        ));
+
+        spec.register_channel_ref(
+            "shared",
+            ReferenceObject::new_component("channels", "shared"),
+        );
+        let error = spec
+            .register_send_operation(operation("shared", "shared"), vec![message_for(string)])
+            .unwrap_err();
+        let Error::InOperation { source, .. } = error else {
+            panic!("missing operation context");
+        };
+        assert!(matches!(*source, Error::ChannelIsReference { .. }));
+    }
+
+    #[test]
+    fn report_conflicting_reply_messages() {
+        let mut spec = fixtures::builder();
+        let string = schemars::json_schema!({ "title": "Reading", "type": "string" });
+        let number = schemars::json_schema!({ "title": "Reading", "type": "number" });
+        spec.register_send_operation(
+            OperationInfo {
+                name: String::from("replies"),
+                address: String::from("replies"),
+            },
+            vec![message_for(string.clone())],
+        )
+        .unwrap();
+
+        let error = spec
+            .register_simple_req_rep_operation(
+                OperationInfo {
+                    name: String::from("read"),
+                    address: String::from("read"),
+                },
+                ReplyInfo {
+                    channel_name: String::from("replies"),
+                    reply_address_location: String::from("$message.header#/replyTo"),
+                },
+                message_for(string.clone()),
+                message_for(number),
+            )
+            .unwrap_err();
+        assert_eq!(
+            error.pointer().as_deref(),
+            Some("/channels/replies/messages/Reading")
+        );
+        let Error::InOperation { source, .. } = error else {
+            panic!("missing operation context");
+        };
+        assert!(matches!(*source, Error::DuplicateMessage { .. }));
+
+        let AsyncApiSpec::V3_0_0(spec) = spec.build().spec;
+        let Some(RefOr::Right(channel)) = spec.channels.get("replies") else {
+            panic!("missing channel");
+        };
+        assert_eq!(
+            channel.messages.get("Reading"),
+            Some(&RefOr::Right(Message::builder().payload(string).build()))
+        );
+        assert!(!spec.operations.contains_key("read"));
+    }
+
+    #[test]
+    fn builders_validate_required_fields() {
+        let error = Info::builder().title("TEST").build().unwrap_err();
+        assert!(matches!(
+            error,
+            Error::MissingField {
+                object: "info",
+                field: "version"
+            }
+        ));
+        let error = Operation::builder()
+            .action(OperationAction::Receive)
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::MissingField {
+                object: "operation",
+                field: "channel"
+            }
+        ));
+
+        let operation = Operation::builder()
+            .action(OperationAction::Receive)
+            .channel(ReferenceObject::new_channel("userSignedUp"))
+            .message(ReferenceObject::new_channel_message("userSignedUp", "user"))
+            .tag(Tag {
+                name: String::from("user"),
+                description: None,
+                external_docs: None,
+            })
+            .build()
+            .unwrap();
+        assert_eq!(operation.messages.map(|messages| messages.len()), Some(1));
+        assert_eq!(operation.tags.len(), 1);
+        assert!(operation.reply.is_none());
     }
 }
@@ -0,0 +1,130 @@
+//! Registration of security schemes and their use by servers and operations.
+use crate::builder::AsyncApiV3Builder;
+use crate::error::Error;
+use crate::spec::common::{RefOr, ReferenceObject};
+use crate::spec::security::SecurityScheme;
+
+impl AsyncApiV3Builder {
+    /// Registers a security scheme in the components.
+    pub fn register_security_scheme(&mut self, name: &str, scheme: SecurityScheme) {
+        self.spec
+            .components
+            .security_schemes
+            .insert(String::from(name), RefOr::Right(scheme));
+    }
+
+    /// Requires the security scheme `scheme_name` of the components to connect to the server
+    /// `server_name`.
+    pub fn register_server_security(
+        &mut self,
+        server_name: &str,
+        scheme_name: &str,
+    ) -> Result<(), Error> {
+        let reference = self.security_scheme_ref(scheme_name)?;
+        let Some(RefOr::Right(server)) = self.spec.servers.get_mut(server_name) else {
+            return Err(Error::ServerNotFound {
+                name: String::from(server_name),
+            });
+        };
+        if !server.security.contains(&reference) {
+            server.security.push(reference);
+        }
+        Ok(())
+    }
+
+    /// Requires the security scheme `scheme_name` of the components to perform the operation
+    /// `operation_name`.
+    pub fn register_operation_security(
+        &mut self,
+        operation_name: &str,
+        scheme_name: &str,
+    ) -> Result<(), Error> {
+        let reference = self.security_scheme_ref(scheme_name)?;
+        let Some(RefOr::Right(operation)) = self.spec.operations.get_mut(operation_name) else {
+            return Err(Error::OperationNotFound {
+                name: String::from(operation_name),
+            });
+        };
+        if !operation.security.contains(&reference) {
+            operation.security.push(reference);
+        }
+        Ok(())
+    }
+
+    fn security_scheme_ref(&self, scheme_name: &str) -> Result<RefOr<SecurityScheme>, Error> {
+        if !self
+            .spec
+            .components
+            .security_schemes
+            .contains_key(scheme_name)
+        {
+            return Err(Error::SecuritySchemeNotFound {
+                name: String::from(scheme_name),
+            });
+        }
+        Ok(RefOr::Left(ReferenceObject::new_security_scheme(
+            scheme_name,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::builder::fixtures;
+    use crate::spec::operation::{Operation, OperationAction};
+    use crate::spec::server::Server;
+    use crate::spec::AsyncApiSpec;
+
+    #[test]
+    fn reference_security_schemes() {
+        let mut builder = fixtures::builder();
+        let server = Server::builder()
+            .host("rabbitmq.example.org")
+            .protocol("amqp")
+            .build()
+            .unwrap();
+        builder.register_server("production", server);
+        let operation = Operation::builder()
+            .action(OperationAction::Send)
+            .channel(ReferenceObject::new_channel("queue"))
+            .build()
+            .unwrap();
+        builder.register_operation("sendSumResult", operation);
+
+        assert!(matches!(
+            builder.register_server_security("production", "user"),
+            Err(Error::SecuritySchemeNotFound { .. })
+        ));
+        let scheme = fixtures::user_password_scheme();
+        builder.register_security_scheme("user", scheme);
+        builder
+            .register_server_security("production", "user")
+            .unwrap();
+        builder
+            .register_server_security("production", "user")
+            .unwrap();
+        builder
+            .register_operation_security("sendSumResult", "user")
+            .unwrap();
+        assert!(matches!(
+            builder.register_server_security("staging", "user"),
+            Err(Error::ServerNotFound { .. })
+        ));
+        assert!(matches!(
+            builder.register_operation_security("sum", "user"),
+            Err(Error::OperationNotFound { .. })
+        ));
+
+        let AsyncApiSpec::V3_0_0(spec) = builder.build().spec;
+        let reference = RefOr::Left(ReferenceObject::new_security_scheme("user"));
+        let Some(RefOr::Right(server)) = spec.servers.get("production") else {
+            panic!("missing server");
+        };
+        assert_eq!(server.security, std::slice::from_ref(&reference));
+        let Some(RefOr::Right(operation)) = spec.operations.get("sendSumResult") else {
+            panic!("missing operation");
+        };
+        assert_eq!(operation.security, [reference]);
+    }
+}
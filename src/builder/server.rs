@@ -0,0 +1,141 @@
+//! Fluent builder of the [`Server`] object.
+use indexmap::IndexMap;
+
+use crate::error::Error;
+use crate::spec::common::{ExternalDocumentation, RefOr, ReferenceObject, Tag};
+use crate::spec::security::SecurityScheme;
+use crate::spec::server::{Server, ServerBindings, Variable};
+
+/// Builder of [`Server`], whose host and protocol are required.
+#[derive(Clone, Debug, Default)]
+pub struct ServerBuilder {
+    host: Option<String>,
+    protocol: Option<String>,
+    protocol_version: Option<String>,
+    pathname: Option<String>,
+    description: Option<String>,
+    title: Option<String>,
+    summary: Option<String>,
+    variables: IndexMap<String, RefOr<Variable>>,
+    security: Vec<RefOr<SecurityScheme>>,
+    tags: Vec<Tag>,
+    external_docs: Option<RefOr<ExternalDocumentation>>,
+    bindings: Option<RefOr<ServerBindings>>,
+}
+
+impl Server {
+    pub fn builder() -> ServerBuilder {
+        ServerBuilder::new()
+    }
+}
+
+impl ServerBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn host(mut self, host: impl Into<String>) -> Self {
+        self.host = Some(host.into());
+        self
+    }
+
+    pub fn protocol(mut self, protocol: impl Into<String>) -> Self {
+        self.protocol = Some(protocol.into());
+        self
+    }
+
+    pub fn protocol_version(mut self, protocol_version: impl Into<String>) -> Self {
+        self.protocol_version = Some(protocol_version.into());
+        self
+    }
+
+    pub fn pathname(mut self, pathname: impl Into<String>) -> Self {
+        self.pathname = Some(pathname.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn summary(mut self, summary: impl Into<String>) -> Self {
+        self.summary = Some(summary.into());
+        self
+    }
+
+    /// Adds a variable used in the host or pathname of the server.
+    pub fn variable(mut self, name: impl Into<String>, variable: Variable) -> Self {
+        self.variables.insert(name.into(), RefOr::Right(variable));
+        self
+    }
+
+    pub fn variable_ref(mut self, name: impl Into<String>, reference: ReferenceObject) -> Self {
+        self.variables.insert(name.into(), RefOr::Left(reference));
+        self
+    }
+
+    pub fn security(mut self, security: SecurityScheme) -> Self {
+        self.security.push(RefOr::Right(security));
+        self
+    }
+
+    pub fn security_ref(mut self, reference: ReferenceObject) -> Self {
+        self.security.push(RefOr::Left(reference));
+        self
+    }
+
+    pub fn tag(mut self, tag: Tag) -> Self {
+        self.tags.push(tag);
+        self
+    }
+
+    pub fn external_docs(mut self, external_docs: ExternalDocumentation) -> Self {
+        self.external_docs = Some(RefOr::Right(external_docs));
+        self
+    }
+
+    pub fn external_docs_ref(mut self, reference: ReferenceObject) -> Self {
+        self.external_docs = Some(RefOr::Left(reference));
+        self
+    }
+
+    pub fn bindings(mut self, bindings: ServerBindings) -> Self {
+        self.bindings = Some(RefOr::Right(bindings));
+        self
+    }
+
+    pub fn bindings_ref(mut self, reference: ReferenceObject) -> Self {
+        self.bindings = Some(RefOr::Left(reference));
+        self
+    }
+
+    /// Builds the server, failing if its host or protocol is missing.
+    pub fn build(self) -> Result<Server, Error> {
+        Ok(Server {
+            host: self.host.ok_or(Error::MissingField {
+                object: "server",
+                field: "host",
+            })?,
+            protocol: self.protocol.ok_or(Error::MissingField {
+                object: "server",
+                field: "protocol",
+            })?,
+            protocol_version: self.protocol_version,
+            pathname: self.pathname,
+            description: self.description,
+            title: self.title,
+            summary: self.summary,
+            variables: self.variables,
+            security: self.security,
+            tags: self.tags,
+            external_docs: self.external_docs,
+            bindings: self.bindings,
+        })
+    }
+}
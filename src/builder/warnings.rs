@@ -0,0 +1,71 @@
+//! Non-fatal issues of a built specification.
+use crate::error::Warning;
+use crate::pointer::json_pointer;
+use crate::references::unused_components;
+use crate::spec::common::Either;
+use crate::spec::message::Messages;
+use crate::spec::AsyncApiV3Spec;
+
+pub(super) fn collect_warnings(spec: &AsyncApiV3Spec) -> Vec<Warning> {
+    let mut warnings = unused_components(spec)
+        .into_iter()
+        .map(|pointer| Warning::UnusedComponent { pointer })
+        .collect::<Vec<_>>();
+    for (channel_name, channel) in &spec.channels {
+        if let Either::Right(channel) = channel {
+            messages_without_payload(
+                &channel.messages,
+                &["channels", channel_name, "messages"],
+                &mut warnings,
+            );
+        }
+    }
+    messages_without_payload(
+        &spec.components.messages,
+        &["components", "messages"],
+        &mut warnings,
+    );
+    warnings.sort_by_key(ToString::to_string);
+    warnings
+}
+
+fn messages_without_payload(messages: &Messages, parent: &[&str], warnings: &mut Vec<Warning>) {
+    for (name, message) in messages {
+        if matches!(message, Either::Right(message) if message.payload.is_none()) {
+            let pointer = json_pointer(&[parent, &[name.as_str()]].concat());
+            warnings.push(Warning::MessageWithoutPayload { pointer });
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::builder::fixtures;
+    use crate::error::Warning;
+    use crate::spec::channel::Channel;
+    use crate::spec::message::Message;
+
+    #[test]
+    fn warn_about_unused_components_and_empty_messages() {
+        let mut builder = fixtures::builder();
+        let channel = Channel::builder()
+            .address("lights/on")
+            .message("turnOn", Message::builder().build())
+            .build();
+        builder.register_channel("lights/on", channel);
+        let scheme = fixtures::user_password_scheme();
+        builder.register_security_scheme("user", scheme);
+
+        assert_eq!(
+            builder.build().warnings,
+            [
+                Warning::UnusedComponent {
+                    pointer: String::from("/components/securitySchemes/user")
+                },
+                Warning::MessageWithoutPayload {
+                    pointer: String::from("/channels/lights~1on/messages/turnOn")
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,111 @@
+//! Generation of Rust code from a specification.
+//!
+//! The generated code only depends on `serde` and `serde_json`. It is meant to be produced by a
+//! build script and included in the crate:
+//!
+//! ```no_run
+//! // build.rs
+//! let out = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("asyncapi.rs");
+//! asyncapiv3::codegen::generate_types_file("asyncapi.yml", out).unwrap();
+//! ```
+//!
+//! ```ignore
+//! // lib.rs
+//! include!(concat!(env!("OUT_DIR"), "/asyncapi.rs"));
+//! ```
+use crate::spec::{document_version, AsyncApiSpec, AsyncApiV3Spec, DocumentVersion};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+mod naming;
+pub mod operations;
+pub mod types;
+
+pub use naming::{to_pascal_case, to_snake_case};
+pub use operations::generate_api;
+pub use types::{generate_types, RustType, TypeGenerator};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Cannot access {path:?}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Cannot parse specification {path:?}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: serde_yaml::Error,
+    },
+    #[error("Unsupported AsyncAPI version {version} of specification {path:?}")]
+    UnsupportedVersion { path: PathBuf, version: String },
+    #[error("Specification {path:?} is not an AsyncAPI document: missing field `asyncapi`")]
+    NotAsyncApi { path: PathBuf },
+    #[error("Unresolved reference '{reference}'")]
+    UnresolvedReference { reference: String },
+}
+
+/// Reads a specification from a YAML or JSON file.
+pub fn read_spec(path: impl AsRef<Path>) -> Result<AsyncApiV3Spec, Error> {
+    let path = path.as_ref();
+    let content = std::fs::read_to_string(path).map_err(|source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let parse_error = |source| Error::Parse {
+        path: path.to_path_buf(),
+        source,
+    };
+    // JSON documents are valid YAML documents
+    match document_version(serde_yaml::Deserializer::from_str(&content)).map_err(parse_error)? {
+        DocumentVersion::Supported => {}
+        DocumentVersion::Unsupported(version) => {
+            return Err(Error::UnsupportedVersion {
+                path: path.to_path_buf(),
+                version,
+            })
+        }
+        DocumentVersion::Missing => {
+            return Err(Error::NotAsyncApi {
+                path: path.to_path_buf(),
+            })
+        }
+    }
+    let AsyncApiSpec::V3_0_0(spec) = serde_yaml::from_str(&content).map_err(parse_error)?;
+    Ok(spec)
+}
+
+/// Generates the [types](generate_types) of the specification at `spec_path` and writes them to
+/// `out_path`. When called from a build script, the build is re-run when the specification
+/// changes.
+pub fn generate_types_file(
+    spec_path: impl AsRef<Path>,
+    out_path: impl AsRef<Path>,
+) -> Result<(), Error> {
+    let spec_path = spec_path.as_ref();
+    let spec = read_spec(spec_path)?;
+    write_generated(spec_path, out_path.as_ref(), &generate_types(&spec)?)
+}
+
+/// Generates the [types and operation stubs](generate_api) of the specification at `spec_path`
+/// and writes them to `out_path`. The generated code requires the `runtime` feature.
+pub fn generate_api_file(
+    spec_path: impl AsRef<Path>,
+    out_path: impl AsRef<Path>,
+) -> Result<(), Error> {
+    let spec_path = spec_path.as_ref();
+    let spec = read_spec(spec_path)?;
+    write_generated(spec_path, out_path.as_ref(), &generate_api(&spec)?)
+}
+
+fn write_generated(spec_path: &Path, out_path: &Path, code: &str) -> Result<(), Error> {
+    // only build scripts are run with `OUT_DIR` set, `cargo run` and `cargo test` also set
+    // `CARGO_MANIFEST_DIR`
+    if std::env::var_os("OUT_DIR").is_some() {
+        println!("cargo:rerun-if-changed={}", spec_path.display());
+    }
+    std::fs::write(out_path, code).map_err(|source| Error::Io {
+        path: out_path.to_path_buf(),
+        source,
+    })
+}
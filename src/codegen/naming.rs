@@ -0,0 +1,130 @@
+//! Conversion of specification names to valid Rust identifiers.
+
+/// Keywords that can be used as identifiers with the raw identifier syntax.
+const KEYWORDS: [&str; 46] = [
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where",
+];
+
+/// Keywords that cannot be raw identifiers.
+const RESERVED: [&str; 5] = ["crate", "self", "Self", "super", "_"];
+
+/// Prelude names used by the generated code, which types named after them would shadow.
+const PRELUDE: [&str; 11] = [
+    "Box", "Err", "None", "Ok", "Option", "Result", "Send", "Some", "String", "Sync", "Vec",
+];
+
+/// Splits a name on separators and on case changes, e.g. `userSignedUp`, `user_signed-up` and
+/// `UserSignedUp` all become `[user, signed, up]`.
+fn words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let chars = name.chars().collect::<Vec<_>>();
+    for (idx, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if c.is_uppercase() && !current.is_empty() {
+            let previous = chars[idx - 1];
+            let next_is_lower = chars.get(idx + 1).is_some_and(|next| next.is_lowercase());
+            // `userName` and the `N` of `HTTPName` start a new word
+            if previous.is_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_uppercase() && next_is_lower)
+            {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+pub fn to_pascal_case(name: &str) -> String {
+    words(name)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first
+                    .to_uppercase()
+                    .chain(chars.flat_map(char::to_lowercase))
+                    .collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+pub fn to_snake_case(name: &str) -> String {
+    words(name)
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Identifier of a type (struct, enum or enum variant) named after `name`.
+pub(crate) fn type_ident(name: &str) -> String {
+    let ident = to_pascal_case(name);
+    match ident.chars().next() {
+        None => String::from("Unnamed"),
+        Some(first) if first.is_ascii_digit() => format!("N{ident}"),
+        Some(_) => escape(ident),
+    }
+}
+
+/// Identifier of a field, function or argument named after `name`.
+pub(crate) fn value_ident(name: &str) -> String {
+    let ident = to_snake_case(name);
+    match ident.chars().next() {
+        None => String::from("value"),
+        Some(first) if first.is_ascii_digit() => format!("_{ident}"),
+        Some(_) => escape(ident),
+    }
+}
+
+fn escape(ident: String) -> String {
+    if RESERVED.contains(&ident.as_str()) || PRELUDE.contains(&ident.as_str()) {
+        format!("{ident}_")
+    } else if KEYWORDS.contains(&ident.as_str()) {
+        format!("r#{ident}")
+    } else {
+        ident
+    }
+}
+
+/// Name of an identifier as seen by serde, i.e. without the raw identifier prefix.
+pub(crate) fn unraw(ident: &str) -> &str {
+    ident.strip_prefix("r#").unwrap_or(ident)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn converts_names_to_identifiers() {
+        assert_eq!(type_ident("userSignedUp"), "UserSignedUp");
+        assert_eq!(type_ident("user_signed-up"), "UserSignedUp");
+        assert_eq!(type_ident("HTTPServer"), "HttpServer");
+        assert_eq!(type_ident("2fa"), "N2fa");
+        assert_eq!(type_ident("self"), "Self_");
+        assert_eq!(type_ident("result"), "Result_");
+        assert_eq!(type_ident("String"), "String_");
+        assert_eq!(value_ident("sentAt"), "sent_at");
+        assert_eq!(value_ident("type"), "r#type");
+        assert_eq!(value_ident("self"), "self_");
+        assert_eq!(value_ident("$id"), "id");
+        assert_eq!(value_ident("1st"), "_1st");
+    }
+}
@@ -0,0 +1,453 @@
+//! Generation of typed client and handler stubs from the operations of a specification.
+//!
+//! The generated code relies on the [`runtime`](crate::runtime) module, enabled by the `runtime`
+//! feature:
+//! - `send` operations become methods of a `Client` publishing through a
+//!   [`Transport`](crate::runtime::Transport)
+//! - `receive` operations become methods of a `Handler` trait, messages are routed to them by a
+//!   generated `dispatch` function
+//!
+//! Request/reply operations return the reply type, parameters of the channel address become
+//! arguments.
+use crate::codegen::naming::value_ident;
+use crate::codegen::types::{generated_header, render_doc, RustType, TypeGenerator};
+use crate::codegen::Error;
+use crate::spec::channel::Channel;
+use crate::spec::common::{Either, RefOr, ReferenceObject};
+use crate::spec::operation::{Operation, OperationAction, OperationReply};
+use crate::spec::AsyncApiV3Spec;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+const RUNTIME: &str = "asyncapiv3::runtime";
+
+struct OperationStub {
+    ident: String,
+    description: Option<String>,
+    address: String,
+    /// Name and identifier of the parameters of the address.
+    parameters: Vec<(String, String)>,
+    payload: RustType,
+    reply: Option<ReplyStub>,
+}
+
+struct ReplyStub {
+    ty: RustType,
+    address: Option<String>,
+}
+
+/// Generates the types of a specification together with a client for its `send` operations and
+/// a handler trait for its `receive` operations.
+pub fn generate_api(spec: &AsyncApiV3Spec) -> Result<String, Error> {
+    let mut types = TypeGenerator::new(&spec.components);
+    types.generate_components()?;
+    let message_types = types.generate_messages(spec)?;
+
+    let mut operations = spec.operations.iter().collect::<Vec<_>>();
+    operations.sort_by_key(|(key, _)| *key);
+    let mut sent = Vec::<OperationStub>::new();
+    let mut received = Vec::new();
+    for (key, operation) in operations {
+        let operation = resolve_operation(spec, operation)?;
+        let mut stub = operation_stub(spec, &mut types, &message_types, key, operation)?;
+        let stubs = match operation.action {
+            OperationAction::Send => &mut sent,
+            OperationAction::Receive => &mut received,
+        };
+        // `sendUser` and `send_user` are both `send_user`
+        while stubs.iter().any(|existing| existing.ident == stub.ident) {
+            stub.ident.push('_');
+        }
+        stubs.push(stub);
+    }
+
+    let mut out = generated_header(spec);
+    out.push_str(&types.render());
+    if !sent.is_empty() {
+        let client = types.unique_name("Client");
+        render_client(&mut out, &client, &sent);
+    }
+    if !received.is_empty() {
+        let handler = types.unique_name("Handler");
+        render_handler(&mut out, &handler, &received);
+    }
+    Ok(out)
+}
+
+fn operation_stub(
+    spec: &AsyncApiV3Spec,
+    types: &mut TypeGenerator,
+    message_types: &BTreeMap<String, Option<RustType>>,
+    key: &str,
+    operation: &Operation,
+) -> Result<OperationStub, Error> {
+    let (channel_pointer, channel) = resolve_channel(spec, &operation.channel.reference)?;
+    let address = channel_address(channel, &channel_pointer);
+    let messages = match &operation.messages {
+        Some(messages) => messages
+            .iter()
+            .map(|message| message.reference.clone())
+            .collect(),
+        None => channel_messages(&channel_pointer, channel),
+    };
+    let payload = messages_type(types, message_types, &format!("{key}Message"), &messages)?;
+
+    let reply = match &operation.reply {
+        Some(reply) => {
+            let reply = resolve_reply(spec, reply)?;
+            let reply_channel = reply
+                .channel
+                .as_ref()
+                .map(|channel| resolve_channel(spec, &channel.reference))
+                .transpose()?;
+            let messages = match (&reply.messages, &reply_channel) {
+                (messages, _) if !messages.is_empty() => messages
+                    .iter()
+                    .map(|message| message.reference.clone())
+                    .collect(),
+                (_, Some((pointer, channel))) => channel_messages(pointer, channel),
+                _ => vec![],
+            };
+            Some(ReplyStub {
+                ty: messages_type(types, message_types, &format!("{key}Reply"), &messages)?,
+                // a reply address defined by a runtime expression is only known when the
+                // request is sent
+                address: reply_channel.and_then(|(_, channel)| channel.address.clone()),
+            })
+        }
+        None => None,
+    };
+
+    let mut parameters = Vec::<(String, String)>::new();
+    for name in address_parameters(&address) {
+        let mut ident = value_ident(&name);
+        while ident == "payload" || parameters.iter().any(|(_, existing)| *existing == ident) {
+            ident.push('_');
+        }
+        parameters.push((name, ident));
+    }
+
+    Ok(OperationStub {
+        ident: value_ident(key),
+        description: operation
+            .description
+            .clone()
+            .or_else(|| operation.summary.clone())
+            .or_else(|| operation.title.clone()),
+        address,
+        parameters,
+        payload,
+        reply,
+    })
+}
+
+/// Type of a value that can be any of `messages`: the payload of the message if there is only
+/// one, an enum of the payloads otherwise.
+fn messages_type(
+    types: &mut TypeGenerator,
+    message_types: &BTreeMap<String, Option<RustType>>,
+    name_hint: &str,
+    messages: &[String],
+) -> Result<RustType, Error> {
+    let mut variants = Vec::new();
+    for pointer in messages {
+        let ty = message_types
+            .get(pointer)
+            .ok_or_else(|| Error::UnresolvedReference {
+                reference: pointer.clone(),
+            })?
+            .clone()
+            .unwrap_or(RustType::Any);
+        let name = pointer.rsplit('/').next().unwrap_or(pointer);
+        variants.push((String::from(name), ty));
+    }
+    Ok(match variants.as_slice() {
+        [] => RustType::Any,
+        [(_, first), ..] if variants.iter().all(|(_, ty)| ty == first) => first.clone(),
+        _ => types.define_union(name_hint, variants),
+    })
+}
+
+fn resolve_operation<'s>(
+    spec: &'s AsyncApiV3Spec,
+    operation: &'s RefOr<Operation>,
+) -> Result<&'s Operation, Error> {
+    match operation {
+        RefOr::Right(operation) => Ok(operation),
+        RefOr::Left(reference) => {
+            let resolved = reference
+                .reference
+                .strip_prefix("#/components/operations/")
+                .and_then(|key| spec.components.operations.get(key))
+                .ok_or_else(|| unresolved(reference))?;
+            resolve_operation(spec, resolved)
+        }
+    }
+}
+
+fn resolve_reply<'s>(
+    spec: &'s AsyncApiV3Spec,
+    reply: &'s RefOr<OperationReply>,
+) -> Result<&'s OperationReply, Error> {
+    match reply {
+        RefOr::Right(reply) => Ok(reply),
+        RefOr::Left(reference) => {
+            let resolved = reference
+                .reference
+                .strip_prefix("#/components/replies/")
+                .and_then(|key| spec.components.replies.get(key))
+                .ok_or_else(|| unresolved(reference))?;
+            resolve_reply(spec, resolved)
+        }
+    }
+}
+
+/// Returns the channel referenced by `reference`, together with the pointer of its definition.
+fn resolve_channel<'s>(
+    spec: &'s AsyncApiV3Spec,
+    reference: &str,
+) -> Result<(String, &'s Channel), Error> {
+    let channel = if let Some(key) = reference.strip_prefix("#/channels/") {
+        spec.channels.get(key)
+    } else if let Some(key) = reference.strip_prefix("#/components/channels/") {
+        spec.components.channels.get(key)
+    } else {
+        None
+    };
+    match channel {
+        Some(Either::Right(channel)) => Ok((String::from(reference), channel)),
+        Some(Either::Left(target)) => resolve_channel(spec, &target.reference),
+        None => Err(Error::UnresolvedReference {
+            reference: String::from(reference),
+        }),
+    }
+}
+
+fn channel_messages(channel_pointer: &str, channel: &Channel) -> Vec<String> {
+    let mut messages = channel
+        .messages
+        .keys()
+        .map(|key| format!("{channel_pointer}/messages/{key}"))
+        .collect::<Vec<_>>();
+    messages.sort();
+    messages
+}
+
+/// Address of a channel, channels with an unknown address are identified by their key.
+fn channel_address(channel: &Channel, channel_pointer: &str) -> String {
+    channel.address.clone().unwrap_or_else(|| {
+        String::from(
+            channel_pointer
+                .rsplit('/')
+                .next()
+                .unwrap_or(channel_pointer),
+        )
+    })
+}
+
+fn address_parameters(address: &str) -> Vec<String> {
+    let mut parameters = Vec::new();
+    let mut rest = address;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+            break;
+        };
+        let name = String::from(&rest[start + 1..end]);
+        if !parameters.contains(&name) {
+            parameters.push(name);
+        }
+        rest = &rest[end + 1..];
+    }
+    parameters
+}
+
+fn unresolved(reference: &ReferenceObject) -> Error {
+    Error::UnresolvedReference {
+        reference: reference.reference.clone(),
+    }
+}
+
+fn render_client(out: &mut String, name: &str, operations: &[OperationStub]) {
+    let _ = writeln!(
+        out,
+        "
+/// Client of the operations sent by the application.
+pub struct {name}<T> {{
+    transport: T,
+}}
+
+impl<T: {RUNTIME}::Transport> {name}<T> {{
+    pub fn new(transport: T) -> Self {{
+        Self {{ transport }}
+    }}
+
+    pub fn transport(&self) -> &T {{
+        &self.transport
+    }}"
+    );
+    for operation in operations {
+        out.push('\n');
+        render_doc(out, "    ", operation.description.as_deref());
+        let mut arguments = operation
+            .parameters
+            .iter()
+            .map(|(_, ident)| format!("{ident}: &str"))
+            .collect::<Vec<_>>();
+        arguments.push(format!("payload: &{}", operation.payload));
+        let output = match &operation.reply {
+            Some(reply) => reply.ty.to_string(),
+            None => String::from("()"),
+        };
+        let _ = writeln!(
+            out,
+            "    pub async fn {}(&self, {}) -> ::std::result::Result<{output}, {RUNTIME}::Error<T::Error>> {{",
+            operation.ident,
+            arguments.join(", ")
+        );
+        let parameters = operation
+            .parameters
+            .iter()
+            .map(|(name, ident)| format!("({name:?}, {ident})"))
+            .collect::<Vec<_>>();
+        let _ = writeln!(
+            out,
+            "        let address = {RUNTIME}::fill_address({:?}, &[{}]);",
+            operation.address,
+            parameters.join(", ")
+        );
+        let _ = writeln!(
+            out,
+            "        let payload = serde_json::to_value(payload)
+            .map_err(|source| {RUNTIME}::Error::Payload {{ source }})?;"
+        );
+        match &operation.reply {
+            Some(reply) => {
+                let reply_address = match &reply.address {
+                    Some(address) => format!("Some({address:?})"),
+                    None => String::from("None"),
+                };
+                let _ = writeln!(
+                    out,
+                    "        let reply = self
+            .transport
+            .request(&address, {reply_address}, payload)
+            .await
+            .map_err(|source| {RUNTIME}::Error::Transport {{ source }})?;
+        serde_json::from_value(reply).map_err(|source| {RUNTIME}::Error::Payload {{ source }})"
+                );
+            }
+            None => {
+                let _ = writeln!(
+                    out,
+                    "        self.transport
+            .publish(&address, payload)
+            .await
+            .map_err(|source| {RUNTIME}::Error::Transport {{ source }})"
+                );
+            }
+        }
+        out.push_str("    }\n");
+    }
+    out.push_str("}\n");
+}
+
+fn render_handler(out: &mut String, name: &str, operations: &[OperationStub]) {
+    let _ = writeln!(
+        out,
+        "
+/// Handler of the operations received by the application.
+pub trait {name}: Send + Sync {{"
+    );
+    for (idx, operation) in operations.iter().enumerate() {
+        if idx > 0 {
+            out.push('\n');
+        }
+        render_doc(out, "    ", operation.description.as_deref());
+        let mut arguments = operation
+            .parameters
+            .iter()
+            .map(|(_, ident)| format!("{ident}: String"))
+            .collect::<Vec<_>>();
+        arguments.push(format!("payload: {}", operation.payload));
+        let output = match &operation.reply {
+            Some(reply) => reply.ty.to_string(),
+            None => String::from("()"),
+        };
+        let _ = writeln!(
+            out,
+            "    fn {}(&self, {}) -> impl std::future::Future<Output = {output}> + Send;",
+            operation.ident,
+            arguments.join(", ")
+        );
+    }
+    out.push_str("}\n");
+
+    let _ = writeln!(
+        out,
+        "
+/// Dispatches a message received on `address` to the matching [`{name}`] method. Returns the
+/// serialized reply of request/reply operations.
+pub async fn dispatch<H: {name}>(
+    handler: &H,
+    address: &str,
+    payload: serde_json::Value,
+) -> ::std::result::Result<Option<serde_json::Value>, {RUNTIME}::DispatchError> {{"
+    );
+    for operation in operations {
+        let matcher = format!("{RUNTIME}::match_address({:?}, address)", operation.address);
+        if operation.parameters.is_empty() {
+            let _ = writeln!(out, "    if {matcher}.is_some() {{");
+        } else {
+            let _ = writeln!(out, "    if let Some(mut parameters) = {matcher} {{");
+        }
+        let _ = writeln!(
+            out,
+            "        let payload = serde_json::from_value(payload)
+            .map_err(|source| {RUNTIME}::DispatchError::Payload {{ source }})?;"
+        );
+        let mut arguments = operation
+            .parameters
+            .iter()
+            .map(|(name, _)| format!("parameters.remove({name:?}).unwrap_or_default()"))
+            .collect::<Vec<_>>();
+        arguments.push(String::from("payload"));
+        let call = format!(
+            "handler.{}({}).await",
+            operation.ident,
+            arguments.join(", ")
+        );
+        if operation.reply.is_some() {
+            let _ = writeln!(
+                out,
+                "        let reply = {call};
+        return serde_json::to_value(reply)
+            .map(Some)
+            .map_err(|source| {RUNTIME}::DispatchError::Payload {{ source }});"
+            );
+        } else {
+            let _ = writeln!(out, "        {call};\n        return Ok(None);");
+        }
+        out.push_str("    }\n");
+    }
+    let _ = writeln!(
+        out,
+        "    Err({RUNTIME}::DispatchError::UnknownAddress {{
+        address: String::from(address),
+    }})
+}}"
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn extracts_address_parameters() {
+        assert_eq!(
+            address_parameters("adeo-{env}-case-study-{version}-{env}"),
+            vec![String::from("env"), String::from("version")]
+        );
+        assert!(address_parameters("rpc_queue").is_empty());
+    }
+}
@@ -0,0 +1,988 @@
+//! Generation of Rust types from the JSON schemas of a specification.
+use crate::codegen::naming::{type_ident, unraw, value_ident};
+use crate::codegen::Error;
+use crate::schema_format::inline_definitions;
+use crate::spec::common::{Either, RefOr};
+use crate::spec::component::Components;
+use crate::spec::message::{Message, MultiFormatSchema};
+use crate::spec::AsyncApiV3Spec;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter, Write};
+
+const COMPONENT_SCHEMAS: &str = "#/components/schemas/";
+
+/// A Rust type expression, as used in fields and function signatures.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RustType {
+    /// Primitive types (`bool`, `i64`, `f64`, `String`, ...).
+    Primitive(&'static str),
+    /// Any JSON value.
+    Any,
+    /// A type generated by the [`TypeGenerator`].
+    Named(String),
+    Vec(Box<RustType>),
+    /// A map with string keys.
+    Map(Box<RustType>),
+    Option(Box<RustType>),
+}
+
+impl RustType {
+    fn option(self) -> Self {
+        match self {
+            option @ RustType::Option(_) => option,
+            other => RustType::Option(Box::new(other)),
+        }
+    }
+
+    /// Name of the generated type stored directly (not behind a `Vec` or a map) in this type.
+    fn direct_name(&self) -> Option<&str> {
+        match self {
+            RustType::Named(name) => Some(name),
+            RustType::Option(inner) => inner.direct_name(),
+            _ => None,
+        }
+    }
+
+    fn boxed(&self) -> String {
+        match self {
+            RustType::Named(name) => format!("Box<{name}>"),
+            RustType::Option(inner) => format!("Option<{}>", inner.boxed()),
+            other => other.to_string(),
+        }
+    }
+}
+
+impl Display for RustType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RustType::Primitive(name) => f.write_str(name),
+            RustType::Any => f.write_str("serde_json::Value"),
+            RustType::Named(name) => f.write_str(name),
+            RustType::Vec(inner) => write!(f, "Vec<{inner}>"),
+            RustType::Map(inner) => write!(f, "std::collections::HashMap<String, {inner}>"),
+            RustType::Option(inner) => write!(f, "Option<{inner}>"),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Field {
+    ident: String,
+    json_name: String,
+    description: Option<String>,
+    ty: RustType,
+    required: bool,
+}
+
+#[derive(Debug)]
+struct Variant {
+    ident: String,
+    ty: RustType,
+}
+
+#[derive(Debug)]
+enum TypeDef {
+    Struct {
+        name: String,
+        description: Option<String>,
+        fields: Vec<Field>,
+        additional_properties: Option<RustType>,
+        deny_unknown_fields: bool,
+    },
+    StringEnum {
+        name: String,
+        description: Option<String>,
+        /// Identifier and serialized value of each variant.
+        variants: Vec<(String, String)>,
+    },
+    /// `oneOf`/`anyOf` schemas, deserialized as the first matching variant. The structs wrapped
+    /// by the variants deny unknown fields.
+    Union {
+        name: String,
+        description: Option<String>,
+        variants: Vec<Variant>,
+    },
+    Alias {
+        name: String,
+        description: Option<String>,
+        target: RustType,
+    },
+}
+
+impl TypeDef {
+    fn name(&self) -> &str {
+        match self {
+            TypeDef::Struct { name, .. }
+            | TypeDef::StringEnum { name, .. }
+            | TypeDef::Union { name, .. }
+            | TypeDef::Alias { name, .. } => name,
+        }
+    }
+
+    /// Types stored directly in this type, that make it infinitely sized if recursive.
+    fn direct_dependencies(&self) -> Vec<&str> {
+        match self {
+            TypeDef::Struct { fields, .. } => fields
+                .iter()
+                .filter_map(|field| field.ty.direct_name())
+                .collect(),
+            TypeDef::Union { variants, .. } => variants
+                .iter()
+                .filter_map(|variant| variant.ty.direct_name())
+                .collect(),
+            TypeDef::Alias { target, .. } => target.direct_name().into_iter().collect(),
+            TypeDef::StringEnum { .. } => vec![],
+        }
+    }
+}
+
+/// How to name the type generated for a schema.
+enum Naming {
+    /// The name is already reserved and must be used as is.
+    Exact(String),
+    /// The name is derived from the hint, adding a suffix in case of conflicts.
+    Hint(String),
+}
+
+/// Generator of Rust types (structs and enums with serde derives) from JSON schemas.
+///
+/// Component schemas are generated with their own name, inline schemas are named after the
+/// message or the property they belong to. References to component schemas are followed and
+/// cycles are broken with `Box`.
+pub struct TypeGenerator<'a> {
+    components: &'a Components,
+    definitions: Vec<TypeDef>,
+    used_names: BTreeSet<String>,
+    /// Rust names of the component schemas.
+    component_names: BTreeMap<String, String>,
+    generated_components: BTreeSet<String>,
+}
+
+impl<'a> TypeGenerator<'a> {
+    pub fn new(components: &'a Components) -> Self {
+        let mut generator = Self {
+            components,
+            definitions: vec![],
+            used_names: BTreeSet::new(),
+            component_names: BTreeMap::new(),
+            generated_components: BTreeSet::new(),
+        };
+        let mut keys = components.schemas.keys().collect::<Vec<_>>();
+        keys.sort();
+        for key in keys {
+            let name = generator.unique_name(key);
+            generator.component_names.insert(key.clone(), name);
+        }
+        generator
+    }
+
+    /// Generates the types of every component schema.
+    pub fn generate_components(&mut self) -> Result<(), Error> {
+        let keys = self.component_names.keys().cloned().collect::<Vec<_>>();
+        for key in keys {
+            self.component_type(&key)?;
+        }
+        Ok(())
+    }
+
+    /// Generates the types needed by a message payload or headers definition and returns the
+    /// type to use for it. Inline types are named after `name_hint`.
+    pub fn schema_type(
+        &mut self,
+        name_hint: &str,
+        schema: &RefOr<Either<schemars::Schema, MultiFormatSchema>>,
+    ) -> Result<RustType, Error> {
+        match schema {
+            RefOr::Left(reference) => self.reference_type(&reference.reference),
+            RefOr::Right(Either::Left(schema)) => {
+                self.value_type(schema.as_value(), Naming::Hint(String::from(name_hint)))
+            }
+            RefOr::Right(Either::Right(multi_format)) => {
+                let schema = multi_format_value(multi_format);
+                self.value_type(&schema, Naming::Hint(String::from(name_hint)))
+            }
+        }
+    }
+
+    /// Generates the types needed by the payload of a message, named `<name>Payload`. Returns
+    /// `None` if the message has no payload.
+    pub fn payload_type(
+        &mut self,
+        message_name: &str,
+        message: &Message,
+    ) -> Result<Option<RustType>, Error> {
+        message
+            .payload
+            .as_ref()
+            .map(|payload| self.schema_type(&format!("{message_name}Payload"), payload))
+            .transpose()
+    }
+
+    /// Generates the payload types of the messages defined in the components and in the channels
+    /// of `spec`. Returns the payload type of each message, by JSON pointer (e.g.
+    /// `#/channels/userSignup/messages/userSignedUp`).
+    pub fn generate_messages(
+        &mut self,
+        spec: &AsyncApiV3Spec,
+    ) -> Result<BTreeMap<String, Option<RustType>>, Error> {
+        let mut messages = Vec::new();
+        let mut component_messages = spec.components.messages.iter().collect::<Vec<_>>();
+        component_messages.sort_by_key(|(key, _)| *key);
+        for (key, message) in component_messages {
+            messages.push((format!("#/components/messages/{key}"), key, message));
+        }
+        let mut channels = spec.channels.iter().collect::<Vec<_>>();
+        channels.sort_by_key(|(key, _)| *key);
+        for (channel_key, channel) in channels {
+            let Either::Right(channel) = channel else {
+                continue;
+            };
+            let mut channel_messages = channel.messages.iter().collect::<Vec<_>>();
+            channel_messages.sort_by_key(|(key, _)| *key);
+            for (key, message) in channel_messages {
+                messages.push((
+                    format!("#/channels/{channel_key}/messages/{key}"),
+                    key,
+                    message,
+                ));
+            }
+        }
+
+        let mut types = BTreeMap::new();
+        let mut references = Vec::new();
+        for (pointer, key, message) in messages {
+            match message {
+                Either::Right(message) => {
+                    types.insert(pointer, self.payload_type(key, message)?);
+                }
+                Either::Left(reference) => references.push((pointer, &reference.reference)),
+            }
+        }
+        for (pointer, reference) in references {
+            let ty = types.get(reference.as_str()).cloned().ok_or_else(|| {
+                Error::UnresolvedReference {
+                    reference: reference.clone(),
+                }
+            })?;
+            types.insert(pointer, ty);
+        }
+        Ok(types)
+    }
+
+    /// Defines an untagged enum whose variants wrap the given types, deserialized as the first
+    /// matching variant. Structs wrapped by a variant deny unknown fields to tell variants apart.
+    pub fn define_union(&mut self, name_hint: &str, variants: Vec<(String, RustType)>) -> RustType {
+        let name = self.unique_name(name_hint);
+        self.definitions.push(TypeDef::Union {
+            name: name.clone(),
+            description: None,
+            variants: variants
+                .into_iter()
+                .map(|(ident, ty)| Variant {
+                    ident: type_ident(&ident),
+                    ty,
+                })
+                .collect(),
+        });
+        RustType::Named(name)
+    }
+
+    /// Renders the generated types as Rust source code.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for definition in &self.definitions {
+            out.push('\n');
+            self.render_definition(&mut out, definition);
+        }
+        out
+    }
+
+    /// Reserves a type name derived from `hint`, not conflicting with the generated types.
+    pub fn unique_name(&mut self, hint: &str) -> String {
+        let base = type_ident(hint);
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while self.used_names.contains(&name) {
+            name = format!("{base}{suffix}");
+            suffix += 1;
+        }
+        self.used_names.insert(name.clone());
+        name
+    }
+
+    fn define_name(&mut self, naming: Naming) -> String {
+        match naming {
+            Naming::Exact(name) => name,
+            Naming::Hint(hint) => self.unique_name(&hint),
+        }
+    }
+
+    fn reference_type(&mut self, reference: &str) -> Result<RustType, Error> {
+        let Some(key) = reference.strip_prefix(COMPONENT_SCHEMAS) else {
+            // only component schemas can be named, other references are kept untyped
+            return Ok(RustType::Any);
+        };
+        let name = self.component_type(key)?;
+        Ok(RustType::Named(name))
+    }
+
+    fn component_type(&mut self, key: &str) -> Result<String, Error> {
+        let unresolved = || Error::UnresolvedReference {
+            reference: format!("{COMPONENT_SCHEMAS}{key}"),
+        };
+        let name = self
+            .component_names
+            .get(key)
+            .cloned()
+            .ok_or_else(unresolved)?;
+        if !self.generated_components.insert(String::from(key)) {
+            return Ok(name);
+        }
+        let schema = self.components.schemas.get(key).ok_or_else(unresolved)?;
+        let ty = match schema {
+            RefOr::Left(reference) => self.reference_type(&reference.reference)?,
+            RefOr::Right(Either::Left(schema)) => {
+                self.value_type(schema.as_value(), Naming::Exact(name.clone()))?
+            }
+            RefOr::Right(Either::Right(multi_format)) => {
+                let schema = multi_format_value(multi_format);
+                self.value_type(&schema, Naming::Exact(name.clone()))?
+            }
+        };
+        // nullable component objects are generated as plain structs, references to them are
+        // optional anyway when the value can be missing
+        let defined = ty.direct_name() == Some(name.as_str());
+        if !defined {
+            let description = schema_description(schema);
+            self.definitions.push(TypeDef::Alias {
+                name: name.clone(),
+                description,
+                target: ty,
+            });
+        }
+        Ok(name)
+    }
+
+    fn value_type(&mut self, schema: &Value, naming: Naming) -> Result<RustType, Error> {
+        let Value::Object(object) = schema else {
+            return Ok(RustType::Any);
+        };
+        if let Some(reference) = object.get("$ref").and_then(Value::as_str) {
+            return self.reference_type(reference);
+        }
+        if let (Some(Value::String(_)), Some(_)) =
+            (object.get("schemaFormat"), object.get("schema"))
+        {
+            let schema = multi_format_value(&MultiFormatSchema {
+                schema_format: object["schemaFormat"]
+                    .as_str()
+                    .map(String::from)
+                    .unwrap_or_default(),
+                schema: object["schema"].clone(),
+            });
+            return self.value_type(&schema, naming);
+        }
+        if let Some(Value::Array(branches)) = object.get("oneOf").or_else(|| object.get("anyOf")) {
+            return self.union_type(object, branches, naming);
+        }
+        if let Some(Value::Array(branches)) = object.get("allOf") {
+            return match branches.as_slice() {
+                [single] if !object.contains_key("properties") => self.value_type(single, naming),
+                _ => {
+                    let merged = self.merge_all_of(object, branches);
+                    self.object_type(&merged, naming)
+                }
+            };
+        }
+        if let Some(Value::Array(values)) = object.get("enum") {
+            return self.enum_type(object, values, naming);
+        }
+        if let Some(value) = object.get("const") {
+            return Ok(json_value_type(value));
+        }
+
+        let types = match object.get("type") {
+            Some(Value::String(type_name)) => vec![type_name.as_str()],
+            Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+            _ if object.contains_key("properties") => vec!["object"],
+            _ if object.contains_key("items") => vec!["array"],
+            _ => vec![],
+        };
+        let nullable = types.contains(&"null");
+        let non_null = types
+            .iter()
+            .copied()
+            .filter(|type_name| *type_name != "null")
+            .collect::<Vec<_>>();
+        let ty = match non_null.as_slice() {
+            [] if nullable => RustType::Primitive("()"),
+            [] => RustType::Any,
+            ["string"] => RustType::Primitive("String"),
+            ["boolean"] => RustType::Primitive("bool"),
+            ["integer"] => match object.get("format").and_then(Value::as_str) {
+                Some("int32") => RustType::Primitive("i32"),
+                _ => RustType::Primitive("i64"),
+            },
+            ["number"] => match object.get("format").and_then(Value::as_str) {
+                Some("float") => RustType::Primitive("f32"),
+                _ => RustType::Primitive("f64"),
+            },
+            ["array"] => {
+                let item_hint = format!("{}Item", naming_hint(&naming));
+                match object.get("items") {
+                    Some(items) => {
+                        RustType::Vec(Box::new(self.value_type(items, Naming::Hint(item_hint))?))
+                    }
+                    None => RustType::Vec(Box::new(RustType::Any)),
+                }
+            }
+            ["object"] => self.object_type(object, naming)?,
+            _ => RustType::Any,
+        };
+        Ok(if nullable && !matches!(ty, RustType::Primitive("()")) {
+            ty.option()
+        } else {
+            ty
+        })
+    }
+
+    fn object_type(
+        &mut self,
+        object: &Map<String, Value>,
+        naming: Naming,
+    ) -> Result<RustType, Error> {
+        let properties = object.get("properties").and_then(Value::as_object);
+        let additional = object.get("additionalProperties");
+        if properties.is_none_or(Map::is_empty) {
+            let value_hint = format!("{}Value", naming_hint(&naming));
+            return Ok(match additional {
+                Some(schema @ Value::Object(_)) => {
+                    RustType::Map(Box::new(self.value_type(schema, Naming::Hint(value_hint))?))
+                }
+                _ => RustType::Map(Box::new(RustType::Any)),
+            });
+        }
+
+        let name = self.define_name(naming);
+        let required = object
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|required| {
+                required
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let mut fields = Vec::new();
+        let mut used_idents = BTreeSet::new();
+        for (json_name, property) in properties.into_iter().flatten() {
+            let ty = self.value_type(
+                property,
+                Naming::Hint(format!("{name}{}", type_ident(json_name))),
+            )?;
+            let mut ident = value_ident(json_name);
+            while !used_idents.insert(ident.clone()) {
+                ident.push('_');
+            }
+            fields.push(Field {
+                ident,
+                json_name: json_name.clone(),
+                description: description(property),
+                ty,
+                required: required.contains(&json_name.as_str()),
+            });
+        }
+        let additional_properties = match additional {
+            Some(Value::Bool(true)) => Some(RustType::Any),
+            Some(schema @ Value::Object(_)) => {
+                Some(self.value_type(schema, Naming::Hint(format!("{name}AdditionalProperty")))?)
+            }
+            _ => None,
+        };
+        self.definitions.push(TypeDef::Struct {
+            name: name.clone(),
+            description: description_of(object),
+            fields,
+            additional_properties,
+            deny_unknown_fields: additional == Some(&Value::Bool(false)),
+        });
+        Ok(RustType::Named(name))
+    }
+
+    fn union_type(
+        &mut self,
+        object: &Map<String, Value>,
+        branches: &[Value],
+        naming: Naming,
+    ) -> Result<RustType, Error> {
+        let is_null = |branch: &Value| branch.get("type") == Some(&Value::from("null"));
+        let nullable = branches.iter().any(is_null);
+        let branches = branches
+            .iter()
+            .filter(|branch| !is_null(branch))
+            .collect::<Vec<_>>();
+        let ty = match branches.as_slice() {
+            [] => RustType::Primitive("()"),
+            [single] => self.value_type(single, naming)?,
+            _ => {
+                let name = self.define_name(naming);
+                let mut variants = Vec::<Variant>::new();
+                for (idx, branch) in branches.into_iter().enumerate() {
+                    let base =
+                        variant_name(branch).unwrap_or_else(|| format!("Variant{}", idx + 1));
+                    let mut ident = type_ident(&base);
+                    let mut suffix = 2;
+                    while variants.iter().any(|variant| variant.ident == ident) {
+                        ident = format!("{}{suffix}", type_ident(&base));
+                        suffix += 1;
+                    }
+                    let ty = self.value_type(branch, Naming::Hint(format!("{name}{ident}")))?;
+                    variants.push(Variant { ident, ty });
+                }
+                self.definitions.push(TypeDef::Union {
+                    name: name.clone(),
+                    description: description_of(object),
+                    variants,
+                });
+                RustType::Named(name)
+            }
+        };
+        Ok(if nullable { ty.option() } else { ty })
+    }
+
+    fn enum_type(
+        &mut self,
+        object: &Map<String, Value>,
+        values: &[Value],
+        naming: Naming,
+    ) -> Result<RustType, Error> {
+        let nullable = values.contains(&Value::Null);
+        let values = values
+            .iter()
+            .filter(|value| !value.is_null())
+            .collect::<Vec<_>>();
+        if !values.iter().all(|value| value.is_string()) {
+            let ty = match values.as_slice() {
+                [first, ..]
+                    if values
+                        .iter()
+                        .all(|value| json_value_type(value) == json_value_type(first)) =>
+                {
+                    json_value_type(first)
+                }
+                _ => RustType::Any,
+            };
+            return Ok(if nullable { ty.option() } else { ty });
+        }
+
+        let name = self.define_name(naming);
+        let mut variants = Vec::<(String, String)>::new();
+        for value in values.iter().filter_map(|value| value.as_str()) {
+            let base = if value.is_empty() { "Empty" } else { value };
+            let mut ident = type_ident(base);
+            let mut suffix = 2;
+            while variants.iter().any(|(existing, _)| *existing == ident) {
+                ident = format!("{}{suffix}", type_ident(base));
+                suffix += 1;
+            }
+            variants.push((ident, String::from(value)));
+        }
+        self.definitions.push(TypeDef::StringEnum {
+            name: name.clone(),
+            description: description_of(object),
+            variants,
+        });
+        let ty = RustType::Named(name);
+        Ok(if nullable { ty.option() } else { ty })
+    }
+
+    /// Merges the properties of `allOf` branches (following references to component schemas)
+    /// into a single object schema.
+    fn merge_all_of(&self, object: &Map<String, Value>, branches: &[Value]) -> Map<String, Value> {
+        let mut merged = object.clone();
+        merged.shift_remove("allOf");
+        let mut properties = merged
+            .shift_remove("properties")
+            .and_then(|properties| properties.as_object().cloned())
+            .unwrap_or_default();
+        let mut required = merged
+            .shift_remove("required")
+            .and_then(|required| required.as_array().cloned())
+            .unwrap_or_default();
+
+        let mut pending = branches.to_vec();
+        let mut visited = BTreeSet::new();
+        while let Some(branch) = pending.pop() {
+            if let Some(reference) = branch.get("$ref").and_then(Value::as_str) {
+                if visited.insert(String::from(reference)) {
+                    pending.extend(self.resolve(reference));
+                }
+                continue;
+            }
+            if let Some(Value::Array(nested)) = branch.get("allOf") {
+                pending.extend(nested.iter().cloned());
+            }
+            if let Some(Value::Object(branch_properties)) = branch.get("properties") {
+                for (key, property) in branch_properties {
+                    properties
+                        .entry(key.clone())
+                        .or_insert_with(|| property.clone());
+                }
+            }
+            if let Some(Value::Array(branch_required)) = branch.get("required") {
+                required.extend(branch_required.iter().cloned());
+            }
+            if merged.get("description").is_none() {
+                if let Some(description) = branch.get("description") {
+                    merged.insert(String::from("description"), description.clone());
+                }
+            }
+        }
+
+        merged.insert(String::from("type"), Value::from("object"));
+        merged.insert(String::from("properties"), Value::Object(properties));
+        merged.insert(String::from("required"), Value::Array(required));
+        merged
+    }
+
+    fn resolve(&self, reference: &str) -> Option<Value> {
+        let key = reference.strip_prefix(COMPONENT_SCHEMAS)?;
+        match self.components.schemas.get(key)? {
+            RefOr::Left(reference) => self.resolve(&reference.reference),
+            RefOr::Right(Either::Left(schema)) => Some(schema.as_value().clone()),
+            RefOr::Right(Either::Right(multi_format)) => Some(multi_format_value(multi_format)),
+        }
+    }
+
+    /// Whether `target` (transitively) stores a value of type `source` without indirection.
+    fn reaches(&self, target: &str, source: &str) -> bool {
+        let mut visited = BTreeSet::new();
+        let mut pending = vec![target];
+        while let Some(name) = pending.pop() {
+            if name == source {
+                return true;
+            }
+            if !visited.insert(name) {
+                continue;
+            }
+            if let Some(definition) = self.definitions.iter().find(|def| def.name() == name) {
+                pending.extend(definition.direct_dependencies());
+            }
+        }
+        false
+    }
+
+    /// Whether `name` is stored directly by a variant of a union.
+    fn is_union_variant(&self, name: &str) -> bool {
+        self.definitions.iter().any(|definition| match definition {
+            TypeDef::Union { variants, .. } => variants
+                .iter()
+                .any(|variant| variant.ty.direct_name() == Some(name)),
+            _ => false,
+        })
+    }
+
+    /// Renders `ty`, boxing it when it would make `owner` recursive.
+    fn render_type(&self, owner: &str, ty: &RustType) -> String {
+        match ty.direct_name() {
+            Some(target) if self.reaches(target, owner) => ty.boxed(),
+            _ => ty.to_string(),
+        }
+    }
+
+    fn render_definition(&self, out: &mut String, definition: &TypeDef) {
+        match definition {
+            TypeDef::Struct {
+                name,
+                description,
+                fields,
+                additional_properties,
+                deny_unknown_fields,
+            } => {
+                render_doc(out, "", description.as_deref());
+                out.push_str(
+                    "#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]\n",
+                );
+                // an untagged union picks the first variant that deserializes, so the variants
+                // must reject the properties of the others
+                if *deny_unknown_fields
+                    || (additional_properties.is_none() && self.is_union_variant(name))
+                {
+                    out.push_str("#[serde(deny_unknown_fields)]\n");
+                }
+                let _ = writeln!(out, "pub struct {name} {{");
+                for field in fields {
+                    render_doc(out, "    ", field.description.as_deref());
+                    let mut attributes = Vec::new();
+                    if unraw(&field.ident) != field.json_name {
+                        attributes.push(format!("rename = {:?}", field.json_name));
+                    }
+                    let ty = if field.required {
+                        field.ty.clone()
+                    } else {
+                        attributes.push(String::from(
+                            "default, skip_serializing_if = \"Option::is_none\"",
+                        ));
+                        field.ty.clone().option()
+                    };
+                    if !attributes.is_empty() {
+                        let _ = writeln!(out, "    #[serde({})]", attributes.join(", "));
+                    }
+                    let _ = writeln!(
+                        out,
+                        "    pub {}: {},",
+                        field.ident,
+                        self.render_type(name, &ty)
+                    );
+                }
+                if let Some(additional) = additional_properties {
+                    out.push_str("    #[serde(flatten)]\n");
+                    let _ = writeln!(
+                        out,
+                        "    pub additional_properties: std::collections::HashMap<String, {additional}>,"
+                    );
+                }
+                out.push_str("}\n");
+            }
+            TypeDef::StringEnum {
+                name,
+                description,
+                variants,
+            } => {
+                render_doc(out, "", description.as_deref());
+                out.push_str("#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]\n");
+                let _ = writeln!(out, "pub enum {name} {{");
+                for (ident, value) in variants {
+                    let _ = writeln!(out, "    #[serde(rename = {value:?})]");
+                    let _ = writeln!(out, "    {ident},");
+                }
+                out.push_str("}\n");
+            }
+            TypeDef::Union {
+                name,
+                description,
+                variants,
+            } => {
+                render_doc(out, "", description.as_deref());
+                out.push_str(
+                    "#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]\n",
+                );
+                out.push_str("#[serde(untagged)]\n");
+                let _ = writeln!(out, "pub enum {name} {{");
+                for variant in variants {
+                    let _ = writeln!(
+                        out,
+                        "    {}({}),",
+                        variant.ident,
+                        self.render_type(name, &variant.ty)
+                    );
+                }
+                out.push_str("}\n");
+            }
+            TypeDef::Alias {
+                name,
+                description,
+                target,
+            } => {
+                render_doc(out, "", description.as_deref());
+                let _ = writeln!(out, "pub type {name} = {};", self.render_type(name, target));
+            }
+        }
+    }
+}
+
+/// Generates the Rust types of the component schemas and of the inline message payloads of a
+/// specification.
+pub fn generate_types(spec: &AsyncApiV3Spec) -> Result<String, Error> {
+    let mut generator = TypeGenerator::new(&spec.components);
+    generator.generate_components()?;
+    generator.generate_messages(spec)?;
+    Ok(format!("{}{}", generated_header(spec), generator.render()))
+}
+
+pub(crate) fn generated_header(spec: &AsyncApiV3Spec) -> String {
+    format!(
+        "// @generated by asyncapiv3 from the specification '{}' {}, do not edit.\n",
+        spec.info.title, spec.info.version
+    )
+}
+
+/// The JSON schema of a multi format schema, converted by the
+/// [default registry](crate::schema_format::registry::SchemaFormatRegistry), or an empty (any
+/// value) schema if it cannot be converted.
+fn multi_format_value(multi_format: &MultiFormatSchema) -> Value {
+    match multi_format.to_json_schema() {
+        Ok(schema) => inline_definitions(schema),
+        Err(_) => Value::Object(Map::new()),
+    }
+}
+
+fn naming_hint(naming: &Naming) -> &str {
+    match naming {
+        Naming::Exact(name) | Naming::Hint(name) => name,
+    }
+}
+
+fn variant_name(branch: &Value) -> Option<String> {
+    if let Some(title) = branch.get("title").and_then(Value::as_str) {
+        return Some(String::from(title));
+    }
+    if let Some(reference) = branch.get("$ref").and_then(Value::as_str) {
+        return reference.rsplit('/').next().map(String::from);
+    }
+    let type_name = match branch.get("type").and_then(Value::as_str)? {
+        "string" => "String",
+        "integer" => "Integer",
+        "number" => "Number",
+        "boolean" => "Boolean",
+        "array" => "Array",
+        _ => return None,
+    };
+    Some(String::from(type_name))
+}
+
+fn json_value_type(value: &Value) -> RustType {
+    match value {
+        Value::Null => RustType::Primitive("()"),
+        Value::Bool(_) => RustType::Primitive("bool"),
+        Value::Number(number) if number.is_f64() => RustType::Primitive("f64"),
+        Value::Number(_) => RustType::Primitive("i64"),
+        Value::String(_) => RustType::Primitive("String"),
+        Value::Array(_) | Value::Object(_) => RustType::Any,
+    }
+}
+
+fn description(schema: &Value) -> Option<String> {
+    schema.as_object().and_then(description_of)
+}
+
+fn description_of(object: &Map<String, Value>) -> Option<String> {
+    object
+        .get("description")
+        .or_else(|| object.get("title"))
+        .and_then(Value::as_str)
+        .map(String::from)
+}
+
+fn schema_description(
+    schema: &RefOr<Either<schemars::Schema, MultiFormatSchema>>,
+) -> Option<String> {
+    match schema {
+        RefOr::Right(Either::Left(schema)) => description(schema.as_value()),
+        _ => None,
+    }
+}
+
+pub(crate) fn render_doc(out: &mut String, indent: &str, description: Option<&str>) {
+    for line in description.into_iter().flat_map(str::lines) {
+        let line = line.trim_end();
+        if line.is_empty() {
+            let _ = writeln!(out, "{indent}///");
+        } else {
+            let _ = writeln!(out, "{indent}/// {line}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::spec::AsyncApiSpec;
+
+    fn generate(spec: &str) -> String {
+        let AsyncApiSpec::V3_0_0(spec) = serde_yaml::from_str(spec).unwrap();
+        generate_types(&spec).unwrap()
+    }
+
+    #[test]
+    fn generates_unions_from_one_of() {
+        let code = generate(include_str!("../../test-res/3.0.0/oneof-asyncapi.yml"));
+        assert!(code.contains("#[serde(deny_unknown_fields)]\npub struct ObjectWithKey {\n    #[serde(default, skip_serializing_if = \"Option::is_none\")]\n    pub key: Option<String>,\n}"));
+        assert!(code.contains("#[serde(untagged)]\npub enum TestMessagesPayload {\n    ObjectWithKey(ObjectWithKey),\n    ObjectWithKey2(ObjectWithKey2),\n}"));
+    }
+
+    #[test]
+    fn generates_structs_enums_and_boxes_cycles() {
+        let code = generate(
+            r#"
+asyncapi: 3.0.0
+info:
+  title: Tree
+  version: 1.0.0
+components:
+  schemas:
+    node:
+      type: object
+      description: A tree node.
+      required: [id, kind]
+      additionalProperties:
+        type: integer
+      properties:
+        id:
+          type: string
+        kind:
+          type: string
+          enum: [leaf, inner-node]
+        parent:
+          $ref: '#/components/schemas/node'
+        children:
+          type: array
+          items:
+            $ref: '#/components/schemas/node'
+        createdAt:
+          type: [string, 'null']
+"#,
+        );
+        assert!(code.contains("/// A tree node.\n"));
+        assert!(code.contains("    pub kind: NodeKind,\n"));
+        assert!(code.contains("    pub parent: Option<Box<Node>>,\n"));
+        assert!(code.contains("    pub children: Option<Vec<Node>>,\n"));
+        assert!(code.contains("    #[serde(rename = \"createdAt\", default, skip_serializing_if = \"Option::is_none\")]\n    pub created_at: Option<String>,\n"));
+        assert!(code
+            .contains("    pub additional_properties: std::collections::HashMap<String, i64>,\n"));
+        assert!(code.contains("    #[serde(rename = \"inner-node\")]\n    InnerNode,\n"));
+    }
+
+    #[test]
+    fn generates_structs_from_multi_format_payloads() {
+        let code = generate(
+            r#"
+asyncapi: 3.0.0
+info:
+  title: Orders
+  version: 1.0.0
+channels:
+  orders:
+    address: orders
+    messages:
+      orderPlaced:
+        payload:
+          schemaFormat: application/vnd.google.protobuf;version=3
+          schema: |
+            syntax = "proto3";
+            message OrderPlaced {
+              string order_id = 1;
+              Line line = 2;
+            }
+            message Line {
+              string sku = 1;
+            }
+      orderShipped:
+        payload:
+          schemaFormat: application/vnd.apache.avro;version=1.9.0
+          schema:
+            type: record
+            name: OrderShipped
+            fields:
+              - name: carrier
+                type: string
+"#,
+        );
+        assert!(code.contains("pub struct OrderPlacedPayload {\n"));
+        assert!(code.contains("    #[serde(rename = \"orderId\", default, skip_serializing_if = \"Option::is_none\")]\n    pub order_id: Option<String>,\n"));
+        assert!(code.contains("    pub line: Option<OrderPlacedPayloadLine>,\n"));
+        assert!(code.contains("pub struct OrderShippedPayload {\n    pub carrier: String,\n}"));
+    }
+}
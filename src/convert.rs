@@ -0,0 +1,551 @@
+//! Conversion of AsyncAPI 2 documents to AsyncAPI 3.
+//!
+//! The operations of the channels become root operations: a `publish` operation, used by the
+//! clients to send messages, is received by the application, and a `subscribe` operation is sent.
+//! Their messages are moved to the messages of the channels, and the channels are identified by
+//! names derived from their addresses. Server URLs are split into hosts and pathnames, and the
+//! security requirements become references to the security schemes.
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use crate::pointer::json_pointer;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Unsupported AsyncAPI version {version}, expected 2.x")]
+    UnsupportedVersion { version: String },
+    #[error("Invalid AsyncAPI 2 document at {pointer:?}: {reason}")]
+    Invalid { pointer: String, reason: String },
+    #[error("AsyncAPI 2 document at {pointer:?} cannot be converted: {reason}")]
+    Unsupported { pointer: String, reason: String },
+}
+
+/// Converts an AsyncAPI 2 document to an AsyncAPI 3 document. AsyncAPI 3 documents are returned
+/// as is.
+pub fn v2_to_v3(document: &Value) -> Result<Value, Error> {
+    let Some(root) = document.as_object() else {
+        return Err(invalid("", "expected an object"));
+    };
+    match root.get("asyncapi") {
+        Some(Value::String(version)) if version.starts_with("2.") => {}
+        Some(Value::String(version)) if version.starts_with("3.") => return Ok(document.clone()),
+        Some(version) => {
+            return Err(Error::UnsupportedVersion {
+                version: version.as_str().map_or(version.to_string(), String::from),
+            })
+        }
+        None => return Err(invalid("", "missing field `asyncapi`")),
+    }
+
+    let mut converted = Map::new();
+    converted.insert(String::from("asyncapi"), Value::from("3.0.0"));
+    copy(root, &mut converted, &["id"]);
+
+    let mut info = root
+        .get("info")
+        .and_then(Value::as_object)
+        .cloned()
+        .ok_or_else(|| invalid("/info", "expected an object"))?;
+    copy(root, &mut info, &["tags", "externalDocs"]);
+    converted.insert(String::from("info"), Value::Object(info));
+
+    if let Some(servers) = root.get("servers") {
+        converted.insert(String::from("servers"), servers_v3("/servers", servers)?);
+    }
+    copy(root, &mut converted, &["defaultContentType"]);
+
+    let mut channels = Map::new();
+    let mut operations = Map::new();
+    let v2_channels = match root.get("channels") {
+        Some(Value::Object(channels)) => channels.iter().collect(),
+        Some(_) => return Err(invalid("/channels", "expected an object")),
+        None => vec![],
+    };
+    for (address, channel) in v2_channels {
+        let pointer = json_pointer(&["channels", address]);
+        let id = unique(&channels, &channel_id(address));
+        let channel = channel
+            .as_object()
+            .ok_or_else(|| invalid(&pointer, "expected an object"))?;
+        let (channel, channel_operations) = channel_v3(&pointer, &id, address, channel)?;
+        channels.insert(id, Value::Object(channel));
+        for (id, operation) in channel_operations {
+            let id = unique(&operations, &id);
+            operations.insert(id, Value::Object(operation));
+        }
+    }
+    converted.insert(String::from("channels"), Value::Object(channels));
+    converted.insert(String::from("operations"), Value::Object(operations));
+
+    if let Some(components) = root.get("components") {
+        converted.insert(
+            String::from("components"),
+            components_v3("/components", components)?,
+        );
+    }
+    Ok(Value::Object(converted))
+}
+
+fn invalid(pointer: &str, reason: &str) -> Error {
+    Error::Invalid {
+        pointer: String::from(pointer),
+        reason: String::from(reason),
+    }
+}
+
+fn copy(from: &Map<String, Value>, to: &mut Map<String, Value>, keys: &[&str]) {
+    for key in keys {
+        if let Some(value) = from.get(*key) {
+            to.insert(String::from(*key), value.clone());
+        }
+    }
+}
+
+fn reference(pointer: &str) -> Value {
+    serde_json::json!({ "$ref": format!("#{pointer}") })
+}
+
+/// Name of a channel: the lower camel case words of its address.
+fn channel_id(address: &str) -> String {
+    let mut id = String::new();
+    for word in address
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+    {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            if id.is_empty() {
+                id.push(first.to_ascii_lowercase());
+            } else {
+                id.push(first.to_ascii_uppercase());
+            }
+            id.extend(chars);
+        }
+    }
+    if id.is_empty() {
+        String::from("root")
+    } else {
+        id
+    }
+}
+
+/// `name`, suffixed by a number when already used in `map`.
+fn unique(map: &Map<String, Value>, name: &str) -> String {
+    (1..)
+        .map(|index| match index {
+            1 => String::from(name),
+            index => format!("{name}{index}"),
+        })
+        .find(|name| !map.contains_key(name))
+        .unwrap_or_default()
+}
+
+fn servers_v3(pointer: &str, servers: &Value) -> Result<Value, Error> {
+    let servers = servers
+        .as_object()
+        .ok_or_else(|| invalid(pointer, "expected an object"))?;
+    let mut converted = Map::new();
+    for (name, server) in servers {
+        let server_pointer = format!("{pointer}{}", json_pointer(&[name]));
+        let Some(server) = server.as_object() else {
+            return Err(invalid(&server_pointer, "expected an object"));
+        };
+        if server.contains_key("$ref") {
+            converted.insert(name.clone(), Value::Object(server.clone()));
+            continue;
+        }
+        let mut v3 = Map::new();
+        let url = server
+            .get("url")
+            .and_then(Value::as_str)
+            .ok_or_else(|| invalid(&server_pointer, "missing field `url`"))?;
+        let url = url.split_once("://").map_or(url, |(_, url)| url);
+        let (host, pathname) = match url.find('/') {
+            Some(index) => (&url[..index], Some(&url[index..])),
+            None => (url, None),
+        };
+        v3.insert(String::from("host"), Value::from(host));
+        copy(server, &mut v3, &["protocol", "protocolVersion"]);
+        if let Some(pathname) = pathname.filter(|pathname| *pathname != "/") {
+            v3.insert(String::from("pathname"), Value::from(pathname));
+        }
+        copy(server, &mut v3, &["description", "variables"]);
+        if let Some(security) = security_v3(server) {
+            v3.insert(String::from("security"), security);
+        }
+        copy(server, &mut v3, &["tags", "bindings"]);
+        converted.insert(name.clone(), Value::Object(v3));
+    }
+    Ok(Value::Object(converted))
+}
+
+/// References to the schemes of the security requirements of `object`.
+fn security_v3(object: &Map<String, Value>) -> Option<Value> {
+    let requirements = object.get("security")?.as_array()?;
+    let references = requirements
+        .iter()
+        .filter_map(Value::as_object)
+        .flat_map(|requirement| requirement.keys())
+        .map(|scheme| reference(&json_pointer(&["components", "securitySchemes", scheme])))
+        .collect();
+    Some(Value::Array(references))
+}
+
+type Operations = Vec<(String, Map<String, Value>)>;
+
+fn channel_v3(
+    pointer: &str,
+    id: &str,
+    address: &str,
+    channel: &Map<String, Value>,
+) -> Result<(Map<String, Value>, Operations), Error> {
+    let mut v3 = Map::new();
+    v3.insert(String::from("address"), Value::from(address));
+    let mut messages = Map::new();
+    let mut operations = vec![];
+    for (kind, action) in [("publish", "receive"), ("subscribe", "send")] {
+        let Some(operation) = channel.get(kind) else {
+            continue;
+        };
+        let operation_pointer = format!("{pointer}/{kind}");
+        let operation = operation
+            .as_object()
+            .ok_or_else(|| invalid(&operation_pointer, "expected an object"))?;
+        let operation_id = match operation.get("operationId").and_then(Value::as_str) {
+            Some(operation_id) => String::from(operation_id),
+            None => format!("{id}{}{}", kind[..1].to_ascii_uppercase(), &kind[1..]),
+        };
+
+        let mut v3_operation = Map::new();
+        v3_operation.insert(String::from("action"), Value::from(action));
+        v3_operation.insert(
+            String::from("channel"),
+            reference(&json_pointer(&["channels", id])),
+        );
+        copy(operation, &mut v3_operation, &["summary", "description"]);
+        if let Some(security) = security_v3(operation) {
+            v3_operation.insert(String::from("security"), security);
+        }
+        copy(
+            operation,
+            &mut v3_operation,
+            &["tags", "externalDocs", "bindings", "traits"],
+        );
+
+        let operation_messages = match operation.get("message") {
+            Some(Value::Object(message)) => match message.get("oneOf") {
+                Some(Value::Array(messages)) => messages.clone(),
+                _ => vec![Value::Object(message.clone())],
+            },
+            Some(_) => return Err(invalid(&operation_pointer, "invalid `message`")),
+            None => vec![],
+        };
+        let mut references = vec![];
+        for (index, message) in operation_messages.iter().enumerate() {
+            let name = message_id(message).unwrap_or_else(|| match operation_messages.len() {
+                1 => format!("{operation_id}Message"),
+                _ => format!("{operation_id}Message{}", index + 1),
+            });
+            let name = match messages.get(&name) {
+                Some(existing) if existing == &message_v3(message) => name,
+                _ => unique(&messages, &name),
+            };
+            messages.insert(name.clone(), message_v3(message));
+            references.push(reference(&json_pointer(&[
+                "channels", id, "messages", &name,
+            ])));
+        }
+        v3_operation.insert(String::from("messages"), Value::Array(references));
+        operations.push((operation_id, v3_operation));
+    }
+    v3.insert(String::from("messages"), Value::Object(messages));
+    copy(channel, &mut v3, &["description"]);
+    if let Some(servers) = channel.get("servers").and_then(Value::as_array) {
+        let references = servers
+            .iter()
+            .filter_map(Value::as_str)
+            .map(|server| reference(&json_pointer(&["servers", server])))
+            .collect();
+        v3.insert(String::from("servers"), Value::Array(references));
+    }
+    if let Some(Value::Object(parameters)) = channel.get("parameters") {
+        let parameters = parameters
+            .iter()
+            .map(|(name, parameter)| (name.clone(), parameter_v3(parameter)))
+            .collect();
+        v3.insert(String::from("parameters"), Value::Object(parameters));
+    }
+    copy(channel, &mut v3, &["bindings"]);
+    Ok((v3, operations))
+}
+
+/// Name of a message given by its id, name or component reference.
+fn message_id(message: &Value) -> Option<String> {
+    let message = message.as_object()?;
+    if let Some(reference) = message.get("$ref").and_then(Value::as_str) {
+        return reference
+            .rsplit_once('/')
+            .map(|(_, name)| name.replace("~1", "/").replace("~0", "~"));
+    }
+    ["messageId", "name"]
+        .iter()
+        .find_map(|key| message.get(*key)?.as_str())
+        .map(String::from)
+}
+
+fn message_v3(message: &Value) -> Value {
+    let Some(message) = message.as_object() else {
+        return message.clone();
+    };
+    let mut v3 = message.clone();
+    v3.shift_remove("messageId");
+    if let Some(schema_format) = v3.shift_remove("schemaFormat") {
+        if let Some(payload) = v3.get_mut("payload") {
+            *payload = serde_json::json!({
+                "schemaFormat": schema_format,
+                "schema": payload.take(),
+            });
+        }
+    }
+    Value::Object(v3)
+}
+
+/// Parameter without its schema, whose enumeration, default and examples are kept.
+fn parameter_v3(parameter: &Value) -> Value {
+    let Some(parameter) = parameter.as_object() else {
+        return parameter.clone();
+    };
+    if parameter.contains_key("$ref") {
+        return Value::Object(parameter.clone());
+    }
+    let mut v3 = Map::new();
+    if let Some(Value::Object(schema)) = parameter.get("schema") {
+        let strings = |value: &Value| -> Option<Value> {
+            let values = value.as_array()?.iter().map(string).collect::<Vec<_>>();
+            Some(Value::Array(values))
+        };
+        if let Some(values) = schema.get("enum").and_then(strings) {
+            v3.insert(String::from("enum"), values);
+        }
+        if let Some(default) = schema.get("default") {
+            v3.insert(String::from("default"), string(default));
+        }
+        if let Some(examples) = schema.get("examples").and_then(strings) {
+            v3.insert(String::from("examples"), examples);
+        }
+    }
+    copy(parameter, &mut v3, &["description", "location"]);
+    Value::Object(v3)
+}
+
+fn string(value: &Value) -> Value {
+    match value {
+        Value::String(_) => value.clone(),
+        value => Value::from(value.to_string()),
+    }
+}
+
+fn components_v3(pointer: &str, components: &Value) -> Result<Value, Error> {
+    let components = components
+        .as_object()
+        .ok_or_else(|| invalid(pointer, "expected an object"))?;
+    let mut v3 = components.clone();
+    if let Some(servers) = components.get("servers") {
+        let servers = servers_v3(&format!("{pointer}/servers"), servers)?;
+        v3.insert(String::from("servers"), servers);
+    }
+    // channels cannot be converted without their operations, which would be root operations
+    match v3.shift_remove("channels") {
+        Some(Value::Object(channels)) if channels.is_empty() => {}
+        Some(_) => {
+            return Err(Error::Unsupported {
+                pointer: format!("{pointer}/channels"),
+                reason: String::from(
+                    "the channels of the components have no AsyncAPI 3 equivalent",
+                ),
+            })
+        }
+        None => {}
+    }
+    for section in ["messages", "messageTraits"] {
+        if let Some(Value::Object(messages)) = v3.get_mut(section) {
+            messages
+                .values_mut()
+                .for_each(|message| *message = message_v3(message));
+        }
+    }
+    if let Some(Value::Object(parameters)) = v3.get_mut("parameters") {
+        parameters
+            .values_mut()
+            .for_each(|parameter| *parameter = parameter_v3(parameter));
+    }
+    if let Some(Value::Object(schemes)) = v3.get_mut("securitySchemes") {
+        let flows = schemes
+            .values_mut()
+            .filter_map(|scheme| scheme.get_mut("flows")?.as_object_mut())
+            .flat_map(|flows| flows.values_mut())
+            .filter_map(Value::as_object_mut);
+        for flow in flows {
+            if let Some(scopes) = flow.shift_remove("scopes") {
+                flow.insert(String::from("availableScopes"), scopes);
+            }
+        }
+    }
+    Ok(Value::Object(v3))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::spec::AsyncApiSpec;
+
+    #[test]
+    fn convert_streetlights() {
+        let document: Value = serde_yaml::from_str(
+            "\
+asyncapi: 2.6.0
+info:
+  title: Streetlights
+  version: 1.0.0
+tags:
+  - name: lights
+servers:
+  production:
+    url: mqtt://test.mosquitto.org:1883/lights
+    protocol: mqtt
+    security:
+      - apiKey: []
+channels:
+  smartylighting/streetlights/1/0/event/{streetlightId}/lighting/measured:
+    parameters:
+      streetlightId:
+        description: The ID of the streetlight.
+        schema:
+          type: string
+          enum: [a, b]
+    subscribe:
+      operationId: receiveLightMeasurement
+      message:
+        $ref: '#/components/messages/lightMeasured'
+    publish:
+      message:
+        oneOf:
+          - messageId: turnOn
+            payload: { type: object }
+          - name: turnOff
+            schemaFormat: application/vnd.aai.asyncapi+json;version=2.6.0
+            payload: { type: object }
+components:
+  messages:
+    lightMeasured:
+      messageId: lightMeasured
+      payload: { type: object }
+  securitySchemes:
+    apiKey:
+      type: apiKey
+      in: user
+    oauth:
+      type: oauth2
+      flows:
+        implicit:
+          authorizationUrl: https://example.com/auth
+          scopes:
+            lights: Control the lights
+",
+        )
+        .unwrap();
+        let converted = v2_to_v3(&document).unwrap();
+        let expected: Value = serde_yaml::from_str(
+            "\
+asyncapi: 3.0.0
+info:
+  title: Streetlights
+  version: 1.0.0
+  tags:
+    - name: lights
+servers:
+  production:
+    host: test.mosquitto.org:1883
+    protocol: mqtt
+    pathname: /lights
+    security:
+      - $ref: '#/components/securitySchemes/apiKey'
+channels:
+  smartylightingStreetlights10EventStreetlightIdLightingMeasured:
+    address: smartylighting/streetlights/1/0/event/{streetlightId}/lighting/measured
+    messages:
+      turnOn:
+        payload: { type: object }
+      turnOff:
+        name: turnOff
+        payload:
+          schemaFormat: application/vnd.aai.asyncapi+json;version=2.6.0
+          schema: { type: object }
+      lightMeasured:
+        $ref: '#/components/messages/lightMeasured'
+    parameters:
+      streetlightId:
+        enum: [a, b]
+        description: The ID of the streetlight.
+operations:
+  smartylightingStreetlights10EventStreetlightIdLightingMeasuredPublish:
+    action: receive
+    channel:
+      $ref: '#/channels/smartylightingStreetlights10EventStreetlightIdLightingMeasured'
+    messages:
+      - $ref: '#/channels/smartylightingStreetlights10EventStreetlightIdLightingMeasured/messages/turnOn'
+      - $ref: '#/channels/smartylightingStreetlights10EventStreetlightIdLightingMeasured/messages/turnOff'
+  receiveLightMeasurement:
+    action: send
+    channel:
+      $ref: '#/channels/smartylightingStreetlights10EventStreetlightIdLightingMeasured'
+    messages:
+      - $ref: '#/channels/smartylightingStreetlights10EventStreetlightIdLightingMeasured/messages/lightMeasured'
+components:
+  messages:
+    lightMeasured:
+      payload: { type: object }
+  securitySchemes:
+    apiKey:
+      type: apiKey
+      in: user
+    oauth:
+      type: oauth2
+      flows:
+        implicit:
+          authorizationUrl: https://example.com/auth
+          availableScopes:
+            lights: Control the lights
+",
+        )
+        .unwrap();
+        assert_eq!(converted, expected);
+        serde_json::from_value::<AsyncApiSpec>(converted).unwrap();
+    }
+
+    #[test]
+    fn reject_other_versions() {
+        let document = serde_json::json!({ "asyncapi": "1.2.0" });
+        let err = v2_to_v3(&document).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unsupported AsyncAPI version 1.2.0, expected 2.x"
+        );
+        let document = serde_json::json!({ "asyncapi": "3.0.0", "info": {} });
+        assert_eq!(v2_to_v3(&document).unwrap(), document);
+    }
+
+    #[test]
+    fn reject_component_channels() {
+        let document = serde_json::json!({
+            "asyncapi": "2.6.0",
+            "info": { "title": "Lights", "version": "1.0.0" },
+            "components": { "channels": { "lights": { "subscribe": {} } } },
+        });
+        let err = v2_to_v3(&document).unwrap_err();
+        assert!(
+            matches!(&err, Error::Unsupported { pointer, .. } if pointer == "/components/channels"),
+            "{err}"
+        );
+    }
+}
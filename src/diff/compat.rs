@@ -0,0 +1,340 @@
+//! Compatibility of JSON schemas across versions, following the modes of schema registries.
+//!
+//! With the `Backward` mode, readers using the new schema can read the data written with the
+//! previous one: the new schema must accept every value the previous one accepted. With the
+//! `Forward` mode, readers using the previous schema can read the data written with the new one.
+//! `Full` combines both. The transitive modes check the new schema against all the previous
+//! versions instead of the latest one.
+use std::collections::BTreeSet;
+
+use serde_json::Value;
+
+use super::schema::{self, Direction};
+use super::{messages, resolve_message, schema_value};
+use crate::pointer::json_pointer;
+use crate::spec::AsyncApiV3Spec;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CompatibilityMode {
+    Backward,
+    BackwardTransitive,
+    Forward,
+    ForwardTransitive,
+    Full,
+    FullTransitive,
+}
+
+impl CompatibilityMode {
+    fn is_transitive(self) -> bool {
+        matches!(
+            self,
+            Self::BackwardTransitive | Self::ForwardTransitive | Self::FullTransitive
+        )
+    }
+
+    fn backward(self) -> bool {
+        matches!(
+            self,
+            Self::Backward | Self::BackwardTransitive | Self::Full | Self::FullTransitive
+        )
+    }
+
+    fn forward(self) -> bool {
+        matches!(
+            self,
+            Self::Forward | Self::ForwardTransitive | Self::Full | Self::FullTransitive
+        )
+    }
+}
+
+/// Change making a schema incompatible with a previous version.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct Incompatibility {
+    /// Index of the incompatible version in the previous versions.
+    pub version: usize,
+    /// JSON pointer to the changed schema.
+    pub pointer: String,
+    pub explanation: String,
+}
+
+/// Checks that `new` is compatible with the `previous` versions of a schema, from the oldest to
+/// the latest.
+pub fn check_schema(
+    mode: CompatibilityMode,
+    new: &Value,
+    previous: &[Value],
+) -> Vec<Incompatibility> {
+    versions(mode, previous)
+        .flat_map(|(version, old)| {
+            schema_incompatibilities(mode, version, "", Some(old), Some(new))
+        })
+        .collect()
+}
+
+/// Checks that the message payloads and component schemas of `new` are compatible with the
+/// `previous` versions of the specification, from the oldest to the latest. Schemas missing
+/// from a version are not compared.
+pub fn check_spec(
+    mode: CompatibilityMode,
+    new: &AsyncApiV3Spec,
+    previous: &[AsyncApiV3Spec],
+) -> Vec<Incompatibility> {
+    let mut incompatibilities = vec![];
+    for (version, old) in versions(mode, previous) {
+        let new_messages = messages(new);
+        for (pointer, old_message) in messages(old) {
+            let Some(new_message) = new_messages.get(&pointer) else {
+                continue;
+            };
+            // references are followed, but component messages are also compared on their own
+            let (Some(old_message), Some(new_message)) = (
+                resolve_message(old, old_message),
+                resolve_message(new, new_message),
+            ) else {
+                continue;
+            };
+            incompatibilities.extend(schema_incompatibilities(
+                mode,
+                version,
+                &format!("{pointer}/payload"),
+                schema_value(old_message.payload.as_ref()).as_ref(),
+                schema_value(new_message.payload.as_ref()).as_ref(),
+            ));
+        }
+
+        let names = old
+            .components
+            .schemas
+            .keys()
+            .filter(|name| new.components.schemas.contains_key(*name))
+            .collect::<BTreeSet<_>>();
+        for name in names {
+            incompatibilities.extend(schema_incompatibilities(
+                mode,
+                version,
+                &json_pointer(&["components", "schemas", name]),
+                schema_value(old.components.schemas.get(name)).as_ref(),
+                schema_value(new.components.schemas.get(name)).as_ref(),
+            ));
+        }
+    }
+    incompatibilities.sort_by(|a, b| (a.version, &a.pointer).cmp(&(b.version, &b.pointer)));
+    incompatibilities
+}
+
+/// Previous versions to check with their index: all of them with a transitive mode, or the
+/// latest one.
+fn versions<T>(mode: CompatibilityMode, previous: &[T]) -> impl Iterator<Item = (usize, &T)> {
+    let skipped = if mode.is_transitive() {
+        0
+    } else {
+        previous.len().saturating_sub(1)
+    };
+    previous.iter().enumerate().skip(skipped)
+}
+
+fn schema_incompatibilities(
+    mode: CompatibilityMode,
+    version: usize,
+    pointer: &str,
+    old: Option<&Value>,
+    new: Option<&Value>,
+) -> Vec<Incompatibility> {
+    // a missing schema accepts any value
+    let any = Value::Object(Default::default());
+    let mut changes = vec![];
+    schema::compare(old.unwrap_or(&any), new.unwrap_or(&any), "", &mut changes);
+    changes
+        .into_iter()
+        .filter_map(|change| {
+            let explanation = match change.direction {
+                Direction::Tightening if mode.backward() => format!(
+                    "{}: data written with the previous schema may be rejected",
+                    change.description
+                ),
+                Direction::Loosening if mode.forward() => format!(
+                    "{}: data written with the new schema may be rejected by the previous schema",
+                    change.description
+                ),
+                Direction::Unknown => {
+                    format!("{}: compatibility cannot be determined", change.description)
+                }
+                _ => return None,
+            };
+            Some(Incompatibility {
+                version,
+                pointer: format!("{pointer}{}", change.pointer),
+                explanation,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    fn pointers(incompatibilities: Vec<Incompatibility>) -> Vec<String> {
+        incompatibilities
+            .into_iter()
+            .map(|incompatibility| incompatibility.pointer)
+            .collect()
+    }
+
+    #[test]
+    fn check_modes() {
+        let old = json!({
+            "type": "object",
+            "properties": {
+                "id": { "type": "integer" },
+                "state": { "type": "string", "enum": ["on", "off"] }
+            }
+        });
+        let new = json!({
+            "type": "object",
+            "properties": {
+                "id": { "type": "number" },
+                "state": { "type": "string", "enum": ["on", "off", "dimmed"] }
+            },
+            "required": ["id"],
+            "additionalProperties": false
+        });
+
+        let backward = check_schema(
+            CompatibilityMode::Backward,
+            &new,
+            std::slice::from_ref(&old),
+        );
+        assert_eq!(
+            backward
+                .iter()
+                .map(|incompatibility| incompatibility.explanation.as_str())
+                .collect::<Vec<_>>(),
+            [
+                "property 'id' became required: data written with the previous schema may be rejected",
+                "additional properties no longer allowed: data written with the previous schema may be rejected",
+            ]
+        );
+        assert_eq!(
+            pointers(check_schema(
+                CompatibilityMode::Forward,
+                &new,
+                std::slice::from_ref(&old)
+            )),
+            ["/properties/id", "/properties/state"]
+        );
+        assert_eq!(check_schema(CompatibilityMode::Full, &new, &[old]).len(), 4);
+    }
+
+    #[test]
+    fn check_one_of_branches() {
+        let old = json!({ "oneOf": [{ "type": "string" }, { "type": "integer" }] });
+        let new = json!({ "oneOf": [{ "type": "integer" }] });
+
+        let incompatibilities = check_schema(
+            CompatibilityMode::Backward,
+            &new,
+            std::slice::from_ref(&old),
+        );
+        assert_eq!(
+            incompatibilities,
+            [Incompatibility {
+                version: 0,
+                pointer: String::new(),
+                explanation: String::from(
+                    "branch 0 of 'oneOf' removed: data written with the previous schema may be rejected"
+                ),
+            }]
+        );
+        assert_eq!(check_schema(CompatibilityMode::Forward, &new, &[old]), []);
+    }
+
+    #[test]
+    fn check_transitively() {
+        let v1 = json!({ "type": "object", "required": ["id"] });
+        let v2 = json!({ "type": "object" });
+        let v3 = json!({ "type": "object", "required": ["id"] });
+
+        let previous = [v1, v2];
+        assert_eq!(
+            check_schema(CompatibilityMode::Backward, &v3, &previous).len(),
+            1
+        );
+        assert_eq!(check_schema(CompatibilityMode::Forward, &v3, &previous), []);
+        let transitive = check_schema(CompatibilityMode::ForwardTransitive, &v3, &previous);
+        assert_eq!(transitive, []);
+        let transitive = check_schema(CompatibilityMode::FullTransitive, &v3, &previous);
+        assert_eq!(
+            transitive
+                .iter()
+                .map(|incompatibility| incompatibility.version)
+                .collect::<Vec<_>>(),
+            [1]
+        );
+    }
+
+    #[test]
+    fn check_specification_payloads() {
+        let spec = |payload: Value| -> AsyncApiV3Spec {
+            serde_json::from_value(json!({
+                "info": { "title": "Lights", "version": "1.0.0" },
+                "channels": {
+                    "lightMeasured": {
+                        "messages": { "lightMeasured": { "payload": payload } }
+                    }
+                }
+            }))
+            .unwrap()
+        };
+        let old =
+            spec(json!({ "type": "object", "properties": { "lumens": { "type": "integer" } } }));
+        let new =
+            spec(json!({ "type": "object", "properties": { "lumens": { "type": "string" } } }));
+
+        let incompatibilities = check_spec(CompatibilityMode::Full, &new, &[old]);
+        assert_eq!(
+            pointers(incompatibilities),
+            [
+                "/channels/lightMeasured/messages/lightMeasured/payload/properties/lumens",
+                "/channels/lightMeasured/messages/lightMeasured/payload/properties/lumens",
+            ]
+        );
+    }
+
+    #[cfg(feature = "schema-format")]
+    #[test]
+    fn check_multi_format_payloads() {
+        let spec = |lumens: &str| -> AsyncApiV3Spec {
+            serde_json::from_value(json!({
+                "info": { "title": "Lights", "version": "1.0.0" },
+                "channels": {
+                    "lightMeasured": {
+                        "messages": {
+                            "lightMeasured": {
+                                "payload": {
+                                    "schemaFormat": "application/vnd.apache.avro;version=1.9.0",
+                                    "schema": {
+                                        "type": "record",
+                                        "name": "LightMeasured",
+                                        "fields": [{ "name": "lumens", "type": lumens }]
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }))
+            .unwrap()
+        };
+
+        let incompatibilities =
+            check_spec(CompatibilityMode::Backward, &spec("string"), &[spec("int")]);
+        let mut pointers = pointers(incompatibilities);
+        pointers.dedup();
+        assert_eq!(
+            pointers,
+            ["/channels/lightMeasured/messages/lightMeasured/payload/properties/lumens"]
+        );
+    }
+}
@@ -0,0 +1,859 @@
+//! Comparison of two versions of a specification.
+//!
+//! Each change is classified according to its impact on the clients of the application: a change
+//! to a message received by the application breaks the clients sending it if it rejects values
+//! that were valid, while a change to a message sent by the application breaks the clients
+//! receiving it if it allows values that were invalid.
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::{Display, Formatter};
+
+use indexmap::IndexMap;
+use serde_json::Value;
+
+use crate::pointer::json_pointer;
+use crate::spec::common::{Either, RefOr, ReferenceObject};
+use crate::spec::message::{Message, MultiFormatSchema};
+use crate::spec::operation::OperationAction;
+use crate::spec::AsyncApiV3Spec;
+
+pub mod compat;
+mod schema;
+
+use schema::{Direction, SchemaChange};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Compatibility {
+    /// Existing clients may stop working.
+    Breaking,
+    NonBreaking,
+    /// The impact of the change cannot be determined, e.g. a message not used by any operation.
+    Unclassified,
+}
+
+/// Change between two versions of a specification.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct Change {
+    /// JSON pointer to the changed object, in the old document if it was removed and in the new
+    /// one otherwise.
+    pub pointer: String,
+    pub kind: ChangeKind,
+    pub compatibility: Compatibility,
+    pub description: String,
+}
+
+impl Change {
+    pub fn is_breaking(&self) -> bool {
+        self.compatibility == Compatibility::Breaking
+    }
+}
+
+impl Display for ChangeKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChangeKind::Added => write!(f, "added"),
+            ChangeKind::Removed => write!(f, "removed"),
+            ChangeKind::Changed => write!(f, "changed"),
+        }
+    }
+}
+
+impl Display for Compatibility {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Compatibility::Breaking => write!(f, "breaking"),
+            Compatibility::NonBreaking => write!(f, "non-breaking"),
+            Compatibility::Unclassified => write!(f, "unclassified"),
+        }
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "[{}] {} {}: {}",
+            self.compatibility, self.kind, self.pointer, self.description
+        )
+    }
+}
+
+/// Lists the changes to the servers, channels, operations, messages and component schemas of
+/// `old` in `new`, sorted by pointer.
+pub fn diff(old: &AsyncApiV3Spec, new: &AsyncApiV3Spec) -> Vec<Change> {
+    let mut differ = Differ {
+        old,
+        new,
+        old_roles: roles(old),
+        new_roles: roles(new),
+        changes: vec![],
+    };
+    differ.servers();
+    differ.channels();
+    differ.operations();
+    differ.messages();
+    differ.schemas();
+    let mut changes = differ.changes;
+    changes.sort_by(|a, b| a.pointer.cmp(&b.pointer));
+    changes
+}
+
+/// Whether the clients of the application send or receive a message or schema.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+struct Roles {
+    /// The application receives it, clients send it.
+    received: bool,
+    /// The application sends it, clients receive it.
+    sent: bool,
+}
+
+impl Roles {
+    const RECEIVED: Roles = Roles {
+        received: true,
+        sent: false,
+    };
+    const SENT: Roles = Roles {
+        received: false,
+        sent: true,
+    };
+
+    fn union(self, other: Roles) -> Roles {
+        Roles {
+            received: self.received || other.received,
+            sent: self.sent || other.sent,
+        }
+    }
+
+    fn classify(self, direction: Direction) -> Compatibility {
+        match direction {
+            // clients sending values that are no longer valid break
+            Direction::Tightening if self.received => Compatibility::Breaking,
+            Direction::Tightening if self.sent => Compatibility::NonBreaking,
+            // clients receiving values they do not expect break
+            Direction::Loosening if self.sent => Compatibility::Breaking,
+            Direction::Loosening if self.received => Compatibility::NonBreaking,
+            Direction::Neutral => Compatibility::NonBreaking,
+            _ => Compatibility::Unclassified,
+        }
+    }
+
+    /// Compatibility of the removal of an object with these roles.
+    fn removal(self) -> Compatibility {
+        match self {
+            Roles { received: true, .. } => Compatibility::Breaking,
+            Roles { sent: true, .. } => Compatibility::NonBreaking,
+            _ => Compatibility::Unclassified,
+        }
+    }
+}
+
+struct Differ<'a> {
+    old: &'a AsyncApiV3Spec,
+    new: &'a AsyncApiV3Spec,
+    /// Roles of the messages and component schemas, by pointer.
+    old_roles: HashMap<String, Roles>,
+    new_roles: HashMap<String, Roles>,
+    changes: Vec<Change>,
+}
+
+impl Differ<'_> {
+    fn push(
+        &mut self,
+        pointer: String,
+        kind: ChangeKind,
+        compatibility: Compatibility,
+        description: impl Into<String>,
+    ) {
+        self.changes.push(Change {
+            pointer,
+            kind,
+            compatibility,
+            description: description.into(),
+        });
+    }
+
+    /// Pushes the addition or removal of an object, returning the objects present in both
+    /// versions.
+    fn added_or_removed<'b, T>(
+        &mut self,
+        section: &[&str],
+        old: &'b IndexMap<String, T>,
+        new: &'b IndexMap<String, T>,
+        removal: impl Fn(&Self, &str) -> Compatibility,
+    ) -> Vec<(String, &'b T, &'b T)> {
+        let mut common = vec![];
+        let names = old.keys().chain(new.keys()).collect::<BTreeSet<_>>();
+        for name in names {
+            let pointer = json_pointer(&[section, &[name.as_str()]].concat());
+            match (old.get(name), new.get(name)) {
+                (Some(old), Some(new)) => common.push((pointer, old, new)),
+                (Some(_), None) => {
+                    let compatibility = removal(self, &pointer);
+                    self.push(pointer, ChangeKind::Removed, compatibility, "removed");
+                }
+                (None, Some(_)) => {
+                    let compatibility = Compatibility::NonBreaking;
+                    self.push(pointer, ChangeKind::Added, compatibility, "added");
+                }
+                (None, None) => {}
+            }
+        }
+        common
+    }
+
+    /// Pushes a change of reference, returning the inline objects to compare.
+    fn inline<'b, T: PartialEq>(
+        &mut self,
+        pointer: &str,
+        old: &'b RefOr<T>,
+        new: &'b RefOr<T>,
+    ) -> Option<(&'b T, &'b T)> {
+        match (old, new) {
+            _ if old == new => None,
+            (Either::Right(old), Either::Right(new)) => Some((old, new)),
+            _ => {
+                let compatibility = Compatibility::Unclassified;
+                let pointer = String::from(pointer);
+                self.push(
+                    pointer,
+                    ChangeKind::Changed,
+                    compatibility,
+                    "reference changed",
+                );
+                None
+            }
+        }
+    }
+
+    fn servers(&mut self) {
+        let (old, new) = (&self.old.servers, &self.new.servers);
+        let breaking = |_: &Self, _: &str| Compatibility::Breaking;
+        for (pointer, old, new) in self.added_or_removed(&["servers"], old, new, breaking) {
+            let Some((old, new)) = self.inline(&pointer, old, new) else {
+                continue;
+            };
+            let connection = |server: &crate::spec::server::Server| {
+                (
+                    server.host.clone(),
+                    server.protocol.clone(),
+                    server.protocol_version.clone(),
+                    server.pathname.clone(),
+                )
+            };
+            if connection(old) != connection(new) {
+                let compatibility = Compatibility::Breaking;
+                self.push(
+                    pointer,
+                    ChangeKind::Changed,
+                    compatibility,
+                    "connection changed",
+                );
+            } else {
+                let compatibility = Compatibility::NonBreaking;
+                self.push(
+                    pointer,
+                    ChangeKind::Changed,
+                    compatibility,
+                    "metadata changed",
+                );
+            }
+        }
+    }
+
+    fn channels(&mut self) {
+        let (old, new) = (&self.old.channels, &self.new.channels);
+        let breaking = |_: &Self, _: &str| Compatibility::Breaking;
+        for (pointer, old, new) in self.added_or_removed(&["channels"], old, new, breaking) {
+            let Some((old, new)) = self.inline(&pointer, old, new) else {
+                continue;
+            };
+            if old.address != new.address {
+                let description = format!(
+                    "address changed from {:?} to {:?}",
+                    old.address.as_deref().unwrap_or_default(),
+                    new.address.as_deref().unwrap_or_default()
+                );
+                let compatibility = Compatibility::Breaking;
+                self.push(
+                    pointer.clone(),
+                    ChangeKind::Changed,
+                    compatibility,
+                    description,
+                );
+            }
+            if old.parameters != new.parameters {
+                let compatibility = Compatibility::Unclassified;
+                let description = "parameters changed";
+                self.push(
+                    pointer.clone(),
+                    ChangeKind::Changed,
+                    compatibility,
+                    description,
+                );
+            }
+            // messages are compared on their own
+            if metadata_changed(old, new, &["address", "parameters", "messages"]) {
+                let compatibility = Compatibility::NonBreaking;
+                self.push(
+                    pointer,
+                    ChangeKind::Changed,
+                    compatibility,
+                    "metadata changed",
+                );
+            }
+        }
+    }
+
+    fn operations(&mut self) {
+        let (old, new) = (&self.old.operations, &self.new.operations);
+        let breaking = |_: &Self, _: &str| Compatibility::Breaking;
+        for (pointer, old, new) in self.added_or_removed(&["operations"], old, new, breaking) {
+            let Some((old, new)) = self.inline(&pointer, old, new) else {
+                continue;
+            };
+            if old.action != new.action {
+                let compatibility = Compatibility::Breaking;
+                self.push(
+                    pointer.clone(),
+                    ChangeKind::Changed,
+                    compatibility,
+                    "action changed",
+                );
+            }
+            if old.channel != new.channel {
+                let compatibility = Compatibility::Breaking;
+                let description = format!(
+                    "channel changed from '{}' to '{}'",
+                    old.channel.reference, new.channel.reference
+                );
+                self.push(
+                    pointer.clone(),
+                    ChangeKind::Changed,
+                    compatibility,
+                    description,
+                );
+            }
+            let old_messages = message_references(old.messages.as_deref());
+            let new_messages = message_references(new.messages.as_deref());
+            for message in old_messages.difference(&new_messages) {
+                let description = format!("message '{message}' removed");
+                let compatibility = Compatibility::Breaking;
+                self.push(
+                    pointer.clone(),
+                    ChangeKind::Changed,
+                    compatibility,
+                    description,
+                );
+            }
+            for message in new_messages.difference(&old_messages) {
+                let description = format!("message '{message}' added");
+                let compatibility = Compatibility::NonBreaking;
+                self.push(
+                    pointer.clone(),
+                    ChangeKind::Changed,
+                    compatibility,
+                    description,
+                );
+            }
+            if old.reply != new.reply {
+                let compatibility = Compatibility::Breaking;
+                self.push(
+                    pointer.clone(),
+                    ChangeKind::Changed,
+                    compatibility,
+                    "reply changed",
+                );
+            }
+            if metadata_changed(old, new, &["action", "channel", "messages", "reply"]) {
+                let compatibility = Compatibility::NonBreaking;
+                self.push(
+                    pointer,
+                    ChangeKind::Changed,
+                    compatibility,
+                    "metadata changed",
+                );
+            }
+        }
+    }
+
+    fn messages(&mut self) {
+        let old = messages(self.old);
+        let new = messages(self.new);
+        let names = old.keys().chain(new.keys()).collect::<BTreeSet<_>>();
+        for pointer in names {
+            let roles = self.roles(pointer);
+            match (old.get(pointer), new.get(pointer)) {
+                (Some(_), None) => {
+                    let compatibility = roles.removal();
+                    self.push(
+                        pointer.clone(),
+                        ChangeKind::Removed,
+                        compatibility,
+                        "removed",
+                    );
+                }
+                (None, Some(_)) => {
+                    let compatibility = Compatibility::NonBreaking;
+                    self.push(pointer.clone(), ChangeKind::Added, compatibility, "added");
+                }
+                (Some(Either::Left(old)), Some(Either::Left(new))) if old == new => {}
+                (Some(old_message), Some(new_message)) => {
+                    let resolved = (
+                        resolve_message(self.old, old_message),
+                        resolve_message(self.new, new_message),
+                    );
+                    match resolved {
+                        (Some(old), Some(new)) => self.message(pointer, old, new, roles),
+                        _ => {
+                            let compatibility = Compatibility::Unclassified;
+                            let description = "reference changed";
+                            self.push(
+                                pointer.clone(),
+                                ChangeKind::Changed,
+                                compatibility,
+                                description,
+                            );
+                        }
+                    }
+                }
+                (None, None) => {}
+            }
+        }
+    }
+
+    fn message(&mut self, pointer: &str, old: &Message, new: &Message, roles: Roles) {
+        if old == new {
+            return;
+        }
+        let payload = |message: &Message| schema_value(message.payload.as_ref());
+        self.schema(
+            &format!("{pointer}/payload"),
+            payload(old),
+            payload(new),
+            roles,
+        );
+        let headers = |message: &Message| schema_value(message.headers.as_ref());
+        self.schema(
+            &format!("{pointer}/headers"),
+            headers(old),
+            headers(new),
+            roles,
+        );
+        if old.content_type != new.content_type {
+            let compatibility = Compatibility::Breaking;
+            let description = "content type changed";
+            self.push(
+                String::from(pointer),
+                ChangeKind::Changed,
+                compatibility,
+                description,
+            );
+        }
+        if metadata_changed(old, new, &["headers", "payload", "contentType"]) {
+            let compatibility = Compatibility::NonBreaking;
+            let description = "metadata changed";
+            self.push(
+                String::from(pointer),
+                ChangeKind::Changed,
+                compatibility,
+                description,
+            );
+        }
+    }
+
+    fn schemas(&mut self) {
+        let (old, new) = (&self.old.components.schemas, &self.new.components.schemas);
+        let section = ["components", "schemas"];
+        let removal = |differ: &Self, pointer: &str| differ.roles(pointer).removal();
+        for (pointer, old, new) in self.added_or_removed(&section, old, new, removal) {
+            let roles = self.roles(&pointer);
+            self.schema(
+                &pointer,
+                schema_value(Some(old)),
+                schema_value(Some(new)),
+                roles,
+            );
+        }
+    }
+
+    fn schema(&mut self, pointer: &str, old: Option<Value>, new: Option<Value>, roles: Roles) {
+        let mut changes = vec![];
+        match (old, new) {
+            (Some(old), Some(new)) => schema::compare(&old, &new, "", &mut changes),
+            (None, Some(_)) => changes.push(SchemaChange {
+                pointer: String::new(),
+                direction: Direction::Tightening,
+                description: String::from("schema added"),
+            }),
+            (Some(_), None) => changes.push(SchemaChange {
+                pointer: String::new(),
+                direction: Direction::Loosening,
+                description: String::from("schema removed"),
+            }),
+            (None, None) => {}
+        }
+        for change in changes {
+            self.push(
+                format!("{pointer}{}", change.pointer),
+                ChangeKind::Changed,
+                roles.classify(change.direction),
+                change.description,
+            );
+        }
+    }
+
+    /// Roles of a message or schema in both versions.
+    fn roles(&self, pointer: &str) -> Roles {
+        let roles =
+            |roles: &HashMap<String, Roles>| roles.get(pointer).copied().unwrap_or_default();
+        roles(&self.old_roles).union(roles(&self.new_roles))
+    }
+}
+
+/// Messages of the channels and of the components, by pointer.
+fn messages(spec: &AsyncApiV3Spec) -> HashMap<String, &RefOr<Message>> {
+    let channel_messages = spec
+        .channels
+        .iter()
+        .filter_map(|(channel_name, channel)| match channel {
+            Either::Right(channel) => Some((channel_name, channel)),
+            Either::Left(_) => None,
+        })
+        .flat_map(|(channel_name, channel)| {
+            channel.messages.iter().map(move |(name, message)| {
+                (
+                    json_pointer(&["channels", channel_name, "messages", name]),
+                    message,
+                )
+            })
+        });
+    let component_messages = spec
+        .components
+        .messages
+        .iter()
+        .map(|(name, message)| (json_pointer(&["components", "messages", name]), message));
+    channel_messages.chain(component_messages).collect()
+}
+
+/// Message, following a reference to the component messages.
+fn resolve_message<'a>(
+    spec: &'a AsyncApiV3Spec,
+    message: &'a RefOr<Message>,
+) -> Option<&'a Message> {
+    match message {
+        Either::Right(message) => Some(message),
+        Either::Left(reference) => {
+            let name = reference.reference.strip_prefix("#/components/messages/")?;
+            match spec.components.messages.get(name)? {
+                Either::Right(message) => Some(message),
+                Either::Left(_) => None,
+            }
+        }
+    }
+}
+
+/// Whether the objects differ on other fields than `compared`, which are compared on their own.
+fn metadata_changed<T: serde::Serialize>(old: &T, new: &T, compared: &[&str]) -> bool {
+    let metadata = |object: &T| {
+        let mut value = serde_json::to_value(object).unwrap_or_default();
+        if let Some(object) = value.as_object_mut() {
+            object.retain(|key, _| !compared.contains(&key.as_str()));
+        }
+        value
+    };
+    metadata(old) != metadata(new)
+}
+
+/// JSON schema of a payload, headers or component schema. Multi format schemas are compared
+/// through their JSON schema conversion when it succeeds.
+fn schema_value(
+    schema: Option<&RefOr<Either<schemars::Schema, MultiFormatSchema>>>,
+) -> Option<Value> {
+    let schema = schema?;
+    #[cfg(feature = "schema-format")]
+    if let Either::Right(schema) = schema {
+        let converted = MultiFormatSchema::from_either(schema)
+            .and_then(|multi_format| multi_format.to_json_schema().ok());
+        if let Some(converted) = converted {
+            return Some(crate::schema_format::inline_definitions(converted));
+        }
+    }
+    serde_json::to_value(schema).ok()
+}
+
+/// Roles of the messages and component schemas of a specification, by pointer.
+fn roles(spec: &AsyncApiV3Spec) -> HashMap<String, Roles> {
+    let mut roles = HashMap::<String, Roles>::new();
+    let mut add = |pointer: String, role: Roles| {
+        let entry = roles.entry(pointer).or_default();
+        *entry = entry.union(role);
+    };
+
+    for operation in spec.operations.values() {
+        let Either::Right(operation) = operation else {
+            continue;
+        };
+        let (request, reply) = match operation.action {
+            OperationAction::Receive => (Roles::RECEIVED, Roles::SENT),
+            OperationAction::Send => (Roles::SENT, Roles::RECEIVED),
+        };
+        for pointer in operation_messages(
+            spec,
+            Some(&operation.channel),
+            operation.messages.as_deref(),
+        ) {
+            add(pointer, request);
+        }
+        if let Some(Either::Right(operation_reply)) = &operation.reply {
+            let messages =
+                Some(operation_reply.messages.as_slice()).filter(|messages| !messages.is_empty());
+            for pointer in operation_messages(spec, operation_reply.channel.as_ref(), messages) {
+                add(pointer, reply);
+            }
+        }
+    }
+
+    // channel messages referencing component messages
+    let messages = messages(spec);
+    for (pointer, message) in &messages {
+        if let (Either::Left(reference), Some(role)) = (message, roles.get(pointer).copied()) {
+            let entry = roles.entry(reference_pointer(reference)).or_default();
+            *entry = entry.union(role);
+        }
+    }
+
+    // schemas referenced by messages, and by the schemas they reference
+    let mut pending = vec![];
+    for (pointer, message) in &messages {
+        let (Some(role), Some(message)) =
+            (roles.get(pointer).copied(), resolve_message(spec, message))
+        else {
+            continue;
+        };
+        for schema in [
+            schema_value(message.payload.as_ref()),
+            schema_value(message.headers.as_ref()),
+        ] {
+            schema_references(schema.as_ref(), role, &mut pending);
+        }
+    }
+    while let Some((pointer, role)) = pending.pop() {
+        let entry = roles.entry(pointer.clone()).or_default();
+        let union = entry.union(role);
+        if *entry == union {
+            continue;
+        }
+        *entry = union;
+        let name = pointer.trim_start_matches("/components/schemas/");
+        let schema = spec
+            .components
+            .schemas
+            .get(name)
+            .and_then(|schema| serde_json::to_value(schema).ok());
+        schema_references(schema.as_ref(), union, &mut pending);
+    }
+    roles
+}
+
+/// Pointers of the messages of an operation or reply: `messages`, or all the messages of the
+/// channel if they are not listed.
+fn operation_messages(
+    spec: &AsyncApiV3Spec,
+    channel: Option<&ReferenceObject>,
+    messages: Option<&[ReferenceObject]>,
+) -> Vec<String> {
+    if let Some(messages) = messages {
+        return messages.iter().map(reference_pointer).collect();
+    }
+    let Some(channel_name) =
+        channel.and_then(|channel| channel.reference.strip_prefix("#/channels/"))
+    else {
+        return vec![];
+    };
+    match spec.channels.get(channel_name) {
+        Some(Either::Right(channel)) => channel
+            .messages
+            .keys()
+            .map(|name| json_pointer(&["channels", channel_name, "messages", name]))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+fn message_references(messages: Option<&[ReferenceObject]>) -> BTreeSet<&str> {
+    messages
+        .into_iter()
+        .flatten()
+        .map(|message| message.reference.as_str())
+        .collect()
+}
+
+fn reference_pointer(reference: &ReferenceObject) -> String {
+    String::from(reference.reference.trim_start_matches('#'))
+}
+
+/// Pushes the component schemas referenced by `schema` to `pending`.
+fn schema_references(schema: Option<&Value>, role: Roles, pending: &mut Vec<(String, Roles)>) {
+    match schema {
+        Some(Value::Object(object)) => {
+            for (key, value) in object {
+                match value {
+                    Value::String(reference) if key == "$ref" => {
+                        if let Some(pointer) = reference.strip_prefix("#/components/schemas/") {
+                            pending.push((format!("/components/schemas/{pointer}"), role));
+                        }
+                    }
+                    _ => schema_references(Some(value), role, pending),
+                }
+            }
+        }
+        Some(Value::Array(values)) => values
+            .iter()
+            .for_each(|value| schema_references(Some(value), role, pending)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    fn spec(action: &str, payload: Value) -> AsyncApiV3Spec {
+        serde_json::from_value(json!({
+            "info": { "title": "Lights", "version": "1.0.0" },
+            "channels": {
+                "lightMeasured": {
+                    "address": "lights/measured",
+                    "messages": {
+                        "lightMeasured": { "payload": { "$ref": "#/components/schemas/lightMeasured" } }
+                    }
+                }
+            },
+            "operations": {
+                "onLightMeasured": {
+                    "action": action,
+                    "channel": { "$ref": "#/channels/lightMeasured" }
+                }
+            },
+            "components": {
+                "schemas": { "lightMeasured": payload }
+            }
+        }))
+        .unwrap()
+    }
+
+    fn compatibilities(old: &AsyncApiV3Spec, new: &AsyncApiV3Spec) -> Vec<Compatibility> {
+        diff(old, new)
+            .into_iter()
+            .map(|change| change.compatibility)
+            .collect()
+    }
+
+    #[test]
+    fn classify_required_fields_by_role() {
+        let old = json!({ "type": "object", "properties": { "lumens": { "type": "integer" } } });
+        let new = json!({
+            "type": "object",
+            "properties": { "lumens": { "type": "integer" } },
+            "required": ["lumens"]
+        });
+
+        let changes = diff(&spec("receive", old.clone()), &spec("receive", new.clone()));
+        assert_eq!(
+            changes,
+            [Change {
+                pointer: String::from("/components/schemas/lightMeasured"),
+                kind: ChangeKind::Changed,
+                compatibility: Compatibility::Breaking,
+                description: String::from("property 'lumens' became required"),
+            }]
+        );
+        assert_eq!(
+            compatibilities(&spec("send", old), &spec("send", new)),
+            [Compatibility::NonBreaking]
+        );
+    }
+
+    #[test]
+    fn classify_enum_changes_by_role() {
+        let old = json!({ "type": "string", "enum": ["on", "off", "dimmed"] });
+        let new = json!({ "type": "string", "enum": ["on", "off"] });
+
+        assert_eq!(
+            compatibilities(&spec("receive", old.clone()), &spec("receive", new.clone())),
+            [Compatibility::Breaking]
+        );
+        assert_eq!(
+            compatibilities(&spec("send", old.clone()), &spec("send", new.clone())),
+            [Compatibility::NonBreaking]
+        );
+        // widening breaks the clients receiving the messages
+        assert_eq!(
+            compatibilities(&spec("send", new), &spec("send", old)),
+            [Compatibility::Breaking]
+        );
+    }
+
+    #[cfg(feature = "schema-format")]
+    #[test]
+    fn classify_multi_format_changes_by_role() {
+        let avro = |symbols: Value| {
+            json!({
+                "schemaFormat": "application/vnd.apache.avro;version=1.9.0",
+                "schema": { "type": "enum", "name": "State", "symbols": symbols }
+            })
+        };
+        let old = avro(json!(["ON", "OFF", "DIMMED"]));
+        let new = avro(json!(["ON", "OFF"]));
+
+        let changes = diff(&spec("receive", old.clone()), &spec("receive", new.clone()));
+        assert_eq!(
+            changes
+                .iter()
+                .map(|change| (change.compatibility, change.description.as_str()))
+                .collect::<Vec<_>>(),
+            [(Compatibility::Breaking, "enum value \"DIMMED\" removed")]
+        );
+        assert_eq!(
+            compatibilities(&spec("send", old), &spec("send", new)),
+            [Compatibility::NonBreaking]
+        );
+    }
+
+    #[test]
+    fn report_added_and_removed_objects() {
+        let payload = json!({ "type": "string" });
+        let old = spec("receive", payload.clone());
+        let mut new = spec("receive", payload);
+        new.channels.clear();
+        new.operations.clear();
+        new.servers.insert(
+            String::from("production"),
+            serde_json::from_value(json!({ "host": "test.mosquitto.org", "protocol": "mqtt" }))
+                .unwrap(),
+        );
+
+        let changes = diff(&old, &new)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            [
+                "[breaking] removed /channels/lightMeasured: removed",
+                "[breaking] removed /channels/lightMeasured/messages/lightMeasured: removed",
+                "[breaking] removed /operations/onLightMeasured: removed",
+                "[non-breaking] added /servers/production: added",
+            ]
+        );
+    }
+}
@@ -0,0 +1,373 @@
+//! Structural comparison of two JSON schemas.
+use std::collections::BTreeSet;
+
+use serde_json::Value;
+
+use crate::pointer::json_pointer;
+
+/// Effect of a schema change on the set of valid values.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Direction {
+    /// Some values valid for the old schema are no longer valid.
+    Tightening,
+    /// Some values invalid for the old schema are now valid.
+    Loosening,
+    /// The change does not affect readers nor writers, e.g. a new optional property.
+    Neutral,
+    /// The effect of the change cannot be determined.
+    Unknown,
+}
+
+pub(crate) struct SchemaChange {
+    /// JSON pointer inside the schema.
+    pub pointer: String,
+    pub direction: Direction,
+    pub description: String,
+}
+
+/// Keywords compared by [`compare`], other keywords are compared as opaque values.
+const KEYWORDS: [&str; 8] = [
+    "type",
+    "enum",
+    "required",
+    "properties",
+    "additionalProperties",
+    "items",
+    "oneOf",
+    "anyOf",
+];
+
+/// Keywords that do not affect validation.
+const ANNOTATIONS: [&str; 9] = [
+    "$comment",
+    "title",
+    "description",
+    "default",
+    "examples",
+    "deprecated",
+    "readOnly",
+    "writeOnly",
+    "$schema",
+];
+
+/// Keywords whose increase rejects more values.
+const LOWER_BOUNDS: [&str; 5] = [
+    "minimum",
+    "exclusiveMinimum",
+    "minLength",
+    "minItems",
+    "minProperties",
+];
+
+/// Keywords whose decrease rejects more values.
+const UPPER_BOUNDS: [&str; 5] = [
+    "maximum",
+    "exclusiveMaximum",
+    "maxLength",
+    "maxItems",
+    "maxProperties",
+];
+
+/// Compares two schemas, pushing the changes from `old` to `new` to `changes`.
+pub(crate) fn compare(old: &Value, new: &Value, pointer: &str, changes: &mut Vec<SchemaChange>) {
+    if old == new {
+        return;
+    }
+    let (Some(old_object), Some(new_object)) = (old.as_object(), new.as_object()) else {
+        return push(changes, pointer, Direction::Unknown, "schema changed");
+    };
+    if old.get("$ref") != new.get("$ref") {
+        return push(
+            changes,
+            pointer,
+            Direction::Unknown,
+            "referenced schema changed",
+        );
+    }
+
+    compare_types(old.get("type"), new.get("type"), pointer, changes);
+    compare_enums(old.get("enum"), new.get("enum"), pointer, changes);
+
+    let old_required = strings(old.get("required"));
+    let new_required = strings(new.get("required"));
+    for name in new_required.difference(&old_required) {
+        let description = format!("property '{name}' became required");
+        push(changes, pointer, Direction::Tightening, &description);
+    }
+    for name in old_required.difference(&new_required) {
+        let description = format!("property '{name}' is no longer required");
+        push(changes, pointer, Direction::Loosening, &description);
+    }
+
+    let closed = new.get("additionalProperties") == Some(&Value::Bool(false));
+    let empty = serde_json::Map::new();
+    let old_properties = old
+        .get("properties")
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+    let new_properties = new
+        .get("properties")
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+    for (name, old_property) in old_properties {
+        let property_pointer = format!("{pointer}{}", json_pointer(&["properties", name]));
+        match new_properties.get(name) {
+            Some(new_property) => compare(old_property, new_property, &property_pointer, changes),
+            None => {
+                push(
+                    changes,
+                    &property_pointer,
+                    Direction::Loosening,
+                    "property removed",
+                );
+                if closed {
+                    let description = "property removed and no longer allowed";
+                    push(
+                        changes,
+                        &property_pointer,
+                        Direction::Tightening,
+                        description,
+                    );
+                }
+            }
+        }
+    }
+    let was_closed = old.get("additionalProperties") == Some(&Value::Bool(false));
+    for name in new_properties.keys() {
+        if old_properties.contains_key(name) {
+            continue;
+        }
+        let property_pointer = format!("{pointer}{}", json_pointer(&["properties", name]));
+        if was_closed {
+            let description = "property added while additional properties were not allowed";
+            push(
+                changes,
+                &property_pointer,
+                Direction::Loosening,
+                description,
+            );
+        } else if !new_required.contains(name.as_str()) {
+            let description = "optional property added";
+            push(changes, &property_pointer, Direction::Neutral, description);
+        }
+    }
+
+    compare_additional_properties(
+        old.get("additionalProperties"),
+        new.get("additionalProperties"),
+        pointer,
+        changes,
+    );
+    match (old.get("items"), new.get("items")) {
+        (Some(old_items), Some(new_items)) => {
+            compare(old_items, new_items, &format!("{pointer}/items"), changes)
+        }
+        (None, None) => {}
+        _ => push(changes, pointer, Direction::Unknown, "items changed"),
+    }
+    for keyword in ["oneOf", "anyOf"] {
+        compare_branches(
+            keyword,
+            old.get(keyword),
+            new.get(keyword),
+            pointer,
+            changes,
+        );
+    }
+
+    let keys = old_object
+        .keys()
+        .chain(new_object.keys())
+        .filter(|key| !KEYWORDS.contains(&key.as_str()) && *key != "$ref")
+        .collect::<BTreeSet<_>>();
+    for key in keys {
+        let (old_value, new_value) = (old.get(key), new.get(key));
+        if old_value == new_value {
+            continue;
+        }
+        let key = key.as_str();
+        let description = format!("keyword '{key}' changed");
+        let bound = |value: Option<&Value>| value.and_then(Value::as_f64);
+        let direction = match (bound(old_value), bound(new_value)) {
+            _ if ANNOTATIONS.contains(&key) => Direction::Neutral,
+            (old, new) if LOWER_BOUNDS.contains(&key) => bound_direction(old, new),
+            (old, new) if UPPER_BOUNDS.contains(&key) => bound_direction(new, old),
+            _ => Direction::Unknown,
+        };
+        push(changes, pointer, direction, &description);
+    }
+}
+
+/// Direction of the change of a bound that rejects more values when it increases. A missing
+/// bound is the lowest one.
+fn bound_direction(old: Option<f64>, new: Option<f64>) -> Direction {
+    match (old, new) {
+        (None, Some(_)) => Direction::Tightening,
+        (Some(_), None) => Direction::Loosening,
+        (Some(old), Some(new)) if new > old => Direction::Tightening,
+        (Some(old), Some(new)) if new < old => Direction::Loosening,
+        _ => Direction::Unknown,
+    }
+}
+
+/// Compares the branches of `oneOf` or `anyOf`. Identical branches are matched whatever their
+/// position, the remaining ones are compared by position when their number is unchanged.
+fn compare_branches(
+    keyword: &str,
+    old: Option<&Value>,
+    new: Option<&Value>,
+    pointer: &str,
+    changes: &mut Vec<SchemaChange>,
+) {
+    if old == new {
+        return;
+    }
+    let (Some(old), Some(new)) = (old.and_then(Value::as_array), new.and_then(Value::as_array))
+    else {
+        let description = format!("'{keyword}' changed");
+        return push(changes, pointer, Direction::Unknown, &description);
+    };
+    let old = old.iter().enumerate().collect::<Vec<_>>();
+    let new = new.iter().enumerate().collect::<Vec<_>>();
+    let old_left = old
+        .iter()
+        .filter(|(_, branch)| !new.iter().any(|(_, other)| other == branch))
+        .collect::<Vec<_>>();
+    let new_left = new
+        .iter()
+        .filter(|(_, branch)| !old.iter().any(|(_, other)| other == branch))
+        .collect::<Vec<_>>();
+    if old.len() == new.len() {
+        for ((_, old_branch), (idx, new_branch)) in old_left.iter().zip(&new_left) {
+            let branch_pointer = format!("{pointer}/{keyword}/{idx}");
+            compare(old_branch, new_branch, &branch_pointer, changes);
+        }
+        return;
+    }
+    for (idx, _) in old_left {
+        let description = format!("branch {idx} of '{keyword}' removed");
+        push(changes, pointer, Direction::Tightening, &description);
+    }
+    for (idx, _) in new_left {
+        let branch_pointer = format!("{pointer}/{keyword}/{idx}");
+        push(
+            changes,
+            &branch_pointer,
+            Direction::Loosening,
+            "branch added",
+        );
+    }
+}
+
+fn compare_types(
+    old: Option<&Value>,
+    new: Option<&Value>,
+    pointer: &str,
+    changes: &mut Vec<SchemaChange>,
+) {
+    if old == new {
+        return;
+    }
+    let (Some(old), Some(new)) = (old, new) else {
+        let direction = match new {
+            Some(_) => Direction::Tightening,
+            None => Direction::Loosening,
+        };
+        return push(changes, pointer, direction, "type changed");
+    };
+    let old_types = strings(Some(old));
+    let new_types = strings(Some(new));
+    let covers = |types: &BTreeSet<&str>, ty: &str| {
+        types.contains(ty) || (ty == "integer" && types.contains("number"))
+    };
+    if old_types.iter().any(|ty| !covers(&new_types, ty)) {
+        let description = format!("type narrowed from {old} to {new}");
+        push(changes, pointer, Direction::Tightening, &description);
+    }
+    if new_types.iter().any(|ty| !covers(&old_types, ty)) {
+        let description = format!("type widened from {old} to {new}");
+        push(changes, pointer, Direction::Loosening, &description);
+    }
+}
+
+fn compare_enums(
+    old: Option<&Value>,
+    new: Option<&Value>,
+    pointer: &str,
+    changes: &mut Vec<SchemaChange>,
+) {
+    let values = |value: Option<&Value>| value.and_then(Value::as_array).cloned();
+    match (values(old), values(new)) {
+        (Some(old), Some(new)) => {
+            let removed = old.iter().filter(|value| !new.contains(value));
+            for value in removed {
+                let description = format!("enum value {value} removed");
+                push(changes, pointer, Direction::Tightening, &description);
+            }
+            let added = new.iter().filter(|value| !old.contains(value));
+            for value in added {
+                let description = format!("enum value {value} added");
+                push(changes, pointer, Direction::Loosening, &description);
+            }
+        }
+        (None, Some(_)) => push(changes, pointer, Direction::Tightening, "enum added"),
+        (Some(_), None) => push(changes, pointer, Direction::Loosening, "enum removed"),
+        (None, None) => {}
+    }
+}
+
+fn compare_additional_properties(
+    old: Option<&Value>,
+    new: Option<&Value>,
+    pointer: &str,
+    changes: &mut Vec<SchemaChange>,
+) {
+    // a missing keyword allows any additional property
+    let allowed = Value::Bool(true);
+    let old = old.unwrap_or(&allowed);
+    let new = new.unwrap_or(&allowed);
+    match (old, new) {
+        (Value::Bool(true), Value::Bool(false)) => push(
+            changes,
+            pointer,
+            Direction::Tightening,
+            "additional properties no longer allowed",
+        ),
+        (Value::Bool(false), Value::Bool(true)) => push(
+            changes,
+            pointer,
+            Direction::Loosening,
+            "additional properties allowed",
+        ),
+        (Value::Object(_), Value::Object(_)) => compare(
+            old,
+            new,
+            &format!("{pointer}/additionalProperties"),
+            changes,
+        ),
+        _ if old != new => push(
+            changes,
+            pointer,
+            Direction::Unknown,
+            "additional properties changed",
+        ),
+        _ => {}
+    }
+}
+
+/// Strings of a string or array of strings.
+fn strings(value: Option<&Value>) -> BTreeSet<&str> {
+    match value {
+        Some(Value::String(value)) => BTreeSet::from([value.as_str()]),
+        Some(Value::Array(values)) => values.iter().filter_map(Value::as_str).collect(),
+        _ => BTreeSet::new(),
+    }
+}
+
+fn push(changes: &mut Vec<SchemaChange>, pointer: &str, direction: Direction, description: &str) {
+    changes.push(SchemaChange {
+        pointer: String::from(pointer),
+        direction,
+        description: String::from(description),
+    });
+}
@@ -1,11 +1,105 @@
 use thiserror::Error;
 
+use crate::pointer::json_pointer;
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("Duplicate definition entry '{key}'")]
-    DuplicateDefinition { key: String },
+    DuplicateDefinition {
+        key: String,
+        /// Definition already registered.
+        existing: Box<serde_json::Value>,
+        /// Definition conflicting with the registered one.
+        conflicting: Box<serde_json::Value>,
+    },
+    #[error("Duplicate message entry '{name}' in channel '{channel}'")]
+    DuplicateMessage {
+        channel: String,
+        name: String,
+        /// Message already registered.
+        existing: Box<serde_json::Value>,
+        /// Message conflicting with the registered one.
+        conflicting: Box<serde_json::Value>,
+    },
     #[error("Duplicate operation entry '{name}'")]
     DuplicateOperation { name: String },
     #[error("Channel not found '{name}'")]
     ChannelNotFound { name: String },
+    #[error("Channel '{name}' is a reference and cannot be modified")]
+    ChannelIsReference { name: String },
+    #[error("Channel '{name}' has address '{existing}' instead of '{conflicting}'")]
+    ChannelAddressMismatch {
+        name: String,
+        existing: String,
+        conflicting: String,
+    },
+    #[error("Server not found '{name}'")]
+    ServerNotFound { name: String },
+    #[error("Operation not found '{name}'")]
+    OperationNotFound { name: String },
+    #[error("Security scheme not found '{name}'")]
+    SecuritySchemeNotFound { name: String },
+    #[error("Parameter not found '{name}'")]
+    ParameterNotFound { name: String },
+    #[error("Parameter '{name}' is not used in the address of channel '{channel}'")]
+    ParameterNotInAddress { channel: String, name: String },
+    #[error("Cannot register operation '{name}': {source}")]
+    InOperation { name: String, source: Box<Error> },
+    #[error("Missing required field '{field}' of {object}")]
+    MissingField {
+        object: &'static str,
+        field: &'static str,
+    },
+}
+
+impl Error {
+    /// JSON pointer to the object of the specification the error relates to.
+    pub fn pointer(&self) -> Option<String> {
+        match self {
+            Error::DuplicateDefinition { key, .. } => {
+                Some(json_pointer(&["components", "schemas", key]))
+            }
+            Error::DuplicateMessage { channel, name, .. } => {
+                Some(json_pointer(&["channels", channel, "messages", name]))
+            }
+            Error::DuplicateOperation { name } | Error::OperationNotFound { name } => {
+                Some(json_pointer(&["operations", name]))
+            }
+            Error::ChannelNotFound { name } | Error::ChannelIsReference { name } => {
+                Some(json_pointer(&["channels", name]))
+            }
+            Error::ChannelAddressMismatch { name, .. } => {
+                Some(json_pointer(&["channels", name, "address"]))
+            }
+            Error::ServerNotFound { name } => Some(json_pointer(&["servers", name])),
+            Error::SecuritySchemeNotFound { name } => {
+                Some(json_pointer(&["components", "securitySchemes", name]))
+            }
+            Error::ParameterNotFound { name } => {
+                Some(json_pointer(&["components", "parameters", name]))
+            }
+            Error::ParameterNotInAddress { channel, name } => {
+                Some(json_pointer(&["channels", channel, "parameters", name]))
+            }
+            Error::InOperation { source, .. } => source.pointer(),
+            Error::MissingField { .. } => None,
+        }
+    }
+
+    /// Name of the operation being registered when the error occurred.
+    pub fn operation(&self) -> Option<&str> {
+        match self {
+            Error::InOperation { name, .. } | Error::DuplicateOperation { name } => Some(name),
+            _ => None,
+        }
+    }
+}
+
+/// Non-fatal issue of a built specification.
+#[derive(Clone, Debug, PartialEq, Error)]
+pub enum Warning {
+    #[error("Component '{pointer}' is never referenced")]
+    UnusedComponent { pointer: String },
+    #[error("Message '{pointer}' has no payload")]
+    MessageWithoutPayload { pointer: String },
 }
@@ -0,0 +1,746 @@
+//! Generates synthetic [message examples](MessageExample) from the schemas of a message.
+//!
+//! The generated values are plausible rather than realistic: they satisfy the constraints
+//! expressed by the schema (`enum`, `const`, `default`, `format`, `minimum`/`maximum`,
+//! `pattern`, `oneOf`/`anyOf`, ...) and are fully deterministic for a given seed, which makes
+//! them suitable both for documentation and for contract tests.
+use crate::spec::common::{Either, RefOr};
+use crate::spec::component::Components;
+use crate::spec::message::{Message, MessageExample, MultiFormatSchema};
+use crate::spec::AsyncApiV3Spec;
+use indexmap::IndexMap;
+use serde_json::{Map, Number, Value};
+
+mod pattern;
+mod rng;
+
+use rng::SplitMix64;
+
+/// Default maximum nesting level followed while generating values.
+const DEFAULT_MAX_DEPTH: usize = 8;
+
+/// Generator of example values driven by JSON schemas.
+pub struct ExampleGenerator<'a> {
+    rng: SplitMix64,
+    components: Option<&'a Components>,
+    max_depth: usize,
+}
+
+impl<'a> ExampleGenerator<'a> {
+    /// Creates a generator whose output is entirely determined by `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: SplitMix64::new(seed),
+            components: None,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+
+    /// Components used to resolve `#/components/schemas/...` references.
+    pub fn with_components(mut self, components: &'a Components) -> Self {
+        self.components = Some(components);
+        self
+    }
+
+    /// Maximum nesting level to follow. Deeper optional properties are omitted, which also
+    /// guarantees termination on recursive schemas.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Generates a value that validates against `schema`.
+    pub fn generate(&mut self, schema: &schemars::Schema) -> Value {
+        self.generate_value(schema.as_value(), schema.as_value(), 0)
+    }
+
+    /// Generates a value for a message payload or headers definition. Returns `None` when the
+    /// schema cannot be interpreted (e.g. unresolvable references or non JSON schema formats).
+    pub fn generate_for(
+        &mut self,
+        schema: &RefOr<Either<schemars::Schema, MultiFormatSchema>>,
+    ) -> Option<Value> {
+        match schema {
+            RefOr::Left(reference) => {
+                let resolved = self.resolve_component(&reference.reference)?;
+                Some(self.generate_value(resolved, resolved, 0))
+            }
+            RefOr::Right(Either::Left(schema)) => {
+                let schema = json_schema(schema.as_value())?;
+                Some(self.generate_value(schema, schema, 0))
+            }
+            RefOr::Right(Either::Right(multi_format))
+                if is_json_schema_format(&multi_format.schema_format) =>
+            {
+                Some(self.generate_value(&multi_format.schema, &multi_format.schema, 0))
+            }
+            RefOr::Right(Either::Right(_)) => None,
+        }
+    }
+
+    /// Generates an example for the given message, using its `payload` and `headers` schemas.
+    pub fn generate_message_example(&mut self, message: &Message) -> Option<MessageExample> {
+        let payload = message
+            .payload
+            .as_ref()
+            .and_then(|schema| self.generate_for(schema));
+        let headers: IndexMap<String, Value> = match message
+            .headers
+            .as_ref()
+            .and_then(|schema| self.generate_for(schema))
+        {
+            Some(Value::Object(headers)) => headers.into_iter().collect(),
+            _ => Default::default(),
+        };
+
+        if payload.is_none() && headers.is_empty() {
+            return None;
+        }
+
+        Some(MessageExample {
+            headers,
+            payload,
+            name: Some(String::from("generated")),
+            summary: Some(String::from("Example generated from the message schema")),
+        })
+    }
+
+    /// Adds a generated example to `message` if it doesn't have any. Returns `true` when an
+    /// example was added.
+    pub fn fill_message_examples(&mut self, message: &mut Message) -> bool {
+        if !message.examples.is_empty() {
+            return false;
+        }
+        match self.generate_message_example(message) {
+            Some(example) => {
+                message.examples.push(example);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn resolve_component(&self, reference: &str) -> Option<&'a Value> {
+        let name = reference.strip_prefix("#/components/schemas/")?;
+        match self.components?.schemas.get(name)? {
+            RefOr::Right(Either::Left(schema)) => json_schema(schema.as_value()),
+            _ => None,
+        }
+    }
+
+    fn resolve<'s>(&self, root: &'s Value, reference: &str) -> Option<&'s Value>
+    where
+        'a: 's,
+    {
+        if reference.starts_with("#/components/schemas/") {
+            return self.resolve_component(reference);
+        }
+        root.pointer(reference.strip_prefix('#')?)
+    }
+
+    fn generate_value(&mut self, root: &Value, schema: &Value, depth: usize) -> Value {
+        let object = match schema {
+            Value::Object(object) => object,
+            _ => return Value::Null,
+        };
+
+        if let Some(Value::String(reference)) = object.get("$ref") {
+            return match self.resolve(root, reference) {
+                Some(resolved) if depth < self.max_depth => {
+                    // references to components are resolved against the component itself
+                    let root = if reference.starts_with("#/components/") {
+                        resolved
+                    } else {
+                        root
+                    };
+                    self.generate_value(root, resolved, depth + 1)
+                }
+                _ => Value::Null,
+            };
+        }
+        if let Some(value) = object.get("const") {
+            return value.clone();
+        }
+        if let Some(Value::Array(values)) = object.get("enum") {
+            if !values.is_empty() {
+                return values[self.rng.below(values.len())].clone();
+            }
+        }
+        if let Some(value) = object.get("default") {
+            return value.clone();
+        }
+        if let Some(Value::Array(values)) = object.get("examples") {
+            if let Some(value) = values.first() {
+                return value.clone();
+            }
+        }
+        for keyword in ["oneOf", "anyOf"] {
+            if let Some(Value::Array(branches)) = object.get(keyword) {
+                if !branches.is_empty() {
+                    let branch = &branches[self.rng.below(branches.len())];
+                    return self.generate_value(root, branch, depth + 1);
+                }
+            }
+        }
+        if let Some(Value::Array(parts)) = object.get("allOf") {
+            let mut merged = Map::new();
+            for part in parts {
+                match self.generate_value(root, part, depth + 1) {
+                    Value::Object(generated) => merged.extend(generated),
+                    other if parts.len() == 1 => return other,
+                    _ => {}
+                }
+            }
+            if let Value::Object(generated) = self.generate_typed(root, object, depth) {
+                merged.extend(generated);
+            }
+            return Value::Object(merged);
+        }
+
+        self.generate_typed(root, object, depth)
+    }
+
+    fn generate_typed(&mut self, root: &Value, schema: &Map<String, Value>, depth: usize) -> Value {
+        let type_name = match schema.get("type") {
+            Some(Value::String(type_name)) => type_name.as_str(),
+            Some(Value::Array(types)) => types
+                .iter()
+                .filter_map(Value::as_str)
+                .find(|type_name| *type_name != "null")
+                .unwrap_or("null"),
+            _ if schema.contains_key("properties") => "object",
+            _ if schema.contains_key("items") || schema.contains_key("prefixItems") => "array",
+            _ if schema.contains_key("pattern") || schema.contains_key("format") => "string",
+            _ if schema.contains_key("minimum") || schema.contains_key("maximum") => "number",
+            _ => return Value::Null,
+        };
+
+        match type_name {
+            "object" => self.generate_object(root, schema, depth),
+            "array" => self.generate_array(root, schema, depth),
+            "string" => Value::String(self.generate_string(schema)),
+            "integer" => self.generate_integer(schema),
+            "number" => self.generate_number(schema),
+            "boolean" => Value::Bool(self.rng.below(2) == 1),
+            _ => Value::Null,
+        }
+    }
+
+    fn generate_object(
+        &mut self,
+        root: &Value,
+        schema: &Map<String, Value>,
+        depth: usize,
+    ) -> Value {
+        let required = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|required| {
+                required
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        let mut generated = Map::new();
+        if let Some(Value::Object(properties)) = schema.get("properties") {
+            for (name, property) in properties {
+                if depth >= self.max_depth && !required.contains(&name.as_str()) {
+                    continue;
+                }
+                let value = self.generate_value(root, property, depth + 1);
+                generated.insert(name.clone(), value);
+            }
+        }
+        if generated.is_empty() && depth < self.max_depth {
+            if let Some(additional @ Value::Object(_)) = schema.get("additionalProperties") {
+                let value = self.generate_value(root, additional, depth + 1);
+                generated.insert(String::from("key"), value);
+            }
+        }
+        Value::Object(generated)
+    }
+
+    fn generate_array(&mut self, root: &Value, schema: &Map<String, Value>, depth: usize) -> Value {
+        if let Some(Value::Array(prefix)) = schema.get("prefixItems") {
+            return Value::Array(
+                prefix
+                    .iter()
+                    .map(|item| self.generate_value(root, item, depth + 1))
+                    .collect(),
+            );
+        }
+        let min_items = get_u64(schema, "minItems").unwrap_or(0);
+        let max_items = get_u64(schema, "maxItems").unwrap_or(min_items.max(2));
+        let count = if depth >= self.max_depth {
+            min_items
+        } else {
+            self.rng.between(min_items, max_items.max(min_items).max(1))
+        };
+        let items = match schema.get("items") {
+            Some(items @ Value::Object(_)) => items,
+            // draft-04 style tuple validation
+            Some(Value::Array(items)) => {
+                return Value::Array(
+                    items
+                        .iter()
+                        .map(|item| self.generate_value(root, item, depth + 1))
+                        .collect(),
+                )
+            }
+            _ => return Value::Array((0..count).map(|_| Value::Null).collect()),
+        };
+        Value::Array(
+            (0..count)
+                .map(|_| self.generate_value(root, items, depth + 1))
+                .collect(),
+        )
+    }
+
+    fn generate_string(&mut self, schema: &Map<String, Value>) -> String {
+        if let Some(Value::String(regex)) = schema.get("pattern") {
+            if let Some(generated) = pattern::generate(regex, &mut self.rng) {
+                return generated;
+            }
+        }
+
+        let min_length = get_u64(schema, "minLength").unwrap_or(0) as usize;
+        let max_length = get_u64(schema, "maxLength").map(|max| max as usize);
+        let generated = match schema.get("format").and_then(Value::as_str) {
+            Some("date-time") => format!("{}T{}Z", self.date(), self.time()),
+            Some("date") => self.date(),
+            Some("time") => format!("{}Z", self.time()),
+            Some("email") => format!("{}@example.com", self.word()),
+            Some("hostname") | Some("idn-hostname") => format!("{}.example.com", self.word()),
+            Some("uri") | Some("url") | Some("iri") => {
+                format!("https://example.com/{}", self.word())
+            }
+            Some("uri-reference") | Some("iri-reference") => format!("/{}", self.word()),
+            Some("uuid") => self.uuid(),
+            Some("ipv4") => format!(
+                "192.168.{}.{}",
+                self.rng.between(0, 255),
+                self.rng.between(1, 254)
+            ),
+            Some("ipv6") => format!("2001:db8::{:x}", self.rng.between(1, 0xffff)),
+            Some("byte") | Some("binary") => String::from("ZXhhbXBsZQ=="),
+            _ => self.word(),
+        };
+
+        let mut generated = generated;
+        while generated.chars().count() < min_length {
+            generated.push('x');
+        }
+        if let Some(max_length) = max_length {
+            generated = generated.chars().take(max_length).collect();
+        }
+        generated
+    }
+
+    fn generate_integer(&mut self, schema: &Map<String, Value>) -> Value {
+        let mut min = get_f64(schema, "minimum").map(|min| min.ceil() as i64);
+        let mut max = get_f64(schema, "maximum").map(|max| max.floor() as i64);
+        if let Some(exclusive) = exclusive_bound(schema, "exclusiveMinimum", "minimum") {
+            min = Some((exclusive.floor() as i64).saturating_add(1));
+        }
+        if let Some(exclusive) = exclusive_bound(schema, "exclusiveMaximum", "maximum") {
+            max = Some((exclusive.ceil() as i64).saturating_sub(1));
+        }
+        let (min, max) = match (min, max) {
+            (Some(min), Some(max)) => (min, max.max(min)),
+            (Some(min), None) => (min, min.saturating_add(100)),
+            (None, Some(max)) => (if max > 0 { 0 } else { max.saturating_sub(100) }, max),
+            (None, None) => (0, 100),
+        };
+        let span = max.abs_diff(min);
+        let mut value = min.saturating_add_unsigned(self.rng.between(0, span));
+        // A fractional `multipleOf` (e.g. 2.5) only admits the integers that are multiples of the
+        // smallest integral multiple of it (e.g. 5).
+        let step = get_f64(schema, "multipleOf")
+            .filter(|m| *m > 0.0)
+            .and_then(|m| {
+                (1..=1000)
+                    .map(|n| f64::from(n) * m)
+                    .find(|s| s.fract() == 0.0)
+            });
+        if let Some(step) = step {
+            if let Some(multiple) = nearest_multiple(value as f64, step, min as f64, max as f64) {
+                value = (multiple as i64).clamp(min, max);
+            }
+        }
+        Value::Number(Number::from(value))
+    }
+
+    fn generate_number(&mut self, schema: &Map<String, Value>) -> Value {
+        let min = get_f64(schema, "minimum")
+            .or_else(|| get_f64(schema, "exclusiveMinimum"))
+            .unwrap_or(0.0);
+        let max = get_f64(schema, "maximum")
+            .or_else(|| get_f64(schema, "exclusiveMaximum"))
+            .unwrap_or(min + 100.0)
+            .max(min);
+        let fraction = self.rng.below(1000) as f64 / 1000.0;
+        let mut value = ((min + (max - min) * fraction) * 100.0).round() / 100.0;
+        let exclusive_min = exclusive_bound(schema, "exclusiveMinimum", "minimum").is_some();
+        let exclusive_max = exclusive_bound(schema, "exclusiveMaximum", "maximum").is_some();
+        if value <= min && exclusive_min {
+            value = min + (max - min) / 2.0;
+        }
+        if value >= max && exclusive_max {
+            value = min + (max - min) / 2.0;
+        }
+        if let Some(multiple) = get_f64(schema, "multipleOf").filter(|m| *m > 0.0) {
+            // Shrinking an exclusive bound by half a step excludes the multiple lying on it.
+            let lowest = if exclusive_min {
+                min + multiple / 2.0
+            } else {
+                min
+            };
+            let highest = if exclusive_max {
+                max - multiple / 2.0
+            } else {
+                max
+            };
+            if let Some(multiple) = nearest_multiple(value, multiple, lowest, highest) {
+                value = multiple;
+            }
+        }
+        Number::from_f64(value)
+            .map(Value::Number)
+            .unwrap_or(Value::Null)
+    }
+
+    fn word(&mut self) -> String {
+        const WORDS: [&str; 8] = [
+            "alpha", "bravo", "charlie", "delta", "echo", "foxtrot", "golf", "hotel",
+        ];
+        String::from(WORDS[self.rng.below(WORDS.len())])
+    }
+
+    fn date(&mut self) -> String {
+        format!(
+            "{:04}-{:02}-{:02}",
+            self.rng.between(2000, 2030),
+            self.rng.between(1, 12),
+            self.rng.between(1, 28)
+        )
+    }
+
+    fn time(&mut self) -> String {
+        format!(
+            "{:02}:{:02}:{:02}",
+            self.rng.between(0, 23),
+            self.rng.between(0, 59),
+            self.rng.between(0, 59)
+        )
+    }
+
+    fn uuid(&mut self) -> String {
+        let high = self.rng.next_u64();
+        let low = self.rng.next_u64();
+        format!(
+            "{:08x}-{:04x}-4{:03x}-{:04x}-{:012x}",
+            high >> 32,
+            (high >> 16) & 0xffff,
+            high & 0x0fff,
+            0x8000 | ((low >> 48) & 0x3fff),
+            low & 0xffff_ffff_ffff
+        )
+    }
+}
+
+/// Adds a generated example to every message of the specification, both in channels and in
+/// components, that doesn't declare any. The example of each message only depends on `seed`
+/// and on the message location, not on the iteration order of the document.
+pub fn fill_spec_examples(spec: &mut AsyncApiV3Spec, seed: u64) {
+    let components = spec.components.clone();
+
+    for (channel_name, channel) in spec.channels.iter_mut() {
+        let RefOr::Right(channel) = channel else {
+            continue;
+        };
+        for (message_name, message) in channel.messages.iter_mut() {
+            if let RefOr::Right(message) = message {
+                let seed = rng::derive_seed(seed, &format!("{channel_name}/{message_name}"));
+                ExampleGenerator::new(seed)
+                    .with_components(&components)
+                    .fill_message_examples(message);
+            }
+        }
+    }
+
+    for (message_name, message) in spec.components.messages.iter_mut() {
+        if let RefOr::Right(message) = message {
+            let seed = rng::derive_seed(seed, message_name);
+            ExampleGenerator::new(seed)
+                .with_components(&components)
+                .fill_message_examples(message);
+        }
+    }
+}
+
+/// The JSON schema of a schema object, unwrapping multi format schemas whose format is a JSON
+/// schema. Returns `None` for other schema formats.
+pub(crate) fn json_schema(schema: &Value) -> Option<&Value> {
+    match (schema.get("schemaFormat"), schema.get("schema")) {
+        (Some(Value::String(format)), Some(inner)) => {
+            is_json_schema_format(format).then_some(inner)
+        }
+        _ => Some(schema),
+    }
+}
+
+fn is_json_schema_format(format: &str) -> bool {
+    let media_type = format.split(';').next().unwrap_or_default().trim();
+    matches!(
+        media_type,
+        "application/vnd.aai.asyncapi"
+            | "application/vnd.aai.asyncapi+json"
+            | "application/vnd.aai.asyncapi+yaml"
+            | "application/schema+json"
+            | "application/schema+yaml"
+    )
+}
+
+/// Multiple of `multiple` closest to `value` within `[min, max]`, if the bounds admit any.
+fn nearest_multiple(value: f64, multiple: f64, min: f64, max: f64) -> Option<f64> {
+    let lowest = (min / multiple).ceil();
+    let highest = (max / multiple).floor();
+    (lowest <= highest).then(|| (value / multiple).round().clamp(lowest, highest) * multiple)
+}
+
+/// Exclusive bound of `exclusive_key`, either a number or, in draft 4, `true` making the bound of
+/// `bound_key` exclusive.
+fn exclusive_bound(
+    schema: &Map<String, Value>,
+    exclusive_key: &str,
+    bound_key: &str,
+) -> Option<f64> {
+    match schema.get(exclusive_key)? {
+        Value::Bool(true) => get_f64(schema, bound_key),
+        exclusive => exclusive.as_f64(),
+    }
+}
+
+fn get_f64(schema: &Map<String, Value>, key: &str) -> Option<f64> {
+    schema.get(key).and_then(Value::as_f64)
+}
+
+fn get_u64(schema: &Map<String, Value>, key: &str) -> Option<u64> {
+    schema.get(key).and_then(Value::as_u64)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    fn schema(value: Value) -> schemars::Schema {
+        schemars::Schema::try_from(value).unwrap()
+    }
+
+    #[test]
+    fn generation_is_deterministic_per_seed() {
+        let schema = schema(json!({
+            "type": "object",
+            "properties": {
+                "id": { "type": "string", "format": "uuid" },
+                "count": { "type": "integer" },
+                "tags": { "type": "array", "items": { "type": "string" } }
+            }
+        }));
+
+        let first = ExampleGenerator::new(42).generate(&schema);
+        let second = ExampleGenerator::new(42).generate(&schema);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn generation_respects_constraints() {
+        let schema = schema(json!({
+            "type": "object",
+            "required": ["kind"],
+            "properties": {
+                "kind": { "const": "reading" },
+                "unit": { "enum": ["C", "F"] },
+                "level": { "type": "integer", "default": 3 },
+                "value": { "type": "integer", "minimum": 10, "maximum": 20 },
+                "ratio": { "type": "number", "minimum": 0, "exclusiveMaximum": 1 },
+                "code": { "type": "string", "pattern": "^[A-Z]{3}-\\d{4}$" },
+                "at": { "type": "string", "format": "date-time" },
+                "target": { "oneOf": [{ "type": "boolean" }, { "type": "null" }] }
+            }
+        }));
+
+        for seed in 0..32 {
+            let generated = ExampleGenerator::new(seed).generate(&schema);
+            assert_eq!(generated["kind"], json!("reading"));
+            assert!(["C", "F"].contains(&generated["unit"].as_str().unwrap()));
+            assert_eq!(generated["level"], json!(3));
+            assert!((10..=20).contains(&generated["value"].as_i64().unwrap()));
+            let ratio = generated["ratio"].as_f64().unwrap();
+            assert!((0.0..1.0).contains(&ratio));
+            let code = generated["code"].as_str().unwrap();
+            assert_eq!(code.len(), 8);
+            assert!(code[..3].chars().all(|c| c.is_ascii_uppercase()));
+            assert!(code[4..].chars().all(|c| c.is_ascii_digit()));
+            assert!(generated["at"].as_str().unwrap().ends_with('Z'));
+            assert!(generated["target"].is_boolean() || generated["target"].is_null());
+        }
+    }
+
+    #[test]
+    fn generation_respects_bounded_multiples() {
+        let schema = schema(json!({
+            "type": "object",
+            "properties": {
+                "even": { "type": "integer", "minimum": 1, "maximum": 9, "multipleOf": 2 },
+                "fifths": { "type": "integer", "minimum": 0, "maximum": 12, "multipleOf": 2.5 },
+                "step": { "type": "number", "minimum": 0, "exclusiveMaximum": 1, "multipleOf": 0.25 },
+                "top": { "type": "number", "minimum": 9.5, "maximum": 10.4, "multipleOf": 0.5 }
+            }
+        }));
+
+        for seed in 0..64 {
+            let generated = ExampleGenerator::new(seed).generate(&schema);
+            let even = generated["even"].as_i64().unwrap();
+            assert!((1..=9).contains(&even) && even % 2 == 0, "{even}");
+            let fifths = generated["fifths"].as_i64().unwrap();
+            assert!([0, 5, 10].contains(&fifths), "{fifths}");
+            let step = generated["step"].as_f64().unwrap();
+            assert!([0.0, 0.25, 0.5, 0.75].contains(&step), "{step}");
+            let top = generated["top"].as_f64().unwrap();
+            assert!([9.5, 10.0].contains(&top), "{top}");
+        }
+    }
+
+    #[test]
+    fn generation_handles_extreme_and_draft_4_bounds() {
+        let schema = schema(json!({
+            "type": "object",
+            "properties": {
+                "huge": { "type": "integer", "exclusiveMinimum": 9.3e18 },
+                "tiny": { "type": "integer", "exclusiveMaximum": -9.3e18 },
+                "above": { "type": "integer", "minimum": 5, "maximum": 6, "exclusiveMinimum": true },
+                "inclusive": {
+                    "type": "number",
+                    "minimum": 0,
+                    "maximum": 1,
+                    "exclusiveMinimum": false,
+                    "multipleOf": 1
+                }
+            }
+        }));
+
+        let mut inclusive = vec![];
+        for seed in 0..64 {
+            let generated = ExampleGenerator::new(seed).generate(&schema);
+            assert_eq!(generated["huge"], json!(i64::MAX));
+            assert_eq!(generated["tiny"], json!(i64::MIN));
+            assert_eq!(generated["above"], json!(6));
+            inclusive.push(generated["inclusive"].as_f64().unwrap());
+        }
+        assert!(inclusive.contains(&0.0), "{inclusive:?}");
+    }
+
+    #[test]
+    fn recursive_schemas_terminate() {
+        let schema = schema(json!({
+            "$ref": "#/$defs/Node",
+            "$defs": {
+                "Node": {
+                    "type": "object",
+                    "required": ["value"],
+                    "properties": {
+                        "value": { "type": "integer" },
+                        "next": { "$ref": "#/$defs/Node" }
+                    }
+                }
+            }
+        }));
+
+        let generated = ExampleGenerator::new(7).with_max_depth(4).generate(&schema);
+        assert!(generated["value"].is_i64());
+    }
+
+    fn message(value: Value) -> Message {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn generate_message_example_with_headers() {
+        let described = message(json!({
+            "headers": {
+                "type": "object",
+                "required": ["correlationId"],
+                "properties": { "correlationId": { "type": "string", "format": "uuid" } }
+            },
+            "payload": { "type": "integer", "minimum": 1, "maximum": 3 }
+        }));
+
+        let example = ExampleGenerator::new(3)
+            .generate_message_example(&described)
+            .unwrap();
+        assert_eq!(example.name.as_deref(), Some("generated"));
+        assert!(example.headers["correlationId"].is_string());
+        assert!((1..=3).contains(&example.payload.unwrap().as_i64().unwrap()));
+
+        assert!(ExampleGenerator::new(3)
+            .generate_message_example(&message(json!({})))
+            .is_none());
+    }
+
+    #[test]
+    fn fill_message_examples_keeps_existing_examples() {
+        let mut generated = message(json!({ "payload": { "const": "on" } }));
+        assert!(ExampleGenerator::new(1).fill_message_examples(&mut generated));
+        assert_eq!(generated.examples.len(), 1);
+        assert_eq!(generated.examples[0].payload, Some(json!("on")));
+
+        let mut documented = message(json!({
+            "payload": { "const": "on" },
+            "examples": [{ "name": "off", "payload": "off" }]
+        }));
+        assert!(!ExampleGenerator::new(1).fill_message_examples(&mut documented));
+        assert_eq!(documented.examples.len(), 1);
+        assert_eq!(documented.examples[0].payload, Some(json!("off")));
+    }
+
+    #[test]
+    fn fill_spec_examples_of_channel_and_component_messages() {
+        let mut spec: AsyncApiV3Spec = serde_json::from_value(json!({
+            "asyncapi": "3.0.0",
+            "info": { "title": "Lights", "version": "1.0.0" },
+            "channels": {
+                "lights": {
+                    "address": "lights",
+                    "messages": {
+                        "switched": { "payload": { "$ref": "#/components/schemas/state" } },
+                        "measured": { "$ref": "#/components/messages/measured" }
+                    }
+                }
+            },
+            "components": {
+                "schemas": { "state": { "enum": ["on", "off"] } },
+                "messages": { "measured": { "payload": { "type": "integer", "minimum": 0 } } }
+            }
+        }))
+        .unwrap();
+
+        fill_spec_examples(&mut spec, 5);
+        let RefOr::Right(channel) = &spec.channels["lights"] else {
+            panic!("inline channel expected");
+        };
+        let RefOr::Right(switched) = &channel.messages["switched"] else {
+            panic!("inline message expected");
+        };
+        let state = switched.examples[0].payload.as_ref().unwrap();
+        assert!(state == "on" || state == "off", "{state}");
+        assert!(matches!(&channel.messages["measured"], RefOr::Left(_)));
+        let RefOr::Right(measured) = &spec.components.messages["measured"] else {
+            panic!("inline message expected");
+        };
+        assert!(measured.examples[0].payload.as_ref().unwrap().is_u64());
+    }
+}
@@ -0,0 +1,264 @@
+//! Generation of strings matching a regular expression. Only the subset of the syntax commonly
+//! found in schema `pattern`s is supported (literals, classes, groups, alternations and
+//! quantifiers); unsupported constructs make the generation fail rather than produce a value
+//! that doesn't match.
+use super::rng::SplitMix64;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Upper bound added to the minimum repetition of unbounded quantifiers.
+const UNBOUNDED_EXTRA_REPETITIONS: u32 = 3;
+
+enum Node {
+    Literal(char),
+    Class {
+        ranges: Vec<(char, char)>,
+        negated: bool,
+    },
+    Alternation(Vec<Vec<Node>>),
+    Repeat {
+        node: Box<Node>,
+        min: u32,
+        max: u32,
+    },
+}
+
+/// Generates a string matching `pattern`, returns `None` if the pattern uses unsupported syntax.
+pub(crate) fn generate(pattern: &str, rng: &mut SplitMix64) -> Option<String> {
+    let mut chars = pattern.chars().peekable();
+    let alternatives = parse_alternation(&mut chars)?;
+    if chars.next().is_some() {
+        return None;
+    }
+    let mut generated = String::new();
+    generate_node(&Node::Alternation(alternatives), rng, &mut generated);
+    Some(generated)
+}
+
+fn parse_alternation(chars: &mut Peekable<Chars>) -> Option<Vec<Vec<Node>>> {
+    let mut alternatives = vec![parse_sequence(chars)?];
+    while chars.peek() == Some(&'|') {
+        chars.next();
+        alternatives.push(parse_sequence(chars)?);
+    }
+    Some(alternatives)
+}
+
+fn parse_sequence(chars: &mut Peekable<Chars>) -> Option<Vec<Node>> {
+    let mut sequence = Vec::new();
+    while let Some(&next) = chars.peek() {
+        let atom = match next {
+            '|' | ')' => break,
+            '^' | '$' => {
+                chars.next();
+                continue;
+            }
+            '(' => {
+                chars.next();
+                if chars.peek() == Some(&'?') {
+                    chars.next();
+                    // only non-capturing groups are supported, lookarounds are not
+                    if chars.next() != Some(':') {
+                        return None;
+                    }
+                }
+                let group = parse_alternation(chars)?;
+                if chars.next() != Some(')') {
+                    return None;
+                }
+                Node::Alternation(group)
+            }
+            '[' => {
+                chars.next();
+                parse_class(chars)?
+            }
+            '.' => {
+                chars.next();
+                Node::Class {
+                    ranges: vec![('\n', '\n')],
+                    negated: true,
+                }
+            }
+            '\\' => {
+                chars.next();
+                parse_escape(chars.next()?)?
+            }
+            '*' | '+' | '?' | '{' => return None,
+            literal => {
+                chars.next();
+                Node::Literal(literal)
+            }
+        };
+        sequence.push(parse_quantifier(chars, atom)?);
+    }
+    Some(sequence)
+}
+
+fn parse_quantifier(chars: &mut Peekable<Chars>, atom: Node) -> Option<Node> {
+    let (min, max) = match chars.peek() {
+        Some('?') => (0, 1),
+        Some('*') => (0, UNBOUNDED_EXTRA_REPETITIONS),
+        Some('+') => (1, 1 + UNBOUNDED_EXTRA_REPETITIONS),
+        Some('{') => {
+            chars.next();
+            let mut spec = String::new();
+            loop {
+                match chars.next()? {
+                    '}' => break,
+                    c => spec.push(c),
+                }
+            }
+            let (min, max) = match spec.split_once(',') {
+                None => {
+                    let count = spec.trim().parse().ok()?;
+                    (count, count)
+                }
+                Some((min, max)) => {
+                    let min = min.trim().parse().ok()?;
+                    let max = match max.trim() {
+                        "" => min + UNBOUNDED_EXTRA_REPETITIONS,
+                        max => max.parse().ok()?,
+                    };
+                    (min, max)
+                }
+            };
+            skip_lazy_marker(chars);
+            return Some(Node::Repeat {
+                node: Box::new(atom),
+                min,
+                max: max.max(min),
+            });
+        }
+        _ => return Some(atom),
+    };
+    chars.next();
+    skip_lazy_marker(chars);
+    Some(Node::Repeat {
+        node: Box::new(atom),
+        min,
+        max,
+    })
+}
+
+fn skip_lazy_marker(chars: &mut Peekable<Chars>) {
+    if chars.peek() == Some(&'?') {
+        chars.next();
+    }
+}
+
+fn parse_escape(escaped: char) -> Option<Node> {
+    let class = |ranges: &[(char, char)], negated| Node::Class {
+        ranges: ranges.to_vec(),
+        negated,
+    };
+    Some(match escaped {
+        'd' => class(&DIGITS, false),
+        'D' => class(&DIGITS, true),
+        'w' => class(&WORD, false),
+        'W' => class(&WORD, true),
+        's' => class(&SPACES, false),
+        'S' => class(&SPACES, true),
+        'n' => Node::Literal('\n'),
+        't' => Node::Literal('\t'),
+        'r' => Node::Literal('\r'),
+        'b' | 'B' | '1'..='9' | 'p' | 'P' => return None,
+        other => Node::Literal(other),
+    })
+}
+
+const DIGITS: [(char, char); 1] = [('0', '9')];
+const WORD: [(char, char); 4] = [('a', 'z'), ('A', 'Z'), ('0', '9'), ('_', '_')];
+const SPACES: [(char, char); 1] = [(' ', ' ')];
+
+fn parse_class(chars: &mut Peekable<Chars>) -> Option<Node> {
+    let negated = chars.peek() == Some(&'^');
+    if negated {
+        chars.next();
+    }
+    let mut ranges = Vec::new();
+    let mut first = true;
+    loop {
+        let start = match chars.next()? {
+            ']' if !first => break,
+            '\\' => match parse_escape(chars.next()?)? {
+                Node::Literal(c) => c,
+                Node::Class {
+                    ranges: escaped,
+                    negated: false,
+                } => {
+                    ranges.extend(escaped);
+                    first = false;
+                    continue;
+                }
+                _ => return None,
+            },
+            c => c,
+        };
+        first = false;
+        if chars.peek() == Some(&'-') {
+            chars.next();
+            match chars.peek() {
+                Some(']') => {
+                    ranges.push((start, start));
+                    ranges.push(('-', '-'));
+                }
+                _ => {
+                    let end = match chars.next()? {
+                        '\\' => match parse_escape(chars.next()?)? {
+                            Node::Literal(c) => c,
+                            _ => return None,
+                        },
+                        c => c,
+                    };
+                    if end < start {
+                        return None;
+                    }
+                    ranges.push((start, end));
+                }
+            }
+        } else {
+            ranges.push((start, start));
+        }
+    }
+    Some(Node::Class { ranges, negated })
+}
+
+fn generate_node(node: &Node, rng: &mut SplitMix64, out: &mut String) {
+    match node {
+        Node::Literal(c) => out.push(*c),
+        Node::Class {
+            ranges,
+            negated: false,
+        } => {
+            if ranges.is_empty() {
+                return;
+            }
+            let (start, end) = ranges[rng.below(ranges.len())];
+            let offset = rng.between(0, u64::from(end) - u64::from(start)) as u32;
+            out.push(char::from_u32(u32::from(start) + offset).unwrap_or(start));
+        }
+        Node::Class {
+            ranges,
+            negated: true,
+        } => {
+            let candidates = ('!'..='~')
+                .filter(|c| !ranges.iter().any(|(start, end)| (start..=end).contains(&c)))
+                .collect::<Vec<_>>();
+            if !candidates.is_empty() {
+                out.push(candidates[rng.below(candidates.len())]);
+            }
+        }
+        Node::Alternation(alternatives) => {
+            let sequence = &alternatives[rng.below(alternatives.len())];
+            for node in sequence {
+                generate_node(node, rng, out);
+            }
+        }
+        Node::Repeat { node, min, max } => {
+            let count = rng.between(u64::from(*min), u64::from(*max));
+            for _ in 0..count {
+                generate_node(node, rng, out);
+            }
+        }
+    }
+}
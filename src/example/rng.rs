@@ -0,0 +1,42 @@
+//! Small deterministic pseudo-random number generator, so that generated examples are stable
+//! across platforms and releases.
+
+/// [SplitMix64](https://prng.di.unimi.it/splitmix64.c) generator.
+pub(crate) struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a value in `0..bound`, `bound` must be greater than zero.
+    pub(crate) fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    /// Returns a value in `min..=max`.
+    pub(crate) fn between(&mut self, min: u64, max: u64) -> u64 {
+        match (max - min).checked_add(1) {
+            Some(span) => min + self.next_u64() % span,
+            None => self.next_u64(),
+        }
+    }
+}
+
+/// Combines a seed with a textual key (FNV-1a) to obtain an independent seed.
+pub(crate) fn derive_seed(seed: u64, key: &str) -> u64 {
+    key.bytes()
+        .fold(seed ^ 0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+}
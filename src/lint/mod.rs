@@ -0,0 +1,303 @@
+//! Style checks of specifications. A [`Linter`] runs built-in and custom [`Rule`]s, whose
+//! severity can be configured from a TOML or YAML file:
+//!
+//! ```toml
+//! [rules]
+//! operation-summary = "error"
+//! message-examples = "off"
+//! ```
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use thiserror::Error;
+
+use crate::spec::AsyncApiV3Spec;
+
+pub mod rules;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Invalid {format} lint configuration: {reason}")]
+    InvalidConfig {
+        format: &'static str,
+        reason: String,
+    },
+    #[error("Unknown lint rule '{rule}'")]
+    UnknownRule { rule: String },
+}
+
+#[derive(
+    Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The rule is disabled.
+    Off,
+    Info,
+    Warning,
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Off => write!(f, "off"),
+            Self::Info => write!(f, "info"),
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A check of a specification.
+pub trait Rule {
+    /// Identifier of the rule in the configuration, in kebab-case.
+    fn id(&self) -> &'static str;
+
+    /// Short description of what the rule checks, shown in reports.
+    fn description(&self) -> &'static str {
+        ""
+    }
+
+    /// Severity of the violations when the rule is not configured.
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check(&self, spec: &AsyncApiV3Spec) -> Vec<Violation>;
+}
+
+/// An object of a specification that does not follow a rule.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Violation {
+    /// JSON pointer to the object.
+    pub pointer: String,
+    pub message: String,
+}
+
+impl Violation {
+    pub fn new(pointer: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            pointer: pointer.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// A violation reported by a linter, with the severity of its rule.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct Diagnostic {
+    pub rule: String,
+    pub severity: Severity,
+    /// JSON pointer to the object violating the rule.
+    pub pointer: String,
+    pub message: String,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}[{}] {}: {}",
+            self.severity, self.rule, self.pointer, self.message
+        )
+    }
+}
+
+/// Severities of the rules, by rule identifier.
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LintConfig {
+    #[serde(default)]
+    pub rules: HashMap<String, Severity>,
+}
+
+impl LintConfig {
+    pub fn from_toml(content: &str) -> Result<Self, Error> {
+        toml::from_str(content).map_err(|err| Error::InvalidConfig {
+            format: "TOML",
+            reason: err.message().to_string(),
+        })
+    }
+
+    pub fn from_yaml(content: &str) -> Result<Self, Error> {
+        serde_yaml::from_str(content).map_err(|err| Error::InvalidConfig {
+            format: "YAML",
+            reason: err.to_string(),
+        })
+    }
+}
+
+/// Runs rules over specifications.
+pub struct Linter {
+    rules: Vec<Box<dyn Rule>>,
+    severities: HashMap<String, Severity>,
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Linter {
+    /// Linter running the [built-in rules](rules).
+    pub fn new() -> Self {
+        Self {
+            rules: rules::builtin(),
+            severities: HashMap::new(),
+        }
+    }
+
+    /// Adds a custom rule.
+    pub fn with_rule(mut self, rule: impl Rule + 'static) -> Self {
+        self.rules.push(Box::new(rule));
+        self
+    }
+
+    /// Sets the severities of the rules, that must be added before, failing on unknown rules.
+    pub fn configure(mut self, config: LintConfig) -> Result<Self, Error> {
+        if let Some(rule) = config
+            .rules
+            .keys()
+            .find(|id| !self.rules.iter().any(|rule| rule.id() == id.as_str()))
+        {
+            return Err(Error::UnknownRule { rule: rule.clone() });
+        }
+        self.severities.extend(config.rules);
+        Ok(self)
+    }
+
+    /// Rules of the linter, with their configured severities.
+    pub fn rules(&self) -> impl Iterator<Item = (&dyn Rule, Severity)> + '_ {
+        self.rules.iter().map(|rule| {
+            let severity = self
+                .severities
+                .get(rule.id())
+                .copied()
+                .unwrap_or_else(|| rule.default_severity());
+            (rule.as_ref(), severity)
+        })
+    }
+
+    /// Diagnostics of the enabled rules, sorted by pointer.
+    pub fn lint(&self, spec: &AsyncApiV3Spec) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+        for (rule, severity) in self.rules() {
+            if severity == Severity::Off {
+                continue;
+            }
+            diagnostics.extend(rule.check(spec).into_iter().map(|violation| Diagnostic {
+                rule: String::from(rule.id()),
+                severity,
+                pointer: violation.pointer,
+                message: violation.message,
+            }));
+        }
+        diagnostics.sort_by(|a, b| (&a.pointer, &a.rule).cmp(&(&b.pointer, &b.rule)));
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    fn spec() -> AsyncApiV3Spec {
+        serde_json::from_value(json!({
+            "info": {
+                "title": "Lights",
+                "version": "1.0.0",
+                "contact": { "name": "Lighting team" },
+                "license": { "name": "MIT" }
+            },
+            "defaultContentType": "application/json",
+            "channels": {
+                "lightMeasured": {
+                    "messages": {
+                        "lightMeasured": { "name": "measure", "payload": { "type": "object" } }
+                    }
+                }
+            },
+            "operations": {
+                "receiveLight": {
+                    "action": "receive",
+                    "channel": { "$ref": "#/channels/lightMeasured" },
+                    "summary": "Receives light measures"
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    struct TitleCase;
+
+    impl Rule for TitleCase {
+        fn id(&self) -> &'static str {
+            "info-title-case"
+        }
+
+        fn check(&self, spec: &AsyncApiV3Spec) -> Vec<Violation> {
+            if spec.info.title.starts_with(char::is_uppercase) {
+                return vec![];
+            }
+            vec![Violation::new("/info/title", "title is not capitalized")]
+        }
+    }
+
+    #[test]
+    fn lint_with_configured_severities() {
+        let diagnostics = Linter::new().lint(&spec());
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            [
+                "warning[channel-name-kebab-case] /channels/lightMeasured: channel name 'lightMeasured' is not in kebab-case",
+                "info[message-examples] /channels/lightMeasured/messages/lightMeasured: message without examples",
+            ]
+        );
+
+        let config = LintConfig::from_toml(
+            r#"
+            [rules]
+            channel-name-kebab-case = "off"
+            message-examples = "error"
+            info-title-case = "error"
+            "#,
+        )
+        .unwrap();
+        let mut spec = spec();
+        spec.info.title = String::from("lights");
+        let diagnostics = Linter::new()
+            .with_rule(TitleCase)
+            .configure(config)
+            .unwrap()
+            .lint(&spec);
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|diagnostic| (diagnostic.rule.as_str(), diagnostic.severity))
+                .collect::<Vec<_>>(),
+            [
+                ("message-examples", Severity::Error),
+                ("info-title-case", Severity::Error),
+            ]
+        );
+    }
+
+    #[test]
+    fn reject_invalid_configurations() {
+        let config = LintConfig::from_yaml("rules:\n  info-title-case: warning\n").unwrap();
+        assert!(matches!(
+            Linter::new().configure(config),
+            Err(Error::UnknownRule { rule }) if rule == "info-title-case"
+        ));
+        assert!(matches!(
+            LintConfig::from_toml("[rules]\noperation-summary = \"fatal\""),
+            Err(Error::InvalidConfig { format: "TOML", .. })
+        ));
+    }
+}
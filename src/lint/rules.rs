@@ -0,0 +1,380 @@
+//! Built-in lint rules.
+use std::collections::BTreeMap;
+
+use super::{Rule, Severity, Violation};
+use crate::pointer::json_pointer;
+use crate::references::unused_components;
+use crate::spec::common::Either;
+use crate::spec::message::{Message, MessageTrait};
+use crate::spec::AsyncApiV3Spec;
+
+/// All the built-in rules.
+pub(super) fn builtin() -> Vec<Box<dyn Rule>> {
+    vec![
+        Box::new(OperationSummary),
+        Box::new(MessageExamples),
+        Box::new(UnusedComponents),
+        Box::new(ChannelNameKebabCase),
+        Box::new(InfoContact),
+        Box::new(InfoLicense),
+        Box::new(MessageContentType),
+        Box::new(DuplicateMessageName),
+    ]
+}
+
+/// Operations must have a summary.
+pub struct OperationSummary;
+
+impl Rule for OperationSummary {
+    fn id(&self) -> &'static str {
+        "operation-summary"
+    }
+
+    fn description(&self) -> &'static str {
+        "Operations must have a summary."
+    }
+
+    fn check(&self, spec: &AsyncApiV3Spec) -> Vec<Violation> {
+        spec.operations
+            .iter()
+            .filter(|(_, operation)| {
+                matches!(operation, Either::Right(operation) if operation.summary.is_none())
+            })
+            .map(|(name, _)| {
+                Violation::new(
+                    json_pointer(&["operations", name]),
+                    "operation without summary",
+                )
+            })
+            .collect()
+    }
+}
+
+/// Messages should have examples, either their own or from their traits.
+pub struct MessageExamples;
+
+impl Rule for MessageExamples {
+    fn id(&self) -> &'static str {
+        "message-examples"
+    }
+
+    fn description(&self) -> &'static str {
+        "Messages should have examples, either their own or from their traits."
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Info
+    }
+
+    fn check(&self, spec: &AsyncApiV3Spec) -> Vec<Violation> {
+        inline_messages(spec)
+            .into_iter()
+            .filter(|(_, message)| {
+                message.examples.is_empty()
+                    && !message_traits(spec, message)
+                        .any(|message_trait| !message_trait.examples.is_empty())
+            })
+            .map(|(pointer, _)| Violation::new(pointer, "message without examples"))
+            .collect()
+    }
+}
+
+/// Components must be referenced.
+pub struct UnusedComponents;
+
+impl Rule for UnusedComponents {
+    fn id(&self) -> &'static str {
+        "unused-components"
+    }
+
+    fn description(&self) -> &'static str {
+        "Components must be referenced."
+    }
+
+    fn check(&self, spec: &AsyncApiV3Spec) -> Vec<Violation> {
+        unused_components(spec)
+            .into_iter()
+            .map(|pointer| Violation::new(pointer, "component is never referenced"))
+            .collect()
+    }
+}
+
+/// Channel keys must be in kebab-case.
+pub struct ChannelNameKebabCase;
+
+impl Rule for ChannelNameKebabCase {
+    fn id(&self) -> &'static str {
+        "channel-name-kebab-case"
+    }
+
+    fn description(&self) -> &'static str {
+        "Channel keys must be in kebab-case."
+    }
+
+    fn check(&self, spec: &AsyncApiV3Spec) -> Vec<Violation> {
+        spec.channels
+            .keys()
+            .filter(|name| !is_kebab_case(name))
+            .map(|name| {
+                Violation::new(
+                    json_pointer(&["channels", name]),
+                    format!("channel name '{name}' is not in kebab-case"),
+                )
+            })
+            .collect()
+    }
+}
+
+/// The info must have a contact.
+pub struct InfoContact;
+
+impl Rule for InfoContact {
+    fn id(&self) -> &'static str {
+        "info-contact"
+    }
+
+    fn description(&self) -> &'static str {
+        "The info must have a contact."
+    }
+
+    fn check(&self, spec: &AsyncApiV3Spec) -> Vec<Violation> {
+        match spec.info.contact {
+            Some(_) => vec![],
+            None => vec![Violation::new("/info", "info without contact")],
+        }
+    }
+}
+
+/// The info must have a license.
+pub struct InfoLicense;
+
+impl Rule for InfoLicense {
+    fn id(&self) -> &'static str {
+        "info-license"
+    }
+
+    fn description(&self) -> &'static str {
+        "The info must have a license."
+    }
+
+    fn check(&self, spec: &AsyncApiV3Spec) -> Vec<Violation> {
+        match spec.info.license {
+            Some(_) => vec![],
+            None => vec![Violation::new("/info", "info without license")],
+        }
+    }
+}
+
+/// Messages must have a content type when the specification has no default one.
+pub struct MessageContentType;
+
+impl Rule for MessageContentType {
+    fn id(&self) -> &'static str {
+        "message-content-type"
+    }
+
+    fn description(&self) -> &'static str {
+        "Messages must have a content type when the specification has no default one."
+    }
+
+    fn check(&self, spec: &AsyncApiV3Spec) -> Vec<Violation> {
+        if spec.default_content_type.is_some() {
+            return vec![];
+        }
+        inline_messages(spec)
+            .into_iter()
+            .filter(|(_, message)| {
+                message.content_type.is_none()
+                    && !message_traits(spec, message)
+                        .any(|message_trait| message_trait.content_type.is_some())
+            })
+            .map(|(pointer, _)| {
+                Violation::new(
+                    pointer,
+                    "message without content type and no default content type",
+                )
+            })
+            .collect()
+    }
+}
+
+/// Message names must be unique. Every message but the first one, by pointer, is reported.
+pub struct DuplicateMessageName;
+
+impl Rule for DuplicateMessageName {
+    fn id(&self) -> &'static str {
+        "duplicate-message-name"
+    }
+
+    fn description(&self) -> &'static str {
+        "Message names must be unique."
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn check(&self, spec: &AsyncApiV3Spec) -> Vec<Violation> {
+        let mut first_pointers = BTreeMap::new();
+        let mut violations = vec![];
+        for (pointer, message) in inline_messages(spec) {
+            let Some(name) = &message.name else {
+                continue;
+            };
+            match first_pointers.get(name) {
+                Some(first_pointer) => violations.push(Violation::new(
+                    pointer,
+                    format!("message name '{name}' is also used by {first_pointer}"),
+                )),
+                None => {
+                    first_pointers.insert(name, pointer);
+                }
+            }
+        }
+        violations
+    }
+}
+
+/// Messages defined in the channels and the components, sorted by pointer.
+fn inline_messages(spec: &AsyncApiV3Spec) -> BTreeMap<String, &Message> {
+    let channel_messages = spec
+        .channels
+        .iter()
+        .filter_map(|(channel_name, channel)| match channel {
+            Either::Right(channel) => Some((channel_name, channel)),
+            Either::Left(_) => None,
+        })
+        .flat_map(|(channel_name, channel)| {
+            channel.messages.iter().map(move |(name, message)| {
+                (
+                    json_pointer(&["channels", channel_name, "messages", name]),
+                    message,
+                )
+            })
+        });
+    let component_messages = spec
+        .components
+        .messages
+        .iter()
+        .map(|(name, message)| (json_pointer(&["components", "messages", name]), message));
+    channel_messages
+        .chain(component_messages)
+        .filter_map(|(pointer, message)| match message {
+            Either::Right(message) => Some((pointer, message)),
+            Either::Left(_) => None,
+        })
+        .collect()
+}
+
+/// Traits of a message, following references to the component message traits.
+fn message_traits<'a>(
+    spec: &'a AsyncApiV3Spec,
+    message: &'a Message,
+) -> impl Iterator<Item = &'a MessageTrait> {
+    message
+        .traits
+        .iter()
+        .filter_map(|message_trait| match message_trait {
+            Either::Right(message_trait) => Some(message_trait),
+            Either::Left(reference) => {
+                let name = reference
+                    .reference
+                    .strip_prefix("#/components/messageTraits/")?;
+                match spec.components.message_traits.get(name)? {
+                    Either::Right(message_trait) => Some(message_trait),
+                    Either::Left(_) => None,
+                }
+            }
+        })
+}
+
+/// Whether `name` is made of lowercase words of letters and digits separated by dashes.
+fn is_kebab_case(name: &str) -> bool {
+    name.split('-').all(|word| {
+        !word.is_empty()
+            && word
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    fn pointers(rule: impl Rule, spec: &AsyncApiV3Spec) -> Vec<String> {
+        rule.check(spec)
+            .into_iter()
+            .map(|violation| violation.pointer)
+            .collect()
+    }
+
+    #[test]
+    fn check_builtin_rules() {
+        let spec: AsyncApiV3Spec = serde_json::from_value(json!({
+            "info": { "title": "Lights", "version": "1.0.0" },
+            "channels": {
+                "light-measured": {
+                    "messages": {
+                        "measured": { "name": "measure", "contentType": "application/json" },
+                        "dimmed": { "name": "measure", "traits": [{ "$ref": "#/components/messageTraits/common" }] }
+                    }
+                },
+                "lights_on": { "messages": { "on": { "$ref": "#/components/messages/on" } } }
+            },
+            "operations": {
+                "receiveLight": {
+                    "action": "receive",
+                    "channel": { "$ref": "#/channels/light-measured" }
+                }
+            },
+            "components": {
+                "messages": { "on": { "examples": [{ "payload": true }] } },
+                "messageTraits": {
+                    "common": { "contentType": "application/json", "examples": [{ "payload": 1 }] }
+                },
+                "schemas": { "unused": { "type": "string" } }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            pointers(OperationSummary, &spec),
+            ["/operations/receiveLight"]
+        );
+        assert_eq!(
+            pointers(MessageExamples, &spec),
+            ["/channels/light-measured/messages/measured"]
+        );
+        assert_eq!(
+            pointers(UnusedComponents, &spec),
+            ["/components/schemas/unused"]
+        );
+        assert_eq!(
+            pointers(ChannelNameKebabCase, &spec),
+            ["/channels/lights_on"]
+        );
+        assert_eq!(pointers(InfoContact, &spec), ["/info"]);
+        assert_eq!(pointers(InfoLicense, &spec), ["/info"]);
+        assert_eq!(
+            pointers(MessageContentType, &spec),
+            ["/components/messages/on"]
+        );
+        assert_eq!(
+            DuplicateMessageName.check(&spec),
+            [Violation::new(
+                "/channels/light-measured/messages/measured",
+                "message name 'measure' is also used by /channels/light-measured/messages/dimmed"
+            )]
+        );
+    }
+
+    #[test]
+    fn recognize_kebab_case() {
+        assert!(is_kebab_case("light-measured-2"));
+        assert!(!is_kebab_case("lightMeasured"));
+        assert!(!is_kebab_case("light--measured"));
+        assert!(!is_kebab_case("-light"));
+    }
+}
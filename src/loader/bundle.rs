@@ -0,0 +1,458 @@
+//! Bundling of specifications split across files: the references to other files are replaced by
+//! the values they point to, so that the bundled document only has local references.
+//!
+//! The local references of the other files are resolved the same way, while the references to
+//! the root file are made local. The channels and messages of operations and replies must stay
+//! references: they are made local references to the place where their target is inlined, or to
+//! a channel or component added for them.
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+
+use super::{invalid_yaml, Error, Location, Position, SourceMap};
+use crate::pointer::json_pointer;
+
+/// Bundles the specification of a YAML or JSON file with the files it references.
+pub fn bundle_file(path: impl AsRef<Path>) -> Result<Value, Error> {
+    let path = path.as_ref();
+    let mut bundler = Bundler {
+        root: canonical(path)?,
+        documents: HashMap::new(),
+        stack: vec![],
+        inlined: vec![],
+        pending: vec![],
+    };
+    let mut document = bundler.document(&bundler.root.clone())?.clone();
+    bundler.resolve(
+        &bundler.root.clone(),
+        &mut document,
+        &mut vec![],
+        &mut vec![],
+    )?;
+    while !bundler.pending.is_empty() {
+        let pending = bundler.pending.remove(0);
+        let target = match bundler.locate(&pending.target, &pending.fragment) {
+            Some(target) => target,
+            None => bundler.add_target(&mut document, &pending)?,
+        };
+        let position = json_pointer(&segments(&pending.path));
+        if let Some(value) = document.pointer_mut(&position) {
+            *value = serde_json::json!({ "$ref": format!("#{target}") });
+        }
+    }
+    Ok(document)
+}
+
+struct Bundler {
+    root: PathBuf,
+    /// Documents and their source maps by canonical path.
+    documents: HashMap<PathBuf, (Value, SourceMap)>,
+    /// References being resolved, to detect cycles.
+    stack: Vec<(PathBuf, String)>,
+    /// Path in the bundled document of the inlined values, by file and fragment.
+    inlined: Vec<((PathBuf, String), Vec<String>)>,
+    /// References to other files that must stay references.
+    pending: Vec<PendingReference>,
+}
+
+/// Reference to another file found where the specification requires a reference object.
+struct PendingReference {
+    /// Path of the reference in the bundled document.
+    path: Vec<String>,
+    /// Position of the reference in its file.
+    location: Location,
+    reference: String,
+    target: PathBuf,
+    fragment: String,
+    /// Path of the map where the target is added if it isn't inlined anywhere.
+    section: &'static [&'static str],
+}
+
+impl Bundler {
+    fn document(&mut self, path: &Path) -> Result<&Value, Error> {
+        if !self.documents.contains_key(path) {
+            let content = std::fs::read_to_string(path).map_err(|source| Error::Io {
+                path: path.to_path_buf(),
+                source,
+            })?;
+            let source_map = SourceMap::parse(&content).map_err(|err| err.in_file(path))?;
+            let document = serde_yaml::from_str::<Value>(&content)
+                .map_err(|err| invalid_yaml(&source_map, err).in_file(path))?;
+            self.documents
+                .insert(path.to_path_buf(), (document, source_map));
+        }
+        Ok(&self.documents[path].0)
+    }
+
+    /// Position of the reference found at `source` in `file`.
+    fn reference_location(&self, file: &Path, source: &[String]) -> Location {
+        let source_map = self.documents.get(file).map(|(_, source_map)| source_map);
+        let mut pointer = segments(source);
+        pointer.push("$ref");
+        let position = source_map.and_then(|source_map| {
+            source_map
+                .position(&json_pointer(&pointer))
+                .or_else(|| source_map.position(&json_pointer(&pointer[..pointer.len() - 1])))
+        });
+        Location {
+            file: Some(file.to_path_buf()),
+            position: position.unwrap_or(Position { line: 1, column: 1 }),
+        }
+    }
+
+    /// Resolves the references of `value`, found at `source` in `file` and placed at `path` in
+    /// the bundled document.
+    fn resolve(
+        &mut self,
+        file: &Path,
+        value: &mut Value,
+        path: &mut Vec<String>,
+        source: &mut Vec<String>,
+    ) -> Result<(), Error> {
+        let reference = match value {
+            Value::Object(object) => match object.get("$ref") {
+                Some(Value::String(reference)) => reference.clone(),
+                _ => {
+                    for (key, value) in object.iter_mut() {
+                        path.push(key.clone());
+                        source.push(key.clone());
+                        self.resolve(file, value, path, source)?;
+                        source.pop();
+                        path.pop();
+                    }
+                    return Ok(());
+                }
+            },
+            Value::Array(values) => {
+                for (index, value) in values.iter_mut().enumerate() {
+                    path.push(index.to_string());
+                    source.push(index.to_string());
+                    self.resolve(file, value, path, source)?;
+                    source.pop();
+                    path.pop();
+                }
+                return Ok(());
+            }
+            _ => return Ok(()),
+        };
+
+        let location = self.reference_location(file, source);
+        let unresolved = |reason: String| Error::Reference {
+            location: location.clone(),
+            reference: reference.clone(),
+            reason,
+        };
+        let (target_file, fragment) = reference.split_once('#').unwrap_or((&reference, ""));
+        let target = match target_file {
+            "" => file.to_path_buf(),
+            target_file => {
+                let directory = file.parent().unwrap_or(Path::new(""));
+                canonical(&directory.join(target_file))
+                    .map_err(|_| unresolved(String::from("the file cannot be read")))?
+            }
+        };
+        if target == self.root {
+            *value = serde_json::json!({ "$ref": format!("#{fragment}") });
+            return Ok(());
+        }
+        if let Some(section) = reference_section(&segments(path)) {
+            self.pending.push(PendingReference {
+                path: path.clone(),
+                location: location.clone(),
+                reference: reference.clone(),
+                target,
+                fragment: String::from(fragment),
+                section,
+            });
+            return Ok(());
+        }
+
+        let key = (target.clone(), String::from(fragment));
+        if self.stack.contains(&key) {
+            return Err(unresolved(String::from("circular reference")));
+        }
+        let mut resolved = self
+            .document(&target)?
+            .pointer(fragment)
+            .cloned()
+            .ok_or_else(|| unresolved(format!("no value at {fragment:?}")))?;
+        self.inlined.push((key.clone(), path.clone()));
+        self.stack.push(key);
+        self.resolve(
+            &target,
+            &mut resolved,
+            path,
+            &mut fragment_segments(fragment),
+        )?;
+        self.stack.pop();
+        *value = resolved;
+        Ok(())
+    }
+
+    /// Local pointer to the fragment of `target`, if it is inlined in the bundled document.
+    fn locate(&self, target: &Path, fragment: &str) -> Option<String> {
+        self.inlined.iter().find_map(|((file, inlined), path)| {
+            let remainder = fragment.strip_prefix(inlined.as_str())?;
+            (file == target && (remainder.is_empty() || remainder.starts_with('/')))
+                .then(|| format!("{}{remainder}", json_pointer(&segments(path))))
+        })
+    }
+
+    /// Adds the target of a pending reference to its section of the bundled document, and returns
+    /// its local pointer.
+    fn add_target(
+        &mut self,
+        document: &mut Value,
+        pending: &PendingReference,
+    ) -> Result<String, Error> {
+        let unresolved = |reason: String| Error::Reference {
+            location: pending.location.clone(),
+            reference: pending.reference.clone(),
+            reason,
+        };
+        let mut resolved = self
+            .document(&pending.target)?
+            .pointer(&pending.fragment)
+            .cloned()
+            .ok_or_else(|| unresolved(format!("no value at {:?}", pending.fragment)))?;
+
+        let mut section = &mut *document;
+        for key in pending.section {
+            let Value::Object(object) = section else {
+                return Err(unresolved(format!("{key:?} cannot be added")));
+            };
+            section = object
+                .entry(String::from(*key))
+                .or_insert_with(|| Value::Object(Map::new()));
+        }
+        let Value::Object(section) = section else {
+            return Err(unresolved(String::from("the target cannot be added")));
+        };
+        let base = match pending.fragment.rsplit('/').next() {
+            Some(name) if !name.is_empty() => name.replace("~1", "/").replace("~0", "~"),
+            _ => pending
+                .target
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+        };
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while section.contains_key(&name) {
+            name = format!("{base}{suffix}");
+            suffix += 1;
+        }
+        section.insert(name.clone(), Value::Null);
+
+        let mut path = pending
+            .section
+            .iter()
+            .map(|key| String::from(*key))
+            .collect::<Vec<_>>();
+        path.push(name);
+        let key = (pending.target.clone(), pending.fragment.clone());
+        self.inlined.push((key.clone(), path.clone()));
+        self.stack.push(key);
+        self.resolve(
+            &pending.target,
+            &mut resolved,
+            &mut path.clone(),
+            &mut fragment_segments(&pending.fragment),
+        )?;
+        self.stack.pop();
+        let pointer = json_pointer(&segments(&path));
+        if let Some(value) = document.pointer_mut(&pointer) {
+            *value = resolved;
+        }
+        Ok(pointer)
+    }
+}
+
+/// Where the target of a reference found at `path` is added when the specification requires a
+/// reference object there, e.g. the channel of an operation.
+fn reference_section(path: &[&str]) -> Option<&'static [&'static str]> {
+    match path {
+        ["operations", _, "channel"] | ["operations", _, "reply", "channel"] => Some(&["channels"]),
+        ["components", "operations", _, "channel"]
+        | ["components", "operations", _, "reply", "channel"]
+        | ["components", "replies", _, "channel"] => Some(&["components", "channels"]),
+        ["operations", _, "messages", _]
+        | ["operations", _, "reply", "messages", _]
+        | ["components", "operations", _, "messages", _]
+        | ["components", "operations", _, "reply", "messages", _]
+        | ["components", "replies", _, "messages", _] => Some(&["components", "messages"]),
+        _ => None,
+    }
+}
+
+fn segments(path: &[String]) -> Vec<&str> {
+    path.iter().map(String::as_str).collect()
+}
+
+/// Unescaped segments of a JSON pointer fragment.
+fn fragment_segments(fragment: &str) -> Vec<String> {
+    fragment
+        .split('/')
+        .skip(1)
+        .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+        .collect()
+}
+
+fn canonical(path: &Path) -> Result<PathBuf, Error> {
+    path.canonicalize().map_err(|source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn write(directory: &Path, name: &str, content: &str) {
+        std::fs::write(directory.join(name), content).unwrap();
+    }
+
+    #[test]
+    fn inline_references_to_other_files() {
+        let directory = std::env::temp_dir().join(format!(
+            "asyncapiv3-bundle-{}-inline-references",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(directory.join("schemas")).unwrap();
+        write(
+            &directory,
+            "asyncapi.yml",
+            "\
+asyncapi: 3.0.0
+info: { title: Lights, version: 1.0.0 }
+channels:
+  lightMeasured:
+    messages:
+      measured:
+        $ref: 'messages.yml#/measured'
+components:
+  schemas:
+    lumens: { type: integer }
+",
+        );
+        write(
+            &directory,
+            "messages.yml",
+            "\
+measured:
+  payload:
+    $ref: 'schemas/measure.json'
+",
+        );
+        write(
+            &directory,
+            "schemas/measure.json",
+            r##"{
+  "type": "object",
+  "properties": {
+    "lumens": { "$ref": "../asyncapi.yml#/components/schemas/lumens" },
+    "sentAt": { "$ref": "#/definitions/timestamp" }
+  },
+  "definitions": { "timestamp": { "type": "string", "format": "date-time" } }
+}"##,
+        );
+
+        let bundled = bundle_file(directory.join("asyncapi.yml")).unwrap();
+        let payload = &bundled["channels"]["lightMeasured"]["messages"]["measured"]["payload"];
+        assert_eq!(
+            payload["properties"],
+            serde_json::json!({
+                "lumens": { "$ref": "#/components/schemas/lumens" },
+                "sentAt": { "type": "string", "format": "date-time" },
+            })
+        );
+        serde_json::from_value::<crate::spec::AsyncApiSpec>(bundled).unwrap();
+
+        write(
+            &directory,
+            "messages.yml",
+            "measured:\n  $ref: '#/measured'\n",
+        );
+        let err = bundle_file(directory.join("asyncapi.yml")).unwrap_err();
+        assert!(err.to_string().ends_with("circular reference"), "{err}");
+        let location = err.location().unwrap();
+        assert!(location.file.as_ref().unwrap().ends_with("messages.yml"));
+        assert_eq!(location.position.line, 2);
+
+        write(&directory, "messages.yml", "measured: {}\n");
+        write(
+            &directory,
+            "asyncapi.yml",
+            "asyncapi: 3.0.0\ninfo:\n  $ref: 'info.yml'\n",
+        );
+        let err = bundle_file(directory.join("asyncapi.yml")).unwrap_err();
+        assert!(
+            matches!(&err, Error::Reference { reference, .. } if reference == "info.yml"),
+            "{err}"
+        );
+        assert_eq!(err.location().unwrap().position.line, 3);
+
+        write(
+            &directory,
+            "asyncapi.yml",
+            "\
+asyncapi: 3.0.0
+info: { title: Lights, version: 1.0.0 }
+channels:
+  lightMeasured:
+    $ref: 'channels.yml#/lightMeasured'
+operations:
+  receiveLight:
+    action: receive
+    channel:
+      $ref: 'channels.yml#/lightMeasured'
+    messages:
+      - $ref: 'channels.yml#/lightMeasured/messages/measured'
+  sendLight:
+    action: send
+    channel:
+      $ref: 'channels.yml#/lightSent'
+",
+        );
+        write(
+            &directory,
+            "messages.yml",
+            "measured:\n  payload: { type: integer }\n",
+        );
+        write(
+            &directory,
+            "channels.yml",
+            "\
+lightMeasured:
+  messages:
+    measured:
+      $ref: 'messages.yml#/measured'
+lightSent:
+  address: lights/sent
+  messages: {}
+",
+        );
+        let bundled = bundle_file(directory.join("asyncapi.yml")).unwrap();
+        let operations = &bundled["operations"];
+        assert_eq!(
+            operations["receiveLight"]["channel"],
+            serde_json::json!({ "$ref": "#/channels/lightMeasured" })
+        );
+        assert_eq!(
+            operations["receiveLight"]["messages"],
+            serde_json::json!([{ "$ref": "#/channels/lightMeasured/messages/measured" }])
+        );
+        assert_eq!(
+            operations["sendLight"]["channel"],
+            serde_json::json!({ "$ref": "#/channels/lightSent" })
+        );
+        assert_eq!(
+            bundled["channels"]["lightSent"],
+            serde_json::json!({ "address": "lights/sent", "messages": {} })
+        );
+        serde_json::from_value::<crate::spec::AsyncApiSpec>(bundled).unwrap();
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
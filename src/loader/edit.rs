@@ -0,0 +1,766 @@
+//! Edition of YAML specifications that keeps their comments, anchors and formatting.
+//!
+//! Edits are applied to the [`spec`](crate::spec) model, and only the values they change are
+//! rewritten in the source: every other byte of the document is left as is.
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+use yaml_rust2::scanner::TScalarStyle;
+
+use super::source_map::{Node, NodeKind, SourceMap};
+use super::Error;
+use crate::pointer::json_pointer;
+use crate::spec::{AsyncApiSpec, AsyncApiV3Spec};
+
+/// A specification with its YAML source, edited without losing comments and anchors.
+#[derive(Clone, Debug)]
+pub struct Document {
+    source: String,
+    spec: AsyncApiV3Spec,
+    source_map: SourceMap,
+}
+
+impl Document {
+    /// Parses a YAML or JSON document.
+    pub fn parse(source: impl Into<String>) -> Result<Self, Error> {
+        let source = source.into();
+        let loaded = super::load_str(&source)?;
+        let AsyncApiSpec::V3_0_0(spec) = loaded.spec;
+        Ok(Self {
+            source,
+            spec,
+            source_map: loaded.source_map,
+        })
+    }
+
+    /// Reads a YAML or JSON document from a file.
+    pub fn load_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path).map_err(|source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::parse(source).map_err(|err| err.in_file(path))
+    }
+
+    /// Writes the document to a file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        std::fs::write(path, &self.source).map_err(|source| Error::Io {
+            path: PathBuf::from(path),
+            source,
+        })
+    }
+
+    /// The specification of the document.
+    pub fn spec(&self) -> &AsyncApiV3Spec {
+        &self.spec
+    }
+
+    /// The source of the document.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Applies `edit` to the specification and rewrites the values it changes in the source.
+    ///
+    /// Changed scalars are replaced in place, keeping their comments and anchors, added
+    /// properties are appended to their mapping, and removed properties are deleted. Other
+    /// changed values are rewritten as a whole. The document is left unchanged when the rewritten
+    /// source does not describe the edited specification.
+    pub fn edit(&mut self, edit: impl FnOnce(&mut AsyncApiV3Spec)) -> Result<(), Error> {
+        let mut spec = self.spec.clone();
+        edit(&mut spec);
+        let old = to_value(&self.spec)?;
+        let new = to_value(&spec)?;
+
+        let editor = Editor::new(&self.source, &self.source_map, &spec, &new);
+        let mut patches = editor.diff("", &old, &new);
+        patches.sort_by_key(|patch| patch.start);
+        let mut source = String::with_capacity(self.source.len());
+        let mut copied = 0;
+        for patch in patches {
+            source.push_str(&self.source[copied..patch.start]);
+            source.push_str(&patch.text);
+            copied = patch.end;
+        }
+        source.push_str(&self.source[copied..]);
+
+        let document = Self::parse(source).map_err(|err| Error::Edit {
+            reason: format!("the edited document is invalid: {err}"),
+        })?;
+        if document.spec != spec {
+            return Err(Error::Edit {
+                reason: String::from("the edited document does not match the specification"),
+            });
+        }
+        *self = document;
+        Ok(())
+    }
+}
+
+fn to_value(spec: &AsyncApiV3Spec) -> Result<Value, Error> {
+    serde_json::to_value(spec).map_err(|err| Error::Edit {
+        reason: err.to_string(),
+    })
+}
+
+/// Replacement of `start..end` in the source.
+struct Patch {
+    start: usize,
+    end: usize,
+    text: String,
+}
+
+/// Lines of a property or sequence item.
+struct Entry {
+    /// Offset of the key or of the `-` indicator.
+    start: usize,
+    /// Offset after the last line.
+    end: usize,
+    /// Column of the key or of the `-` indicator, from 0.
+    indent: usize,
+    /// Whether the entry is a sequence item.
+    item: bool,
+}
+
+struct Editor<'a> {
+    source: &'a str,
+    /// Offsets of the lines.
+    lines: Vec<usize>,
+    source_map: &'a SourceMap,
+    /// The edited specification.
+    spec: &'a AsyncApiV3Spec,
+    new: &'a Value,
+}
+
+impl<'a> Editor<'a> {
+    fn new(
+        source: &'a str,
+        source_map: &'a SourceMap,
+        spec: &'a AsyncApiV3Spec,
+        new: &'a Value,
+    ) -> Self {
+        let lines = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(offset, _)| offset + 1))
+            .collect();
+        Self {
+            source,
+            lines,
+            source_map,
+            spec,
+            new,
+        }
+    }
+
+    fn diff(&self, pointer: &str, old: &Value, new: &Value) -> Vec<Patch> {
+        if old == new {
+            return Vec::new();
+        }
+        let patches = match (self.source_map.node(pointer), old, new) {
+            // the properties of flow mappings can only be changed in place
+            (Some(node), Value::Object(old), Value::Object(new))
+                if node.kind == NodeKind::Mapping
+                    && !new.is_empty()
+                    && (!self.is_flow(node) || old.keys().eq(new.keys())) =>
+            {
+                self.diff_mapping(pointer, old, new)
+            }
+            (Some(node), Value::Array(old), Value::Array(new))
+                if node.kind == NodeKind::Sequence && old.len() == new.len() =>
+            {
+                Some(
+                    old.iter()
+                        .zip(new)
+                        .enumerate()
+                        .flat_map(|(index, (old, new))| {
+                            self.diff(&format!("{pointer}/{index}"), old, new)
+                        })
+                        .collect(),
+                )
+            }
+            _ => None,
+        };
+        patches.unwrap_or_else(|| self.replace(pointer, new))
+    }
+
+    fn diff_mapping(
+        &self,
+        pointer: &str,
+        old: &Map<String, Value>,
+        new: &Map<String, Value>,
+    ) -> Option<Vec<Patch>> {
+        let child = |key: &str| format!("{pointer}{}", json_pointer(&[key]));
+        // new properties are indented as the existing ones
+        let indent = old
+            .keys()
+            .find_map(|key| self.source_map.node(&child(key)))
+            .map(|node| node.position.column - 1)?;
+
+        let mut patches = Vec::new();
+        for key in old.keys().filter(|key| !new.contains_key(*key)) {
+            if self.source_map.node(&child(key)).is_some() {
+                patches.push(self.remove(&child(key))?);
+            }
+        }
+        let mut added = String::new();
+        for (key, value) in new {
+            let pointer = child(key);
+            match (old.get(key), self.source_map.node(&pointer)) {
+                (Some(old), Some(_)) => patches.extend(self.diff(&pointer, old, value)),
+                (None, Some(_)) => patches.extend(self.replace(&pointer, value)),
+                // values missing from the source are serialized with their defaults
+                (Some(old), None) if old == value => {}
+                (_, None) => {
+                    let value = self.pruned(&pointer, value);
+                    if value.is_null() {
+                        continue;
+                    }
+                    added.push_str(&" ".repeat(indent));
+                    added.push_str(&render_property(key, &value, indent));
+                    added.push('\n');
+                }
+            }
+        }
+        if !added.is_empty() {
+            let end = match pointer {
+                "" => self.document_end(),
+                _ => self.entry(pointer)?.end,
+            };
+            if end > 0 && !self.source[..end].ends_with('\n') {
+                added.insert(0, '\n');
+            }
+            patches.push(Patch {
+                start: end,
+                end,
+                text: added,
+            });
+        }
+        Some(patches)
+    }
+
+    /// Deletes the lines of a property.
+    fn remove(&self, pointer: &str) -> Option<Patch> {
+        let entry = self.entry(pointer)?;
+        let start = self.line_start(entry.start);
+        if entry.item || !self.source[start..entry.start].trim().is_empty() {
+            return None;
+        }
+        Some(Patch {
+            start,
+            end: entry.end,
+            text: String::new(),
+        })
+    }
+
+    /// Rewrites the value at `pointer`, or its closest ancestor that can be rewritten.
+    fn replace(&self, pointer: &str, new: &Value) -> Vec<Patch> {
+        if let Some(patch) = self.replace_scalar(pointer, new) {
+            return vec![patch];
+        }
+        let Some((parent, segment)) = pointer.rsplit_once('/') else {
+            return vec![Patch {
+                start: 0,
+                end: self.source.len(),
+                text: render(new) + "\n",
+            }];
+        };
+        let Some(entry) = self.entry(pointer) else {
+            return match self.new.pointer(parent) {
+                Some(value) => self.replace(parent, value),
+                None => self.replace("", self.new),
+            };
+        };
+        let new = self.pruned(pointer, new);
+        let mut text = if entry.item {
+            render_item(&new, entry.indent)
+        } else {
+            let key = segment.replace("~1", "/").replace("~0", "~");
+            render_property(&key, &new, entry.indent)
+        };
+        if self.source[..entry.end].ends_with('\n') {
+            text.push('\n');
+        }
+        vec![Patch {
+            start: entry.start,
+            end: entry.end,
+            text,
+        }]
+    }
+
+    /// Replaces a scalar or an alias written on a single line, keeping what follows it.
+    fn replace_scalar(&self, pointer: &str, new: &Value) -> Option<Patch> {
+        let node = self.source_map.node(pointer)?;
+        let (parent, _) = pointer.rsplit_once('/')?;
+        let parent = self.source_map.node(parent)?;
+        let flow = self.is_flow(parent);
+
+        let start = self.offset(node.value.line, node.value.column)?;
+        let line = &self.source[start..self.line_end(start)];
+        let len = match node.kind {
+            NodeKind::Scalar(TScalarStyle::Plain) => {
+                let len = plain_len(line, flow);
+                // plain scalars continue on the more indented lines
+                let indent = match parent.kind {
+                    NodeKind::Mapping => node.position.column - 1,
+                    _ => self.indent(node.value.line),
+                };
+                if !flow && self.continues(node.value.line, indent) {
+                    return None;
+                }
+                len
+            }
+            NodeKind::Scalar(TScalarStyle::SingleQuoted) => quoted_len(line, '\'')?,
+            NodeKind::Scalar(TScalarStyle::DoubleQuoted) => quoted_len(line, '"')?,
+            NodeKind::Alias => line
+                .find(|c: char| c.is_whitespace() || ",]}".contains(c))
+                .unwrap_or(line.len()),
+            _ => return None,
+        };
+        let text = match (node.kind, new) {
+            _ if flow => serde_json::to_string(new).ok()?,
+            (NodeKind::Scalar(TScalarStyle::DoubleQuoted), Value::String(_)) => {
+                serde_json::to_string(new).ok()?
+            }
+            (NodeKind::Scalar(TScalarStyle::SingleQuoted), Value::String(value)) => {
+                format!("'{}'", value.replace('\'', "''"))
+            }
+            _ => render(new),
+        };
+        if text.contains('\n') {
+            return None;
+        }
+        Some(Patch {
+            start,
+            end: start + len,
+            text,
+        })
+    }
+
+    /// Removes the empty collections of the value at `pointer` that are defaults, so that they
+    /// are not written in the source. `Null` is returned when the value itself is removed.
+    fn pruned(&self, pointer: &str, value: &Value) -> Value {
+        let mut empty = Vec::new();
+        collect_empty(pointer, value, &mut empty);
+        let mut pruned = self.new.clone();
+        for pointer in empty {
+            if self.source_map.node(&pointer).is_some() {
+                continue;
+            }
+            let Some((parent, key)) = pointer.rsplit_once('/') else {
+                continue;
+            };
+            let key = key.replace("~1", "/").replace("~0", "~");
+            let mut candidate = pruned.clone();
+            if let Some(Value::Object(parent)) = candidate.pointer_mut(parent) {
+                parent.shift_remove(&key);
+            }
+            if serde_json::from_value::<AsyncApiV3Spec>(candidate.clone())
+                .ok()
+                .as_ref()
+                == Some(self.spec)
+            {
+                pruned = candidate;
+            }
+        }
+        pruned.pointer(pointer).cloned().unwrap_or(Value::Null)
+    }
+
+    /// Lines of the property or sequence item at `pointer`, when in a block collection.
+    fn entry(&self, pointer: &str) -> Option<Entry> {
+        let node = self.source_map.node(pointer)?;
+        let (parent, _) = pointer.rsplit_once('/')?;
+        let parent = self.source_map.node(parent)?;
+        if self.is_flow(parent) {
+            return None;
+        }
+        let (start, indent, item) = match parent.kind {
+            NodeKind::Mapping => {
+                let start = self.offset(node.position.line, node.position.column)?;
+                (start, node.position.column - 1, false)
+            }
+            _ => {
+                // the position of an item is the one of its value, after the indicator
+                let value = self.offset(node.position.line, node.position.column)?;
+                let line_start = self.line_start(value);
+                let start =
+                    line_start + self.source[line_start..value].match_indices("- ").last()?.0;
+                (start, self.source[line_start..start].chars().count(), true)
+            }
+        };
+        // sequences can be indented as the key of their property
+        let compact = !item && node.kind == NodeKind::Sequence;
+        let mut last = node.position.line;
+        for line in node.position.line + 1..=self.lines.len() {
+            let text = self.line(line);
+            let trimmed = text.trim_start();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            let line_indent = text.len() - trimmed.len();
+            let dash = trimmed == "-" || trimmed.starts_with("- ");
+            if line_indent > indent || (compact && line_indent == indent && dash) {
+                last = line;
+            } else {
+                break;
+            }
+        }
+        Some(Entry {
+            start,
+            end: self.line_end_with_newline(last),
+            indent,
+            item,
+        })
+    }
+
+    /// Offset after the last line of content, before the trailing comments.
+    fn document_end(&self) -> usize {
+        (1..=self.lines.len())
+            .rev()
+            .find(|line| {
+                let trimmed = self.line(*line).trim();
+                !trimmed.is_empty() && !trimmed.starts_with('#') && trimmed != "..."
+            })
+            .map_or(0, |line| self.line_end_with_newline(line))
+    }
+
+    /// Whether the lines following `line` that are more indented than `indent` have content.
+    fn continues(&self, line: usize, indent: usize) -> bool {
+        (line + 1..=self.lines.len())
+            .map(|line| self.line(line))
+            .find(|text| {
+                let trimmed = text.trim_start();
+                !trimmed.is_empty() && !trimmed.starts_with('#')
+            })
+            .is_some_and(|text| text.len() - text.trim_start().len() > indent)
+    }
+
+    fn is_flow(&self, node: &Node) -> bool {
+        matches!(node.kind, NodeKind::Mapping | NodeKind::Sequence)
+            && self
+                .offset(node.value.line, node.value.column)
+                .is_some_and(|offset| self.source[offset..].starts_with(['{', '[']))
+    }
+
+    /// Text of a line, from 1, without its line break.
+    fn line(&self, line: usize) -> &str {
+        let start = self.lines[line - 1];
+        self.source[start..self.line_end(start)].trim_end_matches('\r')
+    }
+
+    fn indent(&self, line: usize) -> usize {
+        let text = self.line(line);
+        text.len() - text.trim_start().len()
+    }
+
+    /// Offset of a position, from line 1 and column 1.
+    fn offset(&self, line: usize, column: usize) -> Option<usize> {
+        let start = *self.lines.get(line.checked_sub(1)?)?;
+        let text = &self.source[start..self.line_end(start)];
+        let offset = match text.char_indices().nth(column.checked_sub(1)?) {
+            Some((offset, _)) => offset,
+            None => text.len(),
+        };
+        Some(start + offset)
+    }
+
+    fn line_start(&self, offset: usize) -> usize {
+        self.source[..offset]
+            .rfind('\n')
+            .map_or(0, |index| index + 1)
+    }
+
+    fn line_end(&self, offset: usize) -> usize {
+        self.source[offset..]
+            .find('\n')
+            .map_or(self.source.len(), |index| offset + index)
+    }
+
+    fn line_end_with_newline(&self, line: usize) -> usize {
+        self.lines.get(line).copied().unwrap_or(self.source.len())
+    }
+}
+
+/// Length of a plain scalar at the start of `line`, before its comment.
+fn plain_len(line: &str, flow: bool) -> usize {
+    let mut previous = ' ';
+    let end = line
+        .char_indices()
+        .find(|(index, c)| {
+            let ends = (*c == '#' && previous.is_whitespace() && *index > 0)
+                || (flow && ",]}".contains(*c));
+            previous = *c;
+            ends
+        })
+        .map_or(line.len(), |(index, _)| index);
+    line[..end].trim_end().len()
+}
+
+/// Length of a quoted scalar at the start of `line`, when it ends on this line.
+fn quoted_len(line: &str, quote: char) -> Option<usize> {
+    let mut chars = line.char_indices().skip(1);
+    while let Some((index, c)) = chars.next() {
+        if quote == '"' && c == '\\' {
+            chars.next();
+        } else if c == quote {
+            // quotes are escaped by doubling them in single quoted scalars
+            if quote == '\'' && line[index + 1..].starts_with('\'') {
+                chars.next();
+            } else {
+                return Some(index + 1);
+            }
+        }
+    }
+    None
+}
+
+/// Pointers of the empty collections that are properties in `value`, `value` included.
+fn collect_empty(pointer: &str, value: &Value, empty: &mut Vec<String>) {
+    match value {
+        Value::Object(object) if object.is_empty() => empty.push(String::from(pointer)),
+        Value::Array(values) if values.is_empty() => empty.push(String::from(pointer)),
+        Value::Object(object) => {
+            for (key, value) in object {
+                collect_empty(&format!("{pointer}{}", json_pointer(&[key])), value, empty);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn render(value: &Value) -> String {
+    let rendered = serde_yaml::to_string(value).unwrap_or_default();
+    String::from(rendered.trim_end_matches('\n'))
+}
+
+fn is_block(value: &Value) -> bool {
+    match value {
+        Value::Object(object) => !object.is_empty(),
+        Value::Array(values) => !values.is_empty(),
+        _ => false,
+    }
+}
+
+/// Property rendered at column `indent`, without the indentation of its first line.
+fn render_property(key: &str, value: &Value, indent: usize) -> String {
+    let key = render(&Value::String(String::from(key)));
+    let rendered = render(value);
+    if is_block(value) {
+        let mut text = format!("{key}:");
+        for line in rendered.lines() {
+            text.push('\n');
+            text.push_str(&" ".repeat(indent + 2));
+            text.push_str(line);
+        }
+        text
+    } else {
+        indent_lines(&format!("{key}: {rendered}"), indent)
+    }
+}
+
+/// Sequence item rendered at column `indent`, without the indentation of its first line.
+fn render_item(value: &Value, indent: usize) -> String {
+    let indent = if is_block(value) { indent + 2 } else { indent };
+    indent_lines(&format!("- {}", render(value)), indent)
+}
+
+/// Indents the lines of `text` following the first one.
+fn indent_lines(text: &str, indent: usize) -> String {
+    let padding = " ".repeat(indent);
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| match index {
+            0 => String::from(line),
+            _ => format!("{padding}{line}"),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::spec::channel::Channel;
+    use crate::spec::common::{Either, RefOr};
+
+    const SOURCE: &str = "\
+# Lights service
+asyncapi: 3.0.0
+info:
+  title: Lights # service name
+  version: &version 1.0.0
+  description: \"Version 1.0.0\"
+channels:
+  lightMeasured:
+    address: lights/measured # sensors
+    messages:
+      lightMeasured: &measured
+        payload:
+          type: object
+  lightDimmed:
+    address: 'lights/dimmed'
+    messages:
+      lightMeasured: *measured
+    tags: [{ name: lights }]
+
+components:
+  messages: {}
+# end
+";
+
+    fn channel(value: Value) -> RefOr<Channel> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn edit_scalars_in_place() {
+        let mut document = Document::parse(SOURCE).unwrap();
+        document
+            .edit(|spec| {
+                spec.info.version = String::from("1.1.0");
+                spec.info.description = Some(String::from("Version 1.1.0"));
+            })
+            .unwrap();
+        assert_eq!(
+            document.source(),
+            SOURCE
+                .replace("&version 1.0.0", "&version 1.1.0")
+                .replace("\"Version 1.0.0\"", "\"Version 1.1.0\"")
+        );
+        assert_eq!(document.spec().info.version, "1.1.0");
+
+        document
+            .edit(|spec| {
+                let Some(Either::Right(channel)) = spec.channels.get_mut("lightDimmed") else {
+                    unreachable!()
+                };
+                channel.address = Some(String::from("lights/dimmed/v2"));
+                channel.tags[0].name = String::from("dimmer");
+            })
+            .unwrap();
+        assert!(document
+            .source()
+            .contains("    address: 'lights/dimmed/v2'\n"));
+        assert!(document
+            .source()
+            .contains("    tags: [{ name: \"dimmer\" }]\n"));
+    }
+
+    #[test]
+    fn add_and_remove_properties() {
+        let mut document = Document::parse(SOURCE).unwrap();
+        document
+            .edit(|spec| {
+                spec.channels.shift_remove("lightMeasured");
+                spec.channels.insert(
+                    String::from("lightOff"),
+                    channel(serde_json::json!({
+                        "address": "lights/off",
+                        "messages": {},
+                    })),
+                );
+                spec.components.messages.insert(
+                    String::from("lightOff"),
+                    serde_json::from_value(serde_json::json!({ "name": "off" })).unwrap(),
+                );
+            })
+            .unwrap_err();
+        // the anchor of the removed channel is used by the other one
+        assert_eq!(document.source(), SOURCE);
+
+        document
+            .edit(|spec| {
+                spec.channels.insert(
+                    String::from("lightOff"),
+                    channel(serde_json::json!({
+                        "address": "lights/off",
+                        "messages": {},
+                    })),
+                );
+                spec.components.messages.insert(
+                    String::from("lightOff"),
+                    serde_json::from_value(serde_json::json!({ "name": "off" })).unwrap(),
+                );
+            })
+            .unwrap();
+        assert_eq!(
+            document.source(),
+            SOURCE
+                .replace(
+                    "    tags: [{ name: lights }]\n",
+                    "    tags: [{ name: lights }]\n  lightOff:\n    address: lights/off\n    messages: {}\n"
+                )
+                .replace(
+                    "components:\n  messages: {}\n",
+                    "components:\n  messages:\n    lightOff:\n      name: off\n"
+                )
+        );
+
+        document
+            .edit(|spec| {
+                spec.channels.shift_remove("lightOff");
+            })
+            .unwrap();
+        assert_eq!(
+            document.source(),
+            SOURCE.replace(
+                "components:\n  messages: {}\n",
+                "components:\n  messages:\n    lightOff:\n      name: off\n"
+            )
+        );
+    }
+
+    #[test]
+    fn reject_edits_changing_aliases() {
+        let mut document = Document::parse(SOURCE).unwrap();
+        let err = document
+            .edit(|spec| {
+                let Some(Either::Right(channel)) = spec.channels.get_mut("lightMeasured") else {
+                    unreachable!()
+                };
+                channel.messages.clear();
+                channel.messages.insert(
+                    String::from("lightMeasured"),
+                    serde_json::from_value(serde_json::json!({ "name": "measured" })).unwrap(),
+                );
+            })
+            .unwrap_err();
+        assert!(matches!(err, Error::Edit { .. }));
+        assert_eq!(document.source(), SOURCE);
+    }
+
+    #[test]
+    fn bump_versions_of_examples() {
+        for entry in std::fs::read_dir("test-res/3.0.0").unwrap() {
+            let path = entry.unwrap().path();
+            let mut document = Document::load_file(&path).unwrap();
+            let version = document.spec().info.version.clone();
+            document
+                .edit(|spec| spec.info.version = String::from("9.9.9"))
+                .unwrap();
+
+            let source = std::fs::read_to_string(&path).unwrap();
+            let changed = source
+                .lines()
+                .zip(document.source().lines())
+                .filter(|(old, new)| old != new)
+                .collect::<Vec<_>>();
+            assert_eq!(changed.len(), 1, "{}", path.display());
+            assert_eq!(changed[0].0.replace(&version, "9.9.9"), changed[0].1);
+            assert_eq!(source.len() + 5, document.source().len() + version.len());
+
+            document
+                .edit(|spec| {
+                    spec.operations.shift_remove_index(0);
+                    spec.channels.insert(
+                        String::from("added"),
+                        channel(serde_json::json!({ "messages": {} })),
+                    );
+                    for tag in &mut spec.info.tags {
+                        tag.name.push_str("-v2");
+                    }
+                })
+                .unwrap();
+        }
+    }
+}
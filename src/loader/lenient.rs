@@ -0,0 +1,570 @@
+//! Tolerant loading of specifications, that reports all the problems of a document instead of
+//! stopping at the first one. Invalid values are dropped, or replaced by defaults when they are
+//! required by the root of the specification, and the unknown fields of bindings are ignored.
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::{invalid_yaml, Error, Location, SourceMap};
+use crate::pointer::json_pointer;
+use crate::references::unresolved_references;
+use crate::spec::channel::{Channel, ChannelBindings, Parameter};
+use crate::spec::common::{ExternalDocumentation, Tag};
+use crate::spec::component::Components;
+use crate::spec::info::Info;
+use crate::spec::message::{CorrelationId, Message, MessageBindings, MessageTrait};
+use crate::spec::operation::{
+    Operation, OperationBindings, OperationReply, OperationReplyAddress, OperationTrait,
+};
+use crate::spec::security::SecurityScheme;
+use crate::spec::server::{Server, ServerBindings, Variable};
+use crate::spec::{document_version, AsyncApiV3Spec, DocumentVersion};
+
+/// A problem of a document.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Issue {
+    /// JSON pointer to the value in the document.
+    pub pointer: String,
+    pub location: Option<Location>,
+    pub message: String,
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(location) = &self.location {
+            write!(f, "{location}: ")?;
+        }
+        let pointer = if self.pointer.is_empty() {
+            "/"
+        } else {
+            &self.pointer
+        };
+        write!(f, "{pointer}: {}", self.message)
+    }
+}
+
+/// A specification loaded as far as possible, with the problems of the document.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LenientSpec {
+    pub spec: AsyncApiV3Spec,
+    pub source_map: SourceMap,
+    /// File the specification was loaded from.
+    pub file: Option<PathBuf>,
+    /// Invalid values, that were dropped or replaced by defaults.
+    pub errors: Vec<Issue>,
+    /// Unknown fields of bindings, that were ignored.
+    pub warnings: Vec<Issue>,
+}
+
+/// Loads a specification from a YAML or JSON string, failing only on syntax errors.
+pub fn load_str(content: &str) -> Result<LenientSpec, Error> {
+    let source_map = SourceMap::parse(content)?;
+    let document =
+        serde_yaml::from_str::<Value>(content).map_err(|err| invalid_yaml(&source_map, err))?;
+    let mut repair = Repair {
+        source_map: &source_map,
+        removed_items: HashMap::new(),
+        fixed: vec![],
+        errors: vec![],
+        warnings: vec![],
+    };
+    for (pointer, reference) in unresolved_references(&document) {
+        let message = format!("unresolved reference {reference:?}");
+        repair.error(&document, &pointer, message);
+    }
+    let spec = repair.deserialize(document);
+    let (mut errors, mut warnings) = (repair.errors, repair.warnings);
+    for issues in [&mut errors, &mut warnings] {
+        issues.sort_by_key(|issue| {
+            let position = issue.location.as_ref().map(|location| location.position);
+            (position, issue.pointer.clone())
+        });
+    }
+    Ok(LenientSpec {
+        spec,
+        source_map,
+        file: None,
+        errors,
+        warnings,
+    })
+}
+
+/// Loads a specification from a YAML or JSON file, failing only on access and syntax errors.
+pub fn load_file(path: impl AsRef<Path>) -> Result<LenientSpec, Error> {
+    let path = path.as_ref();
+    let content = std::fs::read_to_string(path).map_err(|source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let mut loaded = load_str(&content).map_err(|err| err.in_file(path))?;
+    for issue in loaded.errors.iter_mut().chain(&mut loaded.warnings) {
+        if let Some(location) = &mut issue.location {
+            location.file = Some(path.to_path_buf());
+        }
+    }
+    Ok(LenientSpec {
+        file: Some(path.to_path_buf()),
+        ..loaded
+    })
+}
+
+struct Repair<'a> {
+    source_map: &'a SourceMap,
+    /// Original indices of the items removed from the arrays, by original pointer of the array.
+    removed_items: HashMap<String, Vec<usize>>,
+    /// Pointers of the dropped or replaced values, in the current document.
+    fixed: Vec<String>,
+    errors: Vec<Issue>,
+    warnings: Vec<Issue>,
+}
+
+impl Repair<'_> {
+    fn deserialize(&mut self, mut document: Value) -> AsyncApiV3Spec {
+        match document_version(&document).unwrap_or(DocumentVersion::Missing) {
+            DocumentVersion::Supported => {}
+            DocumentVersion::Unsupported(version) => {
+                let message = format!("unsupported AsyncAPI version {version:?}");
+                self.error(&document, "/asyncapi", message);
+            }
+            DocumentVersion::Missing => {
+                self.error(&document, "", String::from("missing field `asyncapi`"))
+            }
+        }
+        if !document.is_object() {
+            let message = String::from("invalid type: expected an object");
+            self.error(&document, "", message);
+            document = Value::Object(Default::default());
+        }
+
+        // every fix removes a value or fills a missing field of the root or the info
+        loop {
+            let err = match serde_path_to_error::deserialize::<_, AsyncApiV3Spec>(&document) {
+                Ok(spec) => return spec,
+                Err(err) => err,
+            };
+            let (pointer, message) = refine(&document, path_pointer(err.path()), err.into_inner());
+            if !self.fix(&mut document, &pointer, message) {
+                return serde_json::from_value(serde_json::json!({
+                    "info": { "title": "", "version": "" }
+                }))
+                .expect("minimal specification");
+            }
+        }
+    }
+
+    /// Fixes the problem at `pointer`, returning whether the document changed.
+    fn fix(&mut self, document: &mut Value, pointer: &str, message: String) -> bool {
+        if message.starts_with("unknown field") {
+            self.warning(document, pointer, message);
+            return self.remove(document, pointer);
+        }
+        if let Some(field) = message
+            .strip_prefix("missing field `")
+            .and_then(|rest| rest.split('`').next())
+            .map(String::from)
+        {
+            let field_pointer = format!("{pointer}{}", json_pointer(&[&field]));
+            // the field may be missing because its value or its parent was fixed
+            let consequence = self.fixed.contains(&field_pointer)
+                || self.fixed.iter().any(|fixed| fixed == pointer);
+            if let Some(default) = default_value(pointer, &field) {
+                if !consequence {
+                    self.error(document, pointer, message);
+                }
+                let Some(Value::Object(object)) = document.pointer_mut(pointer) else {
+                    return false;
+                };
+                object.insert(field, default);
+                self.fixed.push(field_pointer);
+                return true;
+            }
+            if consequence {
+                return self.remove(document, pointer);
+            }
+        }
+        self.error(document, pointer, message);
+        if pointer.is_empty() || pointer == "/info" {
+            let Some(value) = document.pointer_mut(pointer) else {
+                return false;
+            };
+            if value.as_object().is_some_and(|object| object.is_empty()) {
+                return false;
+            }
+            *value = Value::Object(Default::default());
+            self.fixed.push(String::from(pointer));
+            return true;
+        }
+        self.remove(document, pointer)
+    }
+
+    fn remove(&mut self, document: &mut Value, pointer: &str) -> bool {
+        let Some((parent_pointer, key)) = pointer.rsplit_once('/') else {
+            return false;
+        };
+        let key = key.replace("~1", "/").replace("~0", "~");
+        let original_parent = self.original_pointer(document, parent_pointer);
+        let removed = match document.pointer_mut(parent_pointer) {
+            Some(Value::Object(object)) => object.shift_remove(&key).is_some(),
+            Some(Value::Array(items)) => match key.parse::<usize>() {
+                Ok(idx) if idx < items.len() => {
+                    items.remove(idx);
+                    let original_idx = original_index(
+                        self.removed_items.get(&original_parent).map(Vec::as_slice),
+                        idx,
+                    );
+                    let removed_items = self.removed_items.entry(original_parent).or_default();
+                    removed_items.push(original_idx);
+                    removed_items.sort_unstable();
+                    true
+                }
+                _ => false,
+            },
+            _ => false,
+        };
+        if removed {
+            self.fixed.push(String::from(pointer));
+        }
+        removed
+    }
+
+    /// Pointer in the original document of the value at `pointer` in the current document.
+    fn original_pointer(&self, document: &Value, pointer: &str) -> String {
+        let mut current = String::new();
+        let mut original = String::new();
+        for segment in pointer.split('/').skip(1) {
+            let is_item = matches!(document.pointer(&current), Some(Value::Array(_)));
+            let segment = match segment.parse::<usize>() {
+                Ok(idx) if is_item => {
+                    original_index(self.removed_items.get(&original).map(Vec::as_slice), idx)
+                        .to_string()
+                }
+                _ => String::from(segment),
+            };
+            current = format!("{current}/{segment}");
+            original = format!("{original}/{segment}");
+        }
+        original
+    }
+
+    fn error(&mut self, document: &Value, pointer: &str, message: String) {
+        let issue = self.issue(document, pointer, message);
+        self.errors.push(issue);
+    }
+
+    fn warning(&mut self, document: &Value, pointer: &str, message: String) {
+        let issue = self.issue(document, pointer, message);
+        self.warnings.push(issue);
+    }
+
+    fn issue(&self, document: &Value, pointer: &str, message: String) -> Issue {
+        let pointer = self.original_pointer(document, pointer);
+        Issue {
+            location: self.source_map.position(&pointer).map(|position| Location {
+                file: None,
+                position,
+            }),
+            pointer,
+            message,
+        }
+    }
+}
+
+/// Index in the original array of the item at `idx` once the `removed` items were removed.
+fn original_index(removed: Option<&[usize]>, idx: usize) -> usize {
+    let mut original = idx;
+    for removed in removed.unwrap_or_default() {
+        if *removed <= original {
+            original += 1;
+        }
+    }
+    original
+}
+
+/// Default of the required fields whose parent cannot be dropped.
+fn default_value(pointer: &str, field: &str) -> Option<Value> {
+    match (pointer, field) {
+        ("", "info") => Some(Value::Object(Default::default())),
+        ("/info", "title" | "version") => Some(Value::String(String::new())),
+        _ => None,
+    }
+}
+
+fn path_pointer(path: &serde_path_to_error::Path) -> String {
+    let segments = path
+        .iter()
+        .filter_map(|segment| match segment {
+            serde_path_to_error::Segment::Seq { index } => Some(index.to_string()),
+            serde_path_to_error::Segment::Map { key } => Some(key.clone()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    json_pointer(&segments.iter().map(String::as_str).collect::<Vec<_>>())
+}
+
+/// Locates the errors of the values that are either references or objects, for which serde only
+/// reports that the value matches neither, by deserializing them as objects.
+fn refine(document: &Value, mut pointer: String, err: serde_json::Error) -> (String, String) {
+    let mut message = err.to_string();
+    while message.starts_with("data did not match any variant of untagged enum") {
+        let (Some(kind), Some(value)) = (Kind::of(&pointer), document.pointer(&pointer)) else {
+            break;
+        };
+        let Err((inner_pointer, inner_message)) = kind.check(value) else {
+            break;
+        };
+        if inner_pointer.is_empty() && inner_message == message {
+            break;
+        }
+        pointer.push_str(&inner_pointer);
+        message = inner_message;
+    }
+    (pointer, message)
+}
+
+/// Objects of a specification that can be replaced by references.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Kind {
+    Spec,
+    Info,
+    Components,
+    Server,
+    Variable,
+    Channel,
+    Parameter,
+    Operation,
+    OperationTrait,
+    OperationReply,
+    OperationReplyAddress,
+    Message,
+    MessageTrait,
+    CorrelationId,
+    SecurityScheme,
+    ExternalDocumentation,
+    Tag,
+    ServerBindings,
+    ChannelBindings,
+    OperationBindings,
+    MessageBindings,
+}
+
+/// Position in the tree of the specification objects.
+#[derive(Clone, Copy)]
+enum Node {
+    Object(Kind),
+    /// Map or array of objects.
+    Entries(Kind),
+}
+
+impl Kind {
+    /// Kind of the object at `pointer`, if it is one of the specification objects.
+    fn of(pointer: &str) -> Option<Self> {
+        let mut node = Node::Object(Self::Spec);
+        for segment in pointer.split('/').skip(1) {
+            node = match node {
+                Node::Entries(kind) => Node::Object(kind),
+                Node::Object(kind) => kind.child(&segment.replace("~1", "/").replace("~0", "~"))?,
+            };
+        }
+        match node {
+            Node::Object(kind) => Some(kind),
+            Node::Entries(_) => None,
+        }
+    }
+
+    fn child(self, field: &str) -> Option<Node> {
+        use Kind::*;
+        let node = match (self, field) {
+            (Spec, "info") => Node::Object(Info),
+            (Spec, "components") => Node::Object(Components),
+            (Spec | Components, "servers") => Node::Entries(Server),
+            (Spec | Components, "channels") => Node::Entries(Channel),
+            (Spec | Components, "operations") => Node::Entries(Operation),
+            (Components, "messages") => Node::Entries(Message),
+            (Components, "securitySchemes") => Node::Entries(SecurityScheme),
+            (Components, "serverVariables") => Node::Entries(Variable),
+            (Components, "parameters") => Node::Entries(Parameter),
+            (Components, "correlationIds") => Node::Entries(CorrelationId),
+            (Components, "replies") => Node::Entries(OperationReply),
+            (Components, "replyAddresses") => Node::Entries(OperationReplyAddress),
+            (Components, "externalDocs") => Node::Entries(ExternalDocumentation),
+            (Components, "tags") => Node::Entries(Tag),
+            (Components, "operationTraits") => Node::Entries(OperationTrait),
+            (Components, "messageTraits") => Node::Entries(MessageTrait),
+            (Components, "serverBindings") => Node::Entries(ServerBindings),
+            (Components, "channelBindings") => Node::Entries(ChannelBindings),
+            (Components, "operationBindings") => Node::Entries(OperationBindings),
+            (Components, "messageBindings") => Node::Entries(MessageBindings),
+            (Server, "variables") => Node::Entries(Variable),
+            (Server | Operation, "security") => Node::Entries(SecurityScheme),
+            (OperationTrait, "security") => Node::Object(SecurityScheme),
+            (Server, "bindings") => Node::Object(ServerBindings),
+            (Channel, "parameters") => Node::Entries(Parameter),
+            (Channel, "messages") => Node::Entries(Message),
+            (Channel, "bindings") => Node::Object(ChannelBindings),
+            (Operation, "traits") => Node::Entries(OperationTrait),
+            (Operation, "reply") => Node::Object(OperationReply),
+            (Operation | OperationTrait, "bindings") => Node::Object(OperationBindings),
+            (OperationReply, "address") => Node::Object(OperationReplyAddress),
+            (Message, "traits") => Node::Entries(MessageTrait),
+            (Message | MessageTrait, "correlationId") => Node::Object(CorrelationId),
+            (Message | MessageTrait, "bindings") => Node::Object(MessageBindings),
+            (
+                Info | Server | Channel | Operation | OperationTrait | Message | MessageTrait | Tag,
+                "externalDocs",
+            ) => Node::Object(ExternalDocumentation),
+            (
+                Info | Server | Channel | Operation | OperationTrait | Message | MessageTrait,
+                "tags",
+            ) => Node::Entries(Tag),
+            _ => return None,
+        };
+        Some(node)
+    }
+
+    /// Deserializes `value` as an object of this kind, returning the relative pointer and the
+    /// message of the error.
+    fn check(self, value: &Value) -> Result<(), (String, String)> {
+        match self {
+            Self::Spec => check::<AsyncApiV3Spec>(value),
+            Self::Info => check::<Info>(value),
+            Self::Components => check::<Components>(value),
+            Self::Server => check::<Server>(value),
+            Self::Variable => check::<Variable>(value),
+            Self::Channel => check::<Channel>(value),
+            Self::Parameter => check::<Parameter>(value),
+            Self::Operation => check::<Operation>(value),
+            Self::OperationTrait => check::<OperationTrait>(value),
+            Self::OperationReply => check::<OperationReply>(value),
+            Self::OperationReplyAddress => check::<OperationReplyAddress>(value),
+            Self::Message => check::<Message>(value),
+            Self::MessageTrait => check::<MessageTrait>(value),
+            Self::CorrelationId => check::<CorrelationId>(value),
+            Self::SecurityScheme => check::<SecurityScheme>(value),
+            Self::ExternalDocumentation => check::<ExternalDocumentation>(value),
+            Self::Tag => check::<Tag>(value),
+            Self::ServerBindings => check::<ServerBindings>(value),
+            Self::ChannelBindings => check::<ChannelBindings>(value),
+            Self::OperationBindings => check::<OperationBindings>(value),
+            Self::MessageBindings => check::<MessageBindings>(value),
+        }
+    }
+}
+
+fn check<T: DeserializeOwned>(value: &Value) -> Result<(), (String, String)> {
+    serde_path_to_error::deserialize::<_, T>(value)
+        .map(drop)
+        .map_err(|err| (path_pointer(err.path()), err.into_inner().to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::spec::common::Either;
+
+    fn messages(issues: &[Issue]) -> Vec<String> {
+        issues.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn collect_all_errors() {
+        let loaded = load_str(
+            "\
+asyncapi: 3.0.0
+info:
+  title: Lights
+  tags:
+    - description: no name
+    - name: lights
+    - description: no name either
+channels:
+  lightMeasured:
+    bindings:
+      ws:
+        method: GET
+        retries: 3
+    messages:
+      measured:
+        payload: { type: object }
+      dimmed:
+        correlationId: { description: no location }
+  lightsOn:
+    address: 42
+    messages: {}
+operations:
+  receiveLight:
+    action: listen
+    channel: { $ref: '#/channels/lightMeasured' }
+  sendLight:
+    action: send
+    channel: { $ref: '#/channels/lightsOn' }
+",
+        )
+        .unwrap();
+
+        assert_eq!(
+            messages(&loaded.errors),
+            [
+                "2:1: /info: missing field `version`",
+                "5:7: /info/tags/0: missing field `name`",
+                "7:7: /info/tags/2: missing field `name`",
+                "18:9: /channels/lightMeasured/messages/dimmed/correlationId: missing field `location`",
+                "20:5: /channels/lightsOn/address: invalid type: integer `42`, expected a string",
+                "24:5: /operations/receiveLight/action: unknown variant `listen`, expected `send` or `receive`",
+            ]
+        );
+        assert_eq!(
+            messages(&loaded.warnings),
+            ["13:9: /channels/lightMeasured/bindings/ws/retries: unknown field `retries`, expected one of `method`, `query`, `headers`, `bindingVersion`"]
+        );
+
+        let spec = loaded.spec;
+        assert_eq!(spec.info.version, "");
+        assert_eq!(spec.info.tags.len(), 1);
+        let Either::Right(channel) = &spec.channels["lightMeasured"] else {
+            panic!("channel should be inline");
+        };
+        assert!(channel.messages.contains_key("measured"));
+        assert!(channel.messages.contains_key("dimmed"));
+        assert!(spec.channels.contains_key("lightsOn"));
+        assert!(!spec.operations.contains_key("receiveLight"));
+        assert!(spec.operations.contains_key("sendLight"));
+    }
+
+    #[test]
+    fn substitute_the_root() {
+        let loaded = load_str("asyncapi: 2.6.0\n").unwrap();
+        assert_eq!(
+            messages(&loaded.errors),
+            [
+                "1:1: /: missing field `info`",
+                "1:1: /asyncapi: unsupported AsyncAPI version \"2.6.0\"",
+            ]
+        );
+        assert_eq!(loaded.spec.info.title, "");
+    }
+
+    #[test]
+    fn report_unresolved_references() {
+        let loaded = load_str(
+            "\
+asyncapi: 3.0.0
+info: { title: Lights, version: 1.0.0 }
+channels:
+  lightMeasured:
+    messages:
+      measured:
+        $ref: '#/components/messages/measured'
+      dimmed:
+        $ref: 'messages.yml#/dimmed'
+",
+        )
+        .unwrap();
+        assert_eq!(
+            messages(&loaded.errors),
+            ["7:9: /channels/lightMeasured/messages/measured/$ref: unresolved reference \"#/components/messages/measured\""]
+        );
+    }
+}
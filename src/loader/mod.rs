@@ -0,0 +1,222 @@
+//! Loading of specifications that keeps the position of every value in the source, to report
+//! diagnostics at `file:line:column`: the pointers of lint diagnostics, builder errors and
+//! specification changes can be located with [`LoadedSpec::locate`].
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use crate::spec::{document_version, AsyncApiSpec, DocumentVersion};
+
+pub mod bundle;
+pub mod edit;
+pub mod lenient;
+pub mod source_map;
+
+pub use source_map::{Position, SourceMap};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Cannot access {path:?}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("{location}: invalid syntax: {reason}")]
+    Syntax { location: Location, reason: String },
+    #[error("{location}: invalid specification: {reason}")]
+    Invalid { location: Location, reason: String },
+    #[error("Cannot edit the document: {reason}")]
+    Edit { reason: String },
+    #[error("{location}: cannot resolve {reference:?}: {reason}")]
+    Reference {
+        location: Location,
+        reference: String,
+        reason: String,
+    },
+}
+
+impl Error {
+    /// Location of the error in the source, if any.
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            Self::Io { .. } | Self::Edit { .. } => None,
+            Self::Syntax { location, .. }
+            | Self::Invalid { location, .. }
+            | Self::Reference { location, .. } => Some(location),
+        }
+    }
+
+    fn in_file(mut self, path: &Path) -> Self {
+        if let Self::Syntax { location, .. } | Self::Invalid { location, .. } = &mut self {
+            location.file = Some(path.to_path_buf());
+        }
+        self
+    }
+}
+
+/// Position in a source file, or in a string when `file` is missing.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Location {
+    pub file: Option<PathBuf>,
+    pub position: Position,
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.file {
+            Some(file) => write!(f, "{}:{}", file.display(), self.position),
+            None => write!(f, "{}", self.position),
+        }
+    }
+}
+
+/// A specification with the positions of its values in the source.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoadedSpec {
+    pub spec: AsyncApiSpec,
+    pub source_map: SourceMap,
+    /// File the specification was loaded from.
+    pub file: Option<PathBuf>,
+}
+
+impl LoadedSpec {
+    /// Location of the value at `pointer`, or of its closest ancestor in the source.
+    pub fn locate(&self, pointer: &str) -> Option<Location> {
+        Some(Location {
+            file: self.file.clone(),
+            position: self.source_map.position(pointer)?,
+        })
+    }
+}
+
+/// Loads a specification from a YAML or JSON string.
+pub fn load_str(content: &str) -> Result<LoadedSpec, Error> {
+    let source_map = SourceMap::parse(content)?;
+    let invalid = |pointer: &str, reason: String| Error::Invalid {
+        location: Location {
+            file: None,
+            position: source_map
+                .position(pointer)
+                .unwrap_or(Position { line: 1, column: 1 }),
+        },
+        reason,
+    };
+
+    let version = document_version(serde_yaml::Deserializer::from_str(content))
+        .map_err(|err| invalid_yaml(&source_map, err))?;
+    let spec = match version {
+        DocumentVersion::Supported => AsyncApiSpec::V3_0_0(
+            serde_yaml::from_str(content).map_err(|err| invalid_yaml(&source_map, err))?,
+        ),
+        DocumentVersion::Unsupported(version) => {
+            let reason = format!("unsupported AsyncAPI version {version}");
+            return Err(invalid("/asyncapi", reason));
+        }
+        DocumentVersion::Missing => {
+            return Err(invalid("", String::from("missing field `asyncapi`")))
+        }
+    };
+    Ok(LoadedSpec {
+        spec,
+        source_map,
+        file: None,
+    })
+}
+
+fn invalid_yaml(source_map: &SourceMap, err: serde_yaml::Error) -> Error {
+    let mut reason = err.to_string();
+    let position = match err.location() {
+        Some(location) => {
+            let suffix = format!(" at line {} column {}", location.line(), location.column());
+            if let Some(stripped) = reason.strip_suffix(&suffix) {
+                reason = String::from(stripped);
+            }
+            Position {
+                line: location.line(),
+                column: location.column(),
+            }
+        }
+        None => source_map
+            .position("")
+            .unwrap_or(Position { line: 1, column: 1 }),
+    };
+    Error::Invalid {
+        location: Location {
+            file: None,
+            position,
+        },
+        reason,
+    }
+}
+
+/// Loads a specification from a YAML or JSON file.
+pub fn load_file(path: impl AsRef<Path>) -> Result<LoadedSpec, Error> {
+    let path = path.as_ref();
+    let content = std::fs::read_to_string(path).map_err(|source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let loaded = load_str(&content).map_err(|err| err.in_file(path))?;
+    Ok(LoadedSpec {
+        file: Some(path.to_path_buf()),
+        ..loaded
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn locate_values_and_errors() {
+        let loaded = load_str(
+            "\
+asyncapi: 3.0.0
+info:
+  title: Lights
+  version: 1.0.0
+channels:
+  lightMeasured:
+    address: lights/measured
+    messages: {}
+",
+        )
+        .unwrap();
+        let location = loaded.locate("/channels/lightMeasured/address").unwrap();
+        assert_eq!(location.to_string(), "7:5");
+
+        let err = load_str("asyncapi: 3.0.0\ninfo:\n  title: Lights\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "3:3: invalid specification: info: missing field `version`"
+        );
+        let err = load_str("asyncapi: 2.6.0\ninfo: {}\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "1:1: invalid specification: unsupported AsyncAPI version 2.6.0"
+        );
+        let err = load_str("asyncapi: 3.0.0\ninfo: [\n").unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Syntax {
+                location: Location {
+                    position: Position { line: 3, .. },
+                    ..
+                },
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn locate_in_files() {
+        let loaded = load_file("test-res/3.0.0/streetlights-kafka-asyncapi.yml").unwrap();
+        let location = loaded.locate("/info/title").unwrap();
+        assert_eq!(
+            location.file.as_deref(),
+            Some(Path::new("test-res/3.0.0/streetlights-kafka-asyncapi.yml"))
+        );
+        assert_eq!(location.position.column, 3);
+    }
+}
@@ -0,0 +1,252 @@
+//! Positions of the values of a YAML or JSON document.
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::{Marker, TScalarStyle};
+
+use crate::pointer::json_pointer;
+
+/// Position in a source, starting at line 1 and column 1.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, serde::Serialize)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl From<Marker> for Position {
+    fn from(marker: Marker) -> Self {
+        Self {
+            line: marker.line(),
+            column: marker.col() + 1,
+        }
+    }
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Positions of the values of a document by JSON pointer. The position of an object property
+/// is the one of its key.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SourceMap {
+    nodes: HashMap<String, Node>,
+}
+
+/// A value of the document.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) struct Node {
+    /// Position of the key of a property, or of the value.
+    pub position: Position,
+    /// Position of the value. Block mappings start at the end of their first key.
+    pub value: Position,
+    pub kind: NodeKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum NodeKind {
+    Scalar(TScalarStyle),
+    Mapping,
+    Sequence,
+    Alias,
+}
+
+impl SourceMap {
+    /// Maps the first document of a YAML source. JSON documents are valid YAML documents.
+    pub fn parse(content: &str) -> Result<Self, super::Error> {
+        let mut builder = SourceMapBuilder::default();
+        Parser::new_from_str(content)
+            .load(&mut builder, false)
+            .map_err(|err| super::Error::Syntax {
+                location: super::Location {
+                    file: None,
+                    position: Position::from(*err.marker()),
+                },
+                reason: String::from(err.info()),
+            })?;
+        Ok(Self {
+            nodes: builder.nodes,
+        })
+    }
+
+    /// Value at `pointer`, if it is in the document.
+    pub(super) fn node(&self, pointer: &str) -> Option<&Node> {
+        self.nodes.get(pointer)
+    }
+
+    /// Position of the value at `pointer`, or of its closest ancestor in the document when the
+    /// value is missing.
+    pub fn position(&self, pointer: &str) -> Option<Position> {
+        let mut pointer = pointer;
+        loop {
+            if let Some(node) = self.nodes.get(pointer) {
+                return Some(node.position);
+            }
+            pointer = &pointer[..pointer.rfind('/')?];
+        }
+    }
+}
+
+enum Frame {
+    Mapping {
+        pointer: String,
+        /// Key of the next value, `None` when it is not a scalar.
+        key: Option<(Option<String>, Position)>,
+        /// Whether no key has been read yet.
+        first: bool,
+    },
+    Sequence {
+        pointer: String,
+        index: usize,
+    },
+}
+
+#[derive(Default)]
+struct SourceMapBuilder {
+    nodes: HashMap<String, Node>,
+    stack: Vec<Frame>,
+    /// Depth inside a collection that is not mapped, such as a complex key.
+    ignored: usize,
+}
+
+impl MarkedEventReceiver for SourceMapBuilder {
+    fn on_event(&mut self, event: Event, marker: Marker) {
+        let starts = matches!(event, Event::MappingStart(..) | Event::SequenceStart(..));
+        let ends = matches!(event, Event::MappingEnd | Event::SequenceEnd);
+        if self.ignored > 0 {
+            if starts {
+                self.ignored += 1;
+            } else if ends {
+                self.ignored -= 1;
+            }
+            return;
+        }
+        if ends {
+            self.stack.pop();
+            return;
+        }
+        if !starts && !matches!(event, Event::Scalar(..) | Event::Alias(_)) {
+            return;
+        }
+
+        let position = Position::from(marker);
+        let (pointer, position) = match self.stack.last_mut() {
+            None => (Some(String::new()), position),
+            Some(Frame::Sequence { pointer, index }) => {
+                let item = format!("{pointer}/{index}");
+                *index += 1;
+                (Some(item), position)
+            }
+            Some(Frame::Mapping {
+                pointer,
+                key,
+                first,
+            }) => match key.take() {
+                Some((name, key_position)) => (
+                    name.map(|name| format!("{pointer}{}", json_pointer(&[&name]))),
+                    key_position,
+                ),
+                None => {
+                    // block mappings start after their first key
+                    if std::mem::take(first) {
+                        if let Some(node) = self.nodes.get_mut(pointer.as_str()) {
+                            node.position = position.min(node.position);
+                        }
+                    }
+                    let name = match &event {
+                        Event::Scalar(name, ..) => Some(name.clone()),
+                        _ => None,
+                    };
+                    *key = Some((name, position));
+                    if starts {
+                        self.ignored = 1;
+                    }
+                    return;
+                }
+            },
+        };
+        let Some(pointer) = pointer else {
+            if starts {
+                self.ignored = 1;
+            }
+            return;
+        };
+        let kind = match &event {
+            Event::Scalar(_, style, ..) => NodeKind::Scalar(*style),
+            Event::MappingStart(..) => NodeKind::Mapping,
+            Event::SequenceStart(..) => NodeKind::Sequence,
+            _ => NodeKind::Alias,
+        };
+        let node = Node {
+            position,
+            value: Position::from(marker),
+            kind,
+        };
+        self.nodes.insert(pointer.clone(), node);
+        match event {
+            Event::MappingStart(..) => self.stack.push(Frame::Mapping {
+                pointer,
+                key: None,
+                first: true,
+            }),
+            Event::SequenceStart(..) => self.stack.push(Frame::Sequence { pointer, index: 0 }),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn position(line: usize, column: usize) -> Option<Position> {
+        Some(Position { line, column })
+    }
+
+    #[test]
+    fn map_yaml_positions() {
+        let source_map = SourceMap::parse(
+            "\
+info:
+  title: Lights
+channels:
+  lights/on:
+    messages:
+      - name: on
+      - { name: off }
+? [complex, key]
+: ignored
+tags: [a]
+",
+        )
+        .unwrap();
+
+        assert_eq!(source_map.position(""), position(1, 1));
+        assert_eq!(source_map.position("/info/title"), position(2, 3));
+        assert_eq!(source_map.position("/channels/lights~1on"), position(4, 3));
+        assert_eq!(
+            source_map.position("/channels/lights~1on/messages/0/name"),
+            position(6, 9)
+        );
+        assert_eq!(
+            source_map.position("/channels/lights~1on/messages/1/name"),
+            position(7, 11)
+        );
+        assert_eq!(source_map.position("/info/version"), position(1, 1));
+        assert_eq!(source_map.position("/tags/0"), position(10, 8));
+    }
+
+    #[test]
+    fn map_json_positions() {
+        let source_map = SourceMap::parse(
+            "{\n\t\"info\": {\n\t\t\"title\": \"Lights\"\n\t},\n\t\"ids\": [1, 2]\n}",
+        )
+        .unwrap();
+
+        assert_eq!(source_map.position("/info/title"), position(3, 3));
+        assert_eq!(source_map.position("/ids/1"), position(5, 13));
+    }
+}
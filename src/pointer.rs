@@ -0,0 +1,9 @@
+//! JSON pointers to the objects of a specification.
+
+/// JSON pointer made of `segments`, escaped as described in RFC 6901.
+pub(crate) fn json_pointer(segments: &[&str]) -> String {
+    segments
+        .iter()
+        .map(|segment| format!("/{}", segment.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
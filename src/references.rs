@@ -0,0 +1,91 @@
+//! References between the objects of a specification.
+use serde_json::Value;
+
+use crate::pointer::json_pointer;
+#[cfg(any(feature = "writer", feature = "lint"))]
+use crate::spec::AsyncApiV3Spec;
+
+/// JSON pointers to the components that are not referenced, unsorted.
+#[cfg(any(feature = "writer", feature = "lint"))]
+pub(crate) fn unused_components(spec: &AsyncApiV3Spec) -> Vec<String> {
+    let Ok(document) = serde_json::to_value(spec) else {
+        return vec![];
+    };
+    let mut references = vec![];
+    collect_references(&document, &mut references);
+
+    let Some(Value::Object(sections)) = document.get("components") else {
+        return vec![];
+    };
+    sections
+        .iter()
+        .filter_map(|(section, components)| Some((section, components.as_object()?)))
+        .flat_map(|(section, components)| components.keys().map(move |name| (section, name)))
+        .filter(|(section, name)| {
+            let reference = format!("#/components/{section}/{name}");
+            !references
+                .iter()
+                .any(|used| used == &reference || used.starts_with(&format!("{reference}/")))
+        })
+        .map(|(section, name)| json_pointer(&["components", section, name]))
+        .collect()
+}
+
+/// Local references of `document` that do not point to a value, with the pointers of their
+/// `$ref` fields.
+#[cfg(feature = "loader")]
+pub(crate) fn unresolved_references(document: &Value) -> Vec<(String, String)> {
+    let mut unresolved = vec![];
+    collect_unresolved(document, document, String::new(), &mut unresolved);
+    unresolved
+}
+
+#[cfg(feature = "loader")]
+fn collect_unresolved(
+    document: &Value,
+    value: &Value,
+    pointer: String,
+    unresolved: &mut Vec<(String, String)>,
+) {
+    let children: Box<dyn Iterator<Item = (String, &Value)>> = match value {
+        Value::Object(object) => Box::new(object.iter().map(|(key, value)| (key.clone(), value))),
+        Value::Array(values) => Box::new(
+            values
+                .iter()
+                .enumerate()
+                .map(|(index, value)| (index.to_string(), value)),
+        ),
+        _ => return,
+    };
+    for (key, child) in children {
+        let child_pointer = format!("{pointer}{}", json_pointer(&[&key]));
+        match child {
+            Value::String(reference) if key == "$ref" && value.is_object() => {
+                if let Some(target) = reference.strip_prefix('#') {
+                    if document.pointer(target).is_none() {
+                        unresolved.push((child_pointer, reference.clone()));
+                    }
+                }
+            }
+            _ => collect_unresolved(document, child, child_pointer, unresolved),
+        }
+    }
+}
+
+#[cfg(any(feature = "writer", feature = "lint"))]
+fn collect_references<'a>(value: &'a Value, references: &mut Vec<&'a str>) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                match value {
+                    Value::String(reference) if key == "$ref" => references.push(reference),
+                    _ => collect_references(value, references),
+                }
+            }
+        }
+        Value::Array(values) => values
+            .iter()
+            .for_each(|value| collect_references(value, references)),
+        _ => {}
+    }
+}
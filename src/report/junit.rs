@@ -0,0 +1,138 @@
+//! JUnit XML reports, read by test report tools: each file is a test suite, whose test cases are
+//! its findings, or a single passing test case when it has none.
+use std::fmt::Write;
+
+use super::{Finding, Report};
+use crate::lint::Severity;
+
+impl Report {
+    /// JUnit XML report, where the errors and warnings are failures and the other findings are
+    /// skipped test cases.
+    pub fn to_junit(&self) -> String {
+        let failures = |findings: &[&Finding]| {
+            findings
+                .iter()
+                .filter(|finding| is_failure(finding.severity))
+                .count()
+        };
+        let all = self.findings.iter().collect::<Vec<_>>();
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(
+            xml,
+            "<testsuites name=\"{}\" tests=\"{}\" failures=\"{}\">",
+            env!("CARGO_PKG_NAME"),
+            self.files
+                .iter()
+                .map(|file| self.tests(file))
+                .sum::<usize>(),
+            failures(&all),
+        );
+        for file in &self.files {
+            let name = escape(&file.display().to_string());
+            let findings = self
+                .findings
+                .iter()
+                .filter(|finding| finding.file == *file)
+                .collect::<Vec<_>>();
+            let skipped = findings.len() - failures(&findings);
+            let _ = writeln!(
+                xml,
+                "  <testsuite name=\"{name}\" tests=\"{}\" failures=\"{}\" skipped=\"{skipped}\">",
+                self.tests(file),
+                failures(&findings),
+            );
+            if findings.is_empty() {
+                let _ = writeln!(xml, "    <testcase name=\"{name}\" classname=\"{name}\"/>");
+            }
+            for finding in findings {
+                let pointer = match finding.pointer.as_str() {
+                    "" => "/",
+                    pointer => pointer,
+                };
+                let _ = writeln!(
+                    xml,
+                    "    <testcase name=\"{}\" classname=\"{name}\">",
+                    escape(&format!("{}: {pointer}", finding.rule)),
+                );
+                let mut location = file.display().to_string();
+                if let Some(position) = finding.position {
+                    let _ = write!(location, ":{position}");
+                }
+                let message = escape(&finding.message);
+                if is_failure(finding.severity) {
+                    let _ = writeln!(
+                        xml,
+                        "      <failure message=\"{message}\" type=\"{}\">{}</failure>",
+                        finding.severity,
+                        escape(&format!("{location}: {pointer}: {}", finding.message)),
+                    );
+                } else {
+                    let _ = writeln!(xml, "      <skipped message=\"{message}\"/>");
+                }
+                xml.push_str("    </testcase>\n");
+            }
+            xml.push_str("  </testsuite>\n");
+        }
+        xml.push_str("</testsuites>\n");
+        xml
+    }
+
+    /// Number of test cases of a file.
+    fn tests(&self, file: &std::path::Path) -> usize {
+        let findings = self
+            .findings
+            .iter()
+            .filter(|finding| finding.file == file)
+            .count();
+        findings.max(1)
+    }
+}
+
+fn is_failure(severity: Severity) -> bool {
+    matches!(severity, Severity::Warning | Severity::Error)
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn write_test_suites_by_file() {
+        let junit = super::super::test::report().to_junit();
+        let lines = junit.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines[..6],
+            [
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>",
+                "<testsuites name=\"asyncapiv3\" tests=\"7\" failures=\"6\">",
+                "  <testsuite name=\"lights.yml\" tests=\"5\" failures=\"4\" skipped=\"1\">",
+                "    <testcase name=\"channel-name-kebab-case: /channels/lightMeasured\" classname=\"lights.yml\">",
+                "      <failure message=\"channel name &apos;lightMeasured&apos; is not in kebab-case\" type=\"warning\">lights.yml:6:3: /channels/lightMeasured: channel name &apos;lightMeasured&apos; is not in kebab-case</failure>",
+                "    </testcase>",
+            ]
+        );
+        assert!(junit.contains(
+            "    <testcase name=\"message-examples: /channels/lightMeasured/messages/measured\" classname=\"lights.yml\">\n      <skipped message=\"message without examples\"/>\n"
+        ));
+        assert!(junit.contains("<failure message=\"missing field `version`\" type=\"error\">"));
+        assert_eq!(
+            escape("<a href='x'>&\""),
+            "&lt;a href=&apos;x&apos;&gt;&amp;&quot;"
+        );
+    }
+}
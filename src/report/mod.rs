@@ -0,0 +1,274 @@
+//! Reports of the validation and lint findings of specifications, that can be written as SARIF
+//! 2.1.0 for code scanning tools or as JUnit XML for test report tools.
+//!
+//! Validation problems are reported under the [validation rules](VALIDATION_RULES), and lint
+//! diagnostics under the rules of their linter.
+use std::path::{Path, PathBuf};
+
+use crate::lint::{Diagnostic, Linter, Severity};
+use crate::loader::lenient::{Issue, LenientSpec};
+use crate::loader::{self, LoadedSpec, Position};
+
+mod junit;
+mod sarif;
+
+/// Rule of the documents that are not valid YAML or JSON.
+pub const INVALID_SYNTAX: &str = "invalid-syntax";
+/// Rule of the values that are not valid in an AsyncAPI 3 specification.
+pub const INVALID_SPECIFICATION: &str = "invalid-specification";
+/// Rule of the unknown fields of bindings.
+pub const UNKNOWN_BINDING_FIELD: &str = "unknown-binding-field";
+
+/// Rules of the problems found when loading specifications.
+pub const VALIDATION_RULES: [(&str, &str, Severity); 3] = [
+    (
+        INVALID_SYNTAX,
+        "Documents must be valid YAML or JSON.",
+        Severity::Error,
+    ),
+    (
+        INVALID_SPECIFICATION,
+        "Documents must be valid AsyncAPI 3 specifications.",
+        Severity::Error,
+    ),
+    (
+        UNKNOWN_BINDING_FIELD,
+        "Bindings should only have the fields defined by their protocol.",
+        Severity::Warning,
+    ),
+];
+
+/// Description of a rule, reported with its findings.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuleInfo {
+    pub id: String,
+    pub description: String,
+    /// Configured severity of the rule.
+    pub severity: Severity,
+}
+
+/// A problem found in a specification.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct Finding {
+    pub file: PathBuf,
+    /// Position of the value in the file, if known.
+    #[serde(flatten)]
+    pub position: Option<Position>,
+    /// JSON pointer to the value.
+    pub pointer: String,
+    pub severity: Severity,
+    pub rule: String,
+    pub message: String,
+}
+
+/// Findings of a set of specification files.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Report {
+    rules: Vec<RuleInfo>,
+    files: Vec<PathBuf>,
+    findings: Vec<Finding>,
+}
+
+impl Default for Report {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Report {
+    /// Report with the validation rules.
+    pub fn new() -> Self {
+        Self {
+            rules: VALIDATION_RULES
+                .iter()
+                .map(|(id, description, severity)| RuleInfo {
+                    id: String::from(*id),
+                    description: String::from(*description),
+                    severity: *severity,
+                })
+                .collect(),
+            files: vec![],
+            findings: vec![],
+        }
+    }
+
+    /// Adds the rules of a linter, replacing the rules with the same identifiers.
+    pub fn with_linter(mut self, linter: &Linter) -> Self {
+        for (rule, severity) in linter.rules() {
+            self.rules.retain(|info| info.id != rule.id());
+            self.rules.push(RuleInfo {
+                id: String::from(rule.id()),
+                description: String::from(rule.description()),
+                severity,
+            });
+        }
+        self
+    }
+
+    /// Adds the errors and warnings of a specification loaded from `file`.
+    pub fn add_validation(&mut self, file: &Path, loaded: &LenientSpec) {
+        self.add_file(file);
+        let issues = loaded
+            .errors
+            .iter()
+            .map(|issue| (issue, Severity::Error, INVALID_SPECIFICATION))
+            .chain(
+                loaded
+                    .warnings
+                    .iter()
+                    .map(|issue| (issue, Severity::Warning, UNKNOWN_BINDING_FIELD)),
+            );
+        for (issue, severity, rule) in issues {
+            let Issue {
+                pointer,
+                location,
+                message,
+            } = issue;
+            self.findings.push(Finding {
+                file: file.to_path_buf(),
+                position: location.as_ref().map(|location| location.position),
+                pointer: pointer.clone(),
+                severity,
+                rule: String::from(rule),
+                message: message.clone(),
+            });
+        }
+    }
+
+    /// Adds the error of a specification that cannot be loaded from `file`.
+    pub fn add_load_error(&mut self, file: &Path, err: &loader::Error) {
+        self.add_file(file);
+        let (rule, message) = match err {
+            loader::Error::Syntax { reason, .. } => (INVALID_SYNTAX, reason.clone()),
+            loader::Error::Invalid { reason, .. } => (INVALID_SPECIFICATION, reason.clone()),
+            err => (INVALID_SPECIFICATION, err.to_string()),
+        };
+        self.findings.push(Finding {
+            file: file.to_path_buf(),
+            position: err.location().map(|location| location.position),
+            pointer: String::new(),
+            severity: Severity::Error,
+            rule: String::from(rule),
+            message,
+        });
+    }
+
+    /// Adds the lint diagnostics of a specification loaded from `file`.
+    pub fn add_lint(&mut self, file: &Path, loaded: &LoadedSpec, diagnostics: Vec<Diagnostic>) {
+        self.add_file(file);
+        for diagnostic in diagnostics {
+            self.findings.push(Finding {
+                file: file.to_path_buf(),
+                position: loaded.source_map.position(&diagnostic.pointer),
+                pointer: diagnostic.pointer,
+                severity: diagnostic.severity,
+                rule: diagnostic.rule,
+                message: diagnostic.message,
+            });
+        }
+    }
+
+    /// Rules that can be reported, validation rules first.
+    pub fn rules(&self) -> &[RuleInfo] {
+        &self.rules
+    }
+
+    /// Checked files, in the order they were added.
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    pub fn findings(&self) -> &[Finding] {
+        &self.findings
+    }
+
+    /// Number of findings with the given severity.
+    pub fn count(&self, severity: Severity) -> usize {
+        self.findings
+            .iter()
+            .filter(|finding| finding.severity == severity)
+            .count()
+    }
+
+    fn add_file(&mut self, file: &Path) {
+        if !self.files.iter().any(|known| known == file) {
+            self.files.push(file.to_path_buf());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Report of a valid specification with lint diagnostics and of an invalid one.
+    pub(super) fn report() -> Report {
+        let valid = Path::new("lights.yml");
+        let content = "\
+asyncapi: 3.0.0
+info:
+  title: Lights
+  version: 1.0.0
+channels:
+  lightMeasured:
+    messages:
+      measured:
+        payload: { type: object }
+";
+        let linter = Linter::new();
+        let loaded = loader::load_str(content).unwrap();
+        let crate::spec::AsyncApiSpec::V3_0_0(spec) = &loaded.spec;
+        let mut report = Report::new().with_linter(&linter);
+        report.add_lint(valid, &loaded, linter.lint(spec));
+
+        let invalid = Path::new("invalid.yml");
+        let content = "asyncapi: 3.0.0\ninfo:\n  title: Lights\nchannels: {}\n";
+        let loaded = crate::loader::lenient::load_str(content).unwrap();
+        report.add_validation(invalid, &loaded);
+        let err = loader::load_str("asyncapi: [").unwrap_err();
+        report.add_load_error(Path::new("broken.yml"), &err);
+        report
+    }
+
+    #[test]
+    fn collect_findings_of_validation_and_lint() {
+        let report = report();
+        assert_eq!(
+            report.files(),
+            ["lights.yml", "invalid.yml", "broken.yml"].map(PathBuf::from)
+        );
+        assert_eq!(
+            report.rules()[..4]
+                .iter()
+                .map(|rule| rule.id.as_str())
+                .collect::<Vec<_>>(),
+            [
+                INVALID_SYNTAX,
+                INVALID_SPECIFICATION,
+                UNKNOWN_BINDING_FIELD,
+                "operation-summary"
+            ]
+        );
+        assert_eq!(
+            report
+                .findings()
+                .iter()
+                .map(|finding| (
+                    finding.file.to_str().unwrap(),
+                    finding.position.map(|position| position.line),
+                    finding.rule.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            [
+                ("lights.yml", Some(6), "channel-name-kebab-case"),
+                ("lights.yml", Some(8), "message-content-type"),
+                ("lights.yml", Some(8), "message-examples"),
+                ("lights.yml", Some(2), "info-contact"),
+                ("lights.yml", Some(2), "info-license"),
+                ("invalid.yml", Some(2), INVALID_SPECIFICATION),
+                ("broken.yml", Some(2), INVALID_SYNTAX),
+            ]
+        );
+        assert_eq!(report.count(Severity::Error), 2);
+    }
+}
@@ -0,0 +1,175 @@
+//! SARIF 2.1.0 logs, read by code scanning tools.
+use std::path::Path;
+
+use serde_json::{json, Value};
+
+use super::Report;
+use crate::lint::Severity;
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+impl Report {
+    /// SARIF log with a single run, describing the rules and the files of the report.
+    pub fn to_sarif(&self) -> Value {
+        let rules = self
+            .rules
+            .iter()
+            .map(|rule| {
+                let mut descriptor = json!({
+                    "id": rule.id,
+                    "defaultConfiguration": { "level": level(rule.severity) },
+                });
+                if !rule.description.is_empty() {
+                    descriptor["shortDescription"] = json!({ "text": rule.description });
+                }
+                descriptor
+            })
+            .collect::<Vec<_>>();
+        let artifacts = self
+            .files
+            .iter()
+            .map(|file| json!({ "location": { "uri": uri(file) } }))
+            .collect::<Vec<_>>();
+        let results = self
+            .findings
+            .iter()
+            .map(|finding| {
+                let mut physical_location = json!({
+                    "artifactLocation": {
+                        "uri": uri(&finding.file),
+                        "index": self.files.iter().position(|file| *file == finding.file),
+                    },
+                });
+                if let Some(position) = finding.position {
+                    physical_location["region"] = json!({
+                        "startLine": position.line,
+                        "startColumn": position.column,
+                    });
+                }
+                let mut location = json!({ "physicalLocation": physical_location });
+                if !finding.pointer.is_empty() {
+                    location["logicalLocations"] = json!([{
+                        "fullyQualifiedName": finding.pointer,
+                        "kind": "object",
+                    }]);
+                }
+                let mut result = json!({
+                    "ruleId": finding.rule,
+                    "level": level(finding.severity),
+                    "message": { "text": finding.message },
+                    "locations": [location],
+                });
+                if let Some(index) = self.rules.iter().position(|rule| rule.id == finding.rule) {
+                    result["ruleIndex"] = json!(index);
+                }
+                result
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "$schema": SCHEMA,
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": env!("CARGO_PKG_NAME"),
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                        "rules": rules,
+                    },
+                },
+                "artifacts": artifacts,
+                "results": results,
+            }],
+        })
+    }
+}
+
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Off => "none",
+        Severity::Info => "note",
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    }
+}
+
+/// Relative URI reference of a file, or file URI when the path is absolute.
+fn uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let path = path
+        .split('/')
+        .map(|segment| {
+            segment
+                .bytes()
+                .map(|byte| match byte {
+                    b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b':' => {
+                        char::from(byte).to_string()
+                    }
+                    byte => format!("%{byte:02X}"),
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    if path.starts_with('/') {
+        format!("file://{path}")
+    } else {
+        path
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn write_rules_and_results() {
+        let sarif = super::super::test::report().to_sarif();
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        let driver = &run["tool"]["driver"];
+        assert_eq!(driver["name"], "asyncapiv3");
+        assert_eq!(
+            driver["rules"][4],
+            json!({
+                "id": "message-examples",
+                "defaultConfiguration": { "level": "note" },
+                "shortDescription": {
+                    "text": "Messages should have examples, either their own or from their traits."
+                },
+            })
+        );
+        assert_eq!(
+            run["artifacts"],
+            json!([
+                { "location": { "uri": "lights.yml" } },
+                { "location": { "uri": "invalid.yml" } },
+                { "location": { "uri": "broken.yml" } },
+            ])
+        );
+        assert_eq!(
+            run["results"][0],
+            json!({
+                "ruleId": "channel-name-kebab-case",
+                "ruleIndex": 6,
+                "level": "warning",
+                "message": { "text": "channel name 'lightMeasured' is not in kebab-case" },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": "lights.yml", "index": 0 },
+                        "region": { "startLine": 6, "startColumn": 3 },
+                    },
+                    "logicalLocations": [{
+                        "fullyQualifiedName": "/channels/lightMeasured",
+                        "kind": "object",
+                    }],
+                }],
+            })
+        );
+        assert_eq!(
+            uri(Path::new("/specs/my lights.yml")),
+            "file:///specs/my%20lights.yml"
+        );
+    }
+}
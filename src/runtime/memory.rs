@@ -0,0 +1,98 @@
+//! In-memory [transport](Transport), useful to test generated clients and handlers.
+use crate::runtime::Transport;
+use serde_json::Value;
+use std::future::Future;
+use std::sync::Mutex;
+use thiserror::Error;
+
+type Responder = Box<dyn Fn(&str, Value) -> Option<Value> + Send + Sync>;
+
+#[derive(Debug, Error)]
+pub enum InMemoryError {
+    #[error("No reply to the request sent to '{address}'")]
+    NoReply { address: String },
+}
+
+/// A message published through an [`InMemoryTransport`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct PublishedMessage {
+    pub address: String,
+    pub reply_address: Option<String>,
+    pub payload: Value,
+}
+
+/// Transport recording the published messages. Requests are answered by a responder function.
+pub struct InMemoryTransport {
+    published: Mutex<Vec<PublishedMessage>>,
+    responder: Responder,
+}
+
+impl Default for InMemoryTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InMemoryTransport {
+    /// Transport that never answers requests.
+    pub fn new() -> Self {
+        Self::with_responder(|_, _| None)
+    }
+
+    /// Transport answering requests with the value returned by `responder`, called with the
+    /// address and the payload of the request.
+    pub fn with_responder(
+        responder: impl Fn(&str, Value) -> Option<Value> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            published: Mutex::new(vec![]),
+            responder: Box::new(responder),
+        }
+    }
+
+    /// Messages published so far, requests included.
+    pub fn published(&self) -> Vec<PublishedMessage> {
+        self.published
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    fn record(&self, address: &str, reply_address: Option<&str>, payload: Value) {
+        self.published
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(PublishedMessage {
+                address: String::from(address),
+                reply_address: reply_address.map(String::from),
+                payload,
+            });
+    }
+}
+
+impl Transport for InMemoryTransport {
+    type Error = InMemoryError;
+
+    fn publish(
+        &self,
+        address: &str,
+        payload: Value,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send {
+        self.record(address, None, payload);
+        std::future::ready(Ok(()))
+    }
+
+    fn request(
+        &self,
+        address: &str,
+        reply_address: Option<&str>,
+        payload: Value,
+    ) -> impl Future<Output = Result<Value, Self::Error>> + Send {
+        self.record(address, reply_address, payload.clone());
+        std::future::ready((self.responder)(address, payload).ok_or_else(|| {
+            InMemoryError::NoReply {
+                address: String::from(address),
+            }
+        }))
+    }
+}
@@ -0,0 +1,134 @@
+//! Runtime support for the code generated by the [`codegen`](crate::codegen) module.
+//!
+//! Generated clients send messages through a [`Transport`], generated dispatchers route incoming
+//! messages to the methods of a handler trait. Payloads are exchanged as JSON values.
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use thiserror::Error;
+
+pub mod memory;
+
+/// A messaging transport (broker client, websocket connection, ...) used by generated clients.
+pub trait Transport: Send + Sync {
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    /// Publishes `payload` to `address`.
+    fn publish(
+        &self,
+        address: &str,
+        payload: Value,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send;
+
+    /// Publishes `payload` to `address` and waits for the reply, published to `reply_address`
+    /// when it is known in advance.
+    fn request(
+        &self,
+        address: &str,
+        reply_address: Option<&str>,
+        payload: Value,
+    ) -> impl Future<Output = Result<Value, Self::Error>> + Send;
+}
+
+/// Error of an operation of a generated client.
+#[derive(Debug, Error)]
+pub enum Error<E: std::error::Error + 'static> {
+    #[error("Transport error: {source}")]
+    Transport { source: E },
+    #[error("Invalid payload: {source}")]
+    Payload { source: serde_json::Error },
+}
+
+/// Error of a generated dispatcher.
+#[derive(Debug, Error)]
+pub enum DispatchError {
+    #[error("No operation matches address '{address}'")]
+    UnknownAddress { address: String },
+    #[error("Invalid payload: {source}")]
+    Payload { source: serde_json::Error },
+}
+
+/// Replaces the `{name}` placeholders of an address template with the value of the matching
+/// parameter. Placeholders without a value are kept as they are.
+pub fn fill_address(template: &str, parameters: &[(&str, &str)]) -> String {
+    let mut address = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+            break;
+        };
+        address.push_str(&rest[..start]);
+        let name = &rest[start + 1..end];
+        match parameters.iter().find(|(parameter, _)| *parameter == name) {
+            Some((_, value)) => address.push_str(value),
+            None => address.push_str(&rest[start..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+    address.push_str(rest);
+    address
+}
+
+/// Matches an address against an address template, returning the value of each parameter.
+/// Parameter values cannot be empty and extend up to the first occurrence of the text that
+/// follows the placeholder in the template.
+pub fn match_address(template: &str, address: &str) -> Option<HashMap<String, String>> {
+    let mut parameters = HashMap::new();
+    let mut template = template;
+    let mut address = address;
+    loop {
+        let Some(start) = template.find('{') else {
+            return (template == address).then_some(parameters);
+        };
+        address = address.strip_prefix(&template[..start])?;
+        let end = start + template[start..].find('}')?;
+        let name = &template[start + 1..end];
+        template = &template[end + 1..];
+
+        let literal_end = template.find('{').unwrap_or(template.len());
+        let literal = &template[..literal_end];
+        let value_end = if literal.is_empty() {
+            if literal_end < template.len() {
+                // adjacent placeholders cannot be told apart
+                return None;
+            }
+            address.len()
+        } else {
+            address.find(literal)?
+        };
+        if value_end == 0 {
+            return None;
+        }
+        parameters.insert(String::from(name), String::from(&address[..value_end]));
+        address = &address[value_end..];
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fills_and_matches_addresses() {
+        let template = "streetlights/{streetlightId}/lighting/{kind}";
+        let address = fill_address(template, &[("streetlightId", "42"), ("kind", "measured")]);
+        assert_eq!(address, "streetlights/42/lighting/measured");
+        assert_eq!(fill_address(template, &[]), template);
+
+        let parameters = match_address(template, &address).unwrap();
+        assert_eq!(parameters["streetlightId"], "42");
+        assert_eq!(parameters["kind"], "measured");
+        assert_eq!(
+            match_address(template, "streetlights//lighting/measured"),
+            None
+        );
+        assert_eq!(
+            match_address(template, "streetlights/42/dim/measured"),
+            None
+        );
+        assert_eq!(
+            match_address("rpc_queue", "rpc_queue"),
+            Some(HashMap::new())
+        );
+    }
+}
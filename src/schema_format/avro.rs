@@ -0,0 +1,831 @@
+//! Support for [Apache Avro](https://avro.apache.org/docs/1.9.0/spec.html) schemas, declared
+//! with `schemaFormat: application/vnd.apache.avro;version=1.9.0`.
+use crate::pointer::json_pointer;
+use crate::schema_format::{Error, ValidationError};
+use crate::spec::message::MultiFormatSchema;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+/// Avro media type, without version and structured syntax suffix.
+pub const MEDIA_TYPE: &str = "application/vnd.apache.avro";
+
+const FORMAT: &str = "avro";
+
+/// An Avro type, named types are referenced by their full name.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AvroType {
+    Null,
+    Boolean,
+    Int,
+    Long,
+    Float,
+    Double,
+    Bytes,
+    String,
+    Array(Box<AvroType>),
+    Map(Box<AvroType>),
+    Union(Vec<AvroType>),
+    /// Reference to a record, enum or fixed type.
+    Named(String),
+    /// A type annotated with a logical type such as `timestamp-millis` or `uuid`.
+    Logical {
+        logical_type: String,
+        base: Box<AvroType>,
+    },
+}
+
+/// A record, enum or fixed definition.
+#[derive(Clone, Debug, PartialEq)]
+pub enum NamedType {
+    Record {
+        name: String,
+        doc: Option<String>,
+        fields: Vec<RecordField>,
+    },
+    Enum {
+        name: String,
+        doc: Option<String>,
+        symbols: Vec<String>,
+        default: Option<String>,
+    },
+    Fixed {
+        name: String,
+        size: usize,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordField {
+    pub name: String,
+    pub doc: Option<String>,
+    pub field_type: AvroType,
+    pub default: Option<Value>,
+}
+
+/// A parsed Avro schema whose named types have all been resolved.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AvroSchema {
+    root: AvroType,
+    named: BTreeMap<String, NamedType>,
+}
+
+impl AvroSchema {
+    /// Parses an Avro schema from its JSON representation. A schema encoded as a JSON string
+    /// containing the schema definition is accepted as well.
+    pub fn parse(schema: &Value) -> Result<Self, Error> {
+        if let Value::String(source) = schema {
+            if source.trim_start().starts_with(['{', '[']) {
+                let parsed = serde_json::from_str::<Value>(source).map_err(invalid)?;
+                return Self::parse(&parsed);
+            }
+        }
+
+        let mut parser = Parser::default();
+        let root = parser.parse_type(schema, None)?;
+        let schema = Self {
+            root,
+            named: parser.named,
+        };
+        schema.check_references(&schema.root)?;
+        for named in schema.named.values() {
+            if let NamedType::Record { fields, .. } = named {
+                for field in fields {
+                    schema.check_references(&field.field_type)?;
+                }
+            }
+        }
+        Ok(schema)
+    }
+
+    /// Parses the schema of an Avro multi format schema.
+    pub fn from_multi_format(schema: &MultiFormatSchema) -> Result<Self, Error> {
+        if !schema.format().is_avro() {
+            return Err(Error::UnsupportedFormat {
+                schema_format: schema.schema_format.clone(),
+            });
+        }
+        Self::parse(&schema.schema)
+    }
+
+    /// The top level type of the schema.
+    pub fn root(&self) -> &AvroType {
+        &self.root
+    }
+
+    /// Returns the definition of a named type given its full name.
+    pub fn resolve(&self, full_name: &str) -> Option<&NamedType> {
+        self.named.get(full_name)
+    }
+
+    /// Named types defined in the schema, by full name.
+    pub fn named_types(&self) -> impl Iterator<Item = (&str, &NamedType)> {
+        self.named
+            .iter()
+            .map(|(name, named)| (name.as_str(), named))
+    }
+
+    /// Validates a JSON payload against the schema. Union values can be given either as plain
+    /// values or using the Avro JSON encoding (`{"<type name>": value}`).
+    pub fn validate(&self, payload: &Value) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        self.validate_type(&self.root, payload, "", &mut errors);
+        errors
+    }
+
+    /// Converts the schema to an equivalent JSON schema. Named types are stored under `$defs`
+    /// and referenced by their full name, so recursive types are supported.
+    pub fn to_json_schema(&self) -> schemars::Schema {
+        let mut root = match self.type_to_json_schema(&self.root) {
+            Value::Object(root) => root,
+            _ => Map::new(),
+        };
+        if !self.named.is_empty() {
+            let definitions = self
+                .named
+                .iter()
+                .map(|(name, named)| (name.clone(), self.named_to_json_schema(named)))
+                .collect::<Map<_, _>>();
+            root.insert(String::from("$defs"), Value::Object(definitions));
+        }
+        schemars::Schema::from(root)
+    }
+
+    fn check_references(&self, avro_type: &AvroType) -> Result<(), Error> {
+        match avro_type {
+            AvroType::Named(name) if !self.named.contains_key(name) => {
+                Err(Error::UnresolvedType { name: name.clone() })
+            }
+            AvroType::Array(items) | AvroType::Map(items) => self.check_references(items),
+            AvroType::Logical { base, .. } => self.check_references(base),
+            AvroType::Union(branches) => branches
+                .iter()
+                .try_for_each(|branch| self.check_references(branch)),
+            _ => Ok(()),
+        }
+    }
+
+    fn validate_type(
+        &self,
+        avro_type: &AvroType,
+        value: &Value,
+        pointer: &str,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        let mismatch =
+            |expected: &str| ValidationError::new(pointer, format!("expected {expected}"));
+        match avro_type {
+            AvroType::Null if !value.is_null() => errors.push(mismatch("null")),
+            AvroType::Boolean if !value.is_boolean() => errors.push(mismatch("boolean")),
+            AvroType::Int => match value.as_i64() {
+                Some(int) if i32::try_from(int).is_ok() => {}
+                _ => errors.push(mismatch("32-bit integer")),
+            },
+            AvroType::Long if !value.is_i64() => errors.push(mismatch("64-bit integer")),
+            AvroType::Float | AvroType::Double if !value.is_number() => {
+                errors.push(mismatch("number"))
+            }
+            AvroType::Bytes | AvroType::String if !value.is_string() => {
+                errors.push(mismatch("string"))
+            }
+            AvroType::Array(items) => match value {
+                Value::Array(values) => {
+                    for (idx, item) in values.iter().enumerate() {
+                        self.validate_type(items, item, &format!("{pointer}/{idx}"), errors);
+                    }
+                }
+                _ => errors.push(mismatch("array")),
+            },
+            AvroType::Map(values) => match value {
+                Value::Object(entries) => {
+                    for (key, entry) in entries {
+                        let pointer = format!("{pointer}{}", json_pointer(&[key]));
+                        self.validate_type(values, entry, &pointer, errors);
+                    }
+                }
+                _ => errors.push(mismatch("object")),
+            },
+            AvroType::Union(branches) => self.validate_union(branches, value, pointer, errors),
+            AvroType::Logical { base, .. } => self.validate_type(base, value, pointer, errors),
+            AvroType::Named(name) => match self.named.get(name) {
+                Some(named) => self.validate_named(named, value, pointer, errors),
+                None => errors.push(ValidationError::new(
+                    pointer,
+                    format!("unresolved type '{name}'"),
+                )),
+            },
+            _ => {}
+        }
+    }
+
+    fn validate_union(
+        &self,
+        branches: &[AvroType],
+        value: &Value,
+        pointer: &str,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        let matches = |branch: &AvroType, value: &Value| {
+            let mut branch_errors = Vec::new();
+            self.validate_type(branch, value, pointer, &mut branch_errors);
+            branch_errors.is_empty()
+        };
+        if branches.iter().any(|branch| matches(branch, value)) {
+            return;
+        }
+        if let Value::Object(wrapper) = value {
+            if let (1, Some((type_name, inner))) = (wrapper.len(), wrapper.iter().next()) {
+                if let Some(branch) = branches
+                    .iter()
+                    .find(|branch| self.type_name(branch) == *type_name)
+                {
+                    let pointer = format!("{pointer}{}", json_pointer(&[type_name]));
+                    self.validate_type(branch, inner, &pointer, errors);
+                    return;
+                }
+            }
+        }
+        let names = branches
+            .iter()
+            .map(|branch| self.type_name(branch))
+            .collect::<Vec<_>>()
+            .join(", ");
+        errors.push(ValidationError::new(
+            pointer,
+            format!("value doesn't match any of the union types [{names}]"),
+        ));
+    }
+
+    fn validate_named(
+        &self,
+        named: &NamedType,
+        value: &Value,
+        pointer: &str,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        match named {
+            NamedType::Record { name, fields, .. } => {
+                let Value::Object(object) = value else {
+                    errors.push(ValidationError::new(
+                        pointer,
+                        format!("expected record '{name}'"),
+                    ));
+                    return;
+                };
+                for field in fields {
+                    let field_pointer = format!("{pointer}{}", json_pointer(&[&field.name]));
+                    match object.get(&field.name) {
+                        Some(field_value) => self.validate_type(
+                            &field.field_type,
+                            field_value,
+                            &field_pointer,
+                            errors,
+                        ),
+                        None if field.default.is_none() => errors.push(ValidationError::new(
+                            &field_pointer,
+                            format!("missing required field '{}'", field.name),
+                        )),
+                        None => {}
+                    }
+                }
+                for key in object.keys() {
+                    if !fields.iter().any(|field| field.name == *key) {
+                        errors.push(ValidationError::new(
+                            &format!("{pointer}{}", json_pointer(&[key])),
+                            format!("unknown field '{key}' in record '{name}'"),
+                        ));
+                    }
+                }
+            }
+            NamedType::Enum { name, symbols, .. } => match value.as_str() {
+                Some(symbol) if symbols.iter().any(|s| s == symbol) => {}
+                _ => errors.push(ValidationError::new(
+                    pointer,
+                    format!("expected one of the symbols of enum '{name}'"),
+                )),
+            },
+            NamedType::Fixed { name, size } => match value.as_str() {
+                Some(fixed) if fixed.chars().count() == *size => {}
+                _ => errors.push(ValidationError::new(
+                    pointer,
+                    format!("expected {size} bytes for fixed '{name}'"),
+                )),
+            },
+        }
+    }
+
+    /// Name used to identify a branch in the Avro JSON encoding of unions.
+    fn type_name<'t>(&self, avro_type: &'t AvroType) -> &'t str {
+        match avro_type {
+            AvroType::Null => "null",
+            AvroType::Boolean => "boolean",
+            AvroType::Int => "int",
+            AvroType::Long => "long",
+            AvroType::Float => "float",
+            AvroType::Double => "double",
+            AvroType::Bytes => "bytes",
+            AvroType::String => "string",
+            AvroType::Array(_) => "array",
+            AvroType::Map(_) => "map",
+            AvroType::Union(_) => "union",
+            AvroType::Named(name) => name,
+            AvroType::Logical { base, .. } => self.type_name(base),
+        }
+    }
+
+    fn type_to_json_schema(&self, avro_type: &AvroType) -> Value {
+        match avro_type {
+            AvroType::Null => json!({ "type": "null" }),
+            AvroType::Boolean => json!({ "type": "boolean" }),
+            AvroType::Int => json!({
+                "type": "integer",
+                "format": "int32",
+                "minimum": i32::MIN,
+                "maximum": i32::MAX,
+            }),
+            AvroType::Long => json!({ "type": "integer", "format": "int64" }),
+            AvroType::Float => json!({ "type": "number", "format": "float" }),
+            AvroType::Double => json!({ "type": "number", "format": "double" }),
+            AvroType::Bytes => json!({ "type": "string", "format": "binary" }),
+            AvroType::String => json!({ "type": "string" }),
+            AvroType::Array(items) => json!({
+                "type": "array",
+                "items": self.type_to_json_schema(items),
+            }),
+            AvroType::Map(values) => json!({
+                "type": "object",
+                "additionalProperties": self.type_to_json_schema(values),
+            }),
+            AvroType::Union(branches) => json!({
+                // branches may overlap (e.g. `int` and `long`), a value matching several of them
+                // is still valid
+                "anyOf": branches
+                    .iter()
+                    .map(|branch| self.type_to_json_schema(branch))
+                    .collect::<Vec<_>>(),
+            }),
+            AvroType::Named(name) => {
+                json!({ "$ref": format!("#{}", json_pointer(&["$defs", name])) })
+            }
+            AvroType::Logical { logical_type, base } => {
+                let mut schema = self.type_to_json_schema(base);
+                let format = match logical_type.as_str() {
+                    "uuid" => Some("uuid"),
+                    "date" => Some("date"),
+                    "time-millis" | "time-micros" => Some("time"),
+                    "timestamp-millis"
+                    | "timestamp-micros"
+                    | "local-timestamp-millis"
+                    | "local-timestamp-micros" => Some("date-time"),
+                    _ => None,
+                };
+                if let Value::Object(object) = &mut schema {
+                    // only string based logical types keep their JSON schema format, numeric
+                    // ones are documented through the Avro logical type extension
+                    if let (Some(format), Some("string")) =
+                        (format, object.get("type").and_then(Value::as_str))
+                    {
+                        object.insert(String::from("format"), Value::from(format));
+                    }
+                    object.insert(
+                        String::from("x-avro-logical-type"),
+                        Value::from(logical_type.as_str()),
+                    );
+                }
+                schema
+            }
+        }
+    }
+
+    fn named_to_json_schema(&self, named: &NamedType) -> Value {
+        let mut schema = Map::new();
+        match named {
+            NamedType::Record { name, doc, fields } => {
+                schema.insert(String::from("type"), Value::from("object"));
+                schema.insert(String::from("title"), Value::from(name.as_str()));
+                if let Some(doc) = doc {
+                    schema.insert(String::from("description"), Value::from(doc.as_str()));
+                }
+                let mut properties = Map::new();
+                let mut required = Vec::new();
+                for field in fields {
+                    let mut property = self.type_to_json_schema(&field.field_type);
+                    if let Value::Object(property) = &mut property {
+                        if let Some(doc) = &field.doc {
+                            property.insert(String::from("description"), Value::from(doc.as_str()));
+                        }
+                        if let Some(default) = &field.default {
+                            property.insert(String::from("default"), default.clone());
+                        }
+                    }
+                    if field.default.is_none() {
+                        required.push(Value::from(field.name.as_str()));
+                    }
+                    properties.insert(field.name.clone(), property);
+                }
+                schema.insert(String::from("properties"), Value::Object(properties));
+                if !required.is_empty() {
+                    schema.insert(String::from("required"), Value::Array(required));
+                }
+                schema.insert(String::from("additionalProperties"), Value::Bool(false));
+            }
+            NamedType::Enum {
+                name,
+                doc,
+                symbols,
+                default,
+            } => {
+                schema.insert(String::from("type"), Value::from("string"));
+                schema.insert(String::from("title"), Value::from(name.as_str()));
+                if let Some(doc) = doc {
+                    schema.insert(String::from("description"), Value::from(doc.as_str()));
+                }
+                schema.insert(
+                    String::from("enum"),
+                    symbols.iter().map(|s| Value::from(s.as_str())).collect(),
+                );
+                if let Some(default) = default {
+                    schema.insert(String::from("default"), Value::from(default.as_str()));
+                }
+            }
+            NamedType::Fixed { name, size } => {
+                schema.insert(String::from("type"), Value::from("string"));
+                schema.insert(String::from("title"), Value::from(name.as_str()));
+                schema.insert(String::from("minLength"), Value::from(*size));
+                schema.insert(String::from("maxLength"), Value::from(*size));
+            }
+        }
+        Value::Object(schema)
+    }
+}
+
+#[derive(Default)]
+struct Parser {
+    named: BTreeMap<String, NamedType>,
+}
+
+impl Parser {
+    fn parse_type(&mut self, schema: &Value, namespace: Option<&str>) -> Result<AvroType, Error> {
+        match schema {
+            Value::String(name) => Ok(self.parse_type_name(name, namespace)),
+            Value::Array(branches) => branches
+                .iter()
+                .map(|branch| self.parse_type(branch, namespace))
+                .collect::<Result<Vec<_>, _>>()
+                .map(AvroType::Union),
+            Value::Object(object) => {
+                let base = self.parse_complex(object, namespace)?;
+                match object.get("logicalType").and_then(Value::as_str) {
+                    Some(logical_type) => Ok(AvroType::Logical {
+                        logical_type: String::from(logical_type),
+                        base: Box::new(base),
+                    }),
+                    None => Ok(base),
+                }
+            }
+            other => Err(invalid(format!("unexpected type definition {other}"))),
+        }
+    }
+
+    fn parse_type_name(&self, name: &str, namespace: Option<&str>) -> AvroType {
+        match name {
+            "null" => AvroType::Null,
+            "boolean" => AvroType::Boolean,
+            "int" => AvroType::Int,
+            "long" => AvroType::Long,
+            "float" => AvroType::Float,
+            "double" => AvroType::Double,
+            "bytes" => AvroType::Bytes,
+            "string" => AvroType::String,
+            name => {
+                let qualified = full_name(name, namespace);
+                if self.named.contains_key(&qualified) {
+                    AvroType::Named(qualified)
+                } else {
+                    AvroType::Named(String::from(name))
+                }
+            }
+        }
+    }
+
+    fn parse_complex(
+        &mut self,
+        object: &Map<String, Value>,
+        namespace: Option<&str>,
+    ) -> Result<AvroType, Error> {
+        let type_name = match object.get("type") {
+            Some(Value::String(type_name)) => type_name.as_str(),
+            Some(nested) => return self.parse_type(nested, namespace),
+            None => return Err(invalid("missing 'type' attribute")),
+        };
+        match type_name {
+            "array" => {
+                let items = object
+                    .get("items")
+                    .ok_or_else(|| invalid("array without 'items'"))?;
+                Ok(AvroType::Array(Box::new(
+                    self.parse_type(items, namespace)?,
+                )))
+            }
+            "map" => {
+                let values = object
+                    .get("values")
+                    .ok_or_else(|| invalid("map without 'values'"))?;
+                Ok(AvroType::Map(Box::new(self.parse_type(values, namespace)?)))
+            }
+            "record" | "error" | "enum" | "fixed" => self.parse_named(type_name, object, namespace),
+            primitive => Ok(self.parse_type_name(primitive, namespace)),
+        }
+    }
+
+    fn parse_named(
+        &mut self,
+        type_name: &str,
+        object: &Map<String, Value>,
+        namespace: Option<&str>,
+    ) -> Result<AvroType, Error> {
+        let name = object
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| invalid(format!("{type_name} without 'name'")))?;
+        let namespace = object
+            .get("namespace")
+            .and_then(Value::as_str)
+            .filter(|namespace| !namespace.is_empty())
+            .or(namespace);
+        let qualified = full_name(name, namespace);
+        if self.named.contains_key(&qualified) {
+            return Err(invalid(format!("duplicate definition of '{qualified}'")));
+        }
+        // types declared inside a named type inherit the namespace of its full name
+        let inner_namespace = qualified.rsplit_once('.').map(|(namespace, _)| namespace);
+        let doc = object.get("doc").and_then(Value::as_str).map(String::from);
+
+        let named = match type_name {
+            "enum" => NamedType::Enum {
+                name: qualified.clone(),
+                doc,
+                symbols: object
+                    .get("symbols")
+                    .and_then(Value::as_array)
+                    .ok_or_else(|| invalid(format!("enum '{qualified}' without 'symbols'")))?
+                    .iter()
+                    .map(|symbol| symbol.as_str().map(String::from))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| invalid(format!("enum '{qualified}' has invalid symbols")))?,
+                default: object
+                    .get("default")
+                    .and_then(Value::as_str)
+                    .map(String::from),
+            },
+            "fixed" => NamedType::Fixed {
+                name: qualified.clone(),
+                size: object
+                    .get("size")
+                    .and_then(Value::as_u64)
+                    .ok_or_else(|| invalid(format!("fixed '{qualified}' without 'size'")))?
+                    as usize,
+            },
+            _ => {
+                // register the record before its fields so that it can reference itself
+                self.named.insert(
+                    qualified.clone(),
+                    NamedType::Record {
+                        name: qualified.clone(),
+                        doc: doc.clone(),
+                        fields: vec![],
+                    },
+                );
+                let fields = object
+                    .get("fields")
+                    .and_then(Value::as_array)
+                    .ok_or_else(|| invalid(format!("record '{qualified}' without 'fields'")))?
+                    .iter()
+                    .map(|field| self.parse_field(field, inner_namespace))
+                    .collect::<Result<Vec<_>, _>>()?;
+                NamedType::Record {
+                    name: qualified.clone(),
+                    doc,
+                    fields,
+                }
+            }
+        };
+        self.named.insert(qualified.clone(), named);
+        Ok(AvroType::Named(qualified))
+    }
+
+    fn parse_field(
+        &mut self,
+        field: &Value,
+        namespace: Option<&str>,
+    ) -> Result<RecordField, Error> {
+        let name = field
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| invalid("record field without 'name'"))?;
+        let field_type = field
+            .get("type")
+            .ok_or_else(|| invalid(format!("record field '{name}' without 'type'")))?;
+        Ok(RecordField {
+            name: String::from(name),
+            doc: field.get("doc").and_then(Value::as_str).map(String::from),
+            field_type: self.parse_type(field_type, namespace)?,
+            default: field.get("default").cloned(),
+        })
+    }
+}
+
+fn full_name(name: &str, namespace: Option<&str>) -> String {
+    match namespace {
+        Some(namespace) if !name.contains('.') => format!("{namespace}.{name}"),
+        _ => String::from(name),
+    }
+}
+
+fn invalid(reason: impl ToString) -> Error {
+    Error::InvalidSchema {
+        format: FORMAT,
+        reason: reason.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn costing_schema() -> Value {
+        json!({
+            "type": "record",
+            "name": "CostingRequest",
+            "namespace": "com.adeo.costing",
+            "doc": "Request for the cost of a product",
+            "fields": [
+                { "name": "requestId", "type": { "type": "string", "logicalType": "uuid" } },
+                { "name": "quantity", "type": "int" },
+                {
+                    "name": "unit",
+                    "type": { "type": "enum", "name": "Unit", "symbols": ["PIECE", "METER"] },
+                    "default": "PIECE"
+                },
+                { "name": "note", "type": ["null", "string"], "default": null },
+                { "name": "components", "type": { "type": "array", "items": "CostingRequest" } },
+                { "name": "attributes", "type": { "type": "map", "values": "string" } },
+                { "name": "preferredUnit", "type": ["null", "Unit"] }
+            ]
+        })
+    }
+
+    #[test]
+    fn resolves_named_types() {
+        let schema = AvroSchema::parse(&costing_schema()).unwrap();
+        assert_eq!(
+            schema.root(),
+            &AvroType::Named(String::from("com.adeo.costing.CostingRequest"))
+        );
+        assert!(matches!(
+            schema.resolve("com.adeo.costing.Unit"),
+            Some(NamedType::Enum { symbols, .. }) if symbols.len() == 2
+        ));
+
+        let unresolved = AvroSchema::parse(&json!({
+            "type": "record",
+            "name": "Broken",
+            "fields": [{ "name": "missing", "type": "Unknown" }]
+        }));
+        assert!(matches!(unresolved, Err(Error::UnresolvedType { name }) if name == "Unknown"));
+    }
+
+    #[test]
+    fn validates_payloads() {
+        let schema = AvroSchema::parse(&costing_schema()).unwrap();
+        let valid = json!({
+            "requestId": "1fa6ef40-8f47-40a8-8cf6-f8607d0066ef",
+            "quantity": 3,
+            "components": [{
+                "requestId": "2fa6ef40-8f47-40a8-8cf6-f8607d0066ef",
+                "quantity": 1,
+                "unit": "METER",
+                "components": [],
+                "attributes": {},
+                "preferredUnit": { "com.adeo.costing.Unit": "PIECE" }
+            }],
+            "attributes": { "color": "red" },
+            "preferredUnit": null
+        });
+        assert_eq!(schema.validate(&valid), vec![]);
+
+        let invalid = json!({
+            "requestId": "1fa6ef40-8f47-40a8-8cf6-f8607d0066ef",
+            "quantity": 4294967296_u64,
+            "unit": "LITER",
+            "components": [{}],
+            "attributes": { "color": 1 },
+            "extra": true
+        });
+        let pointers = schema
+            .validate(&invalid)
+            .into_iter()
+            .map(|error| error.pointer)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            pointers,
+            vec![
+                "/quantity",
+                "/unit",
+                "/components/0/requestId",
+                "/components/0/quantity",
+                "/components/0/components",
+                "/components/0/attributes",
+                "/components/0/preferredUnit",
+                "/attributes/color",
+                "/preferredUnit",
+                "/extra",
+            ]
+        );
+    }
+
+    #[test]
+    fn converts_to_json_schema() {
+        let schema = AvroSchema::parse(&costing_schema())
+            .unwrap()
+            .to_json_schema();
+        let schema = schema.as_value();
+        assert_eq!(
+            schema["$ref"],
+            json!("#/$defs/com.adeo.costing.CostingRequest")
+        );
+
+        let record = &schema["$defs"]["com.adeo.costing.CostingRequest"];
+        assert_eq!(record["type"], json!("object"));
+        assert_eq!(
+            record["required"],
+            json!([
+                "requestId",
+                "quantity",
+                "components",
+                "attributes",
+                "preferredUnit"
+            ])
+        );
+        assert_eq!(record["properties"]["requestId"]["format"], json!("uuid"));
+        assert_eq!(
+            record["properties"]["components"]["items"]["$ref"],
+            json!("#/$defs/com.adeo.costing.CostingRequest")
+        );
+        assert_eq!(
+            schema["$defs"]["com.adeo.costing.Unit"]["enum"],
+            json!(["PIECE", "METER"])
+        );
+    }
+
+    #[test]
+    fn validation_and_json_schema_agree_on_required_fields() {
+        let schema = AvroSchema::parse(&json!({
+            "type": "record",
+            "name": "Reading",
+            "fields": [
+                { "name": "value", "type": "double" },
+                { "name": "unit", "type": ["null", "string"] },
+                { "name": "note", "type": ["null", "string"], "default": null }
+            ]
+        }))
+        .unwrap();
+
+        let missing = schema
+            .validate(&json!({ "value": 1.5 }))
+            .into_iter()
+            .map(|error| error.pointer)
+            .collect::<Vec<_>>();
+        assert_eq!(missing, vec!["/unit"]);
+        assert_eq!(
+            schema.validate(&json!({ "value": 1.5, "unit": null })),
+            vec![]
+        );
+
+        let json_schema = schema.to_json_schema();
+        assert_eq!(
+            json_schema.as_value()["$defs"]["Reading"]["required"],
+            json!(["value", "unit"])
+        );
+    }
+
+    #[test]
+    fn overlapping_numeric_unions_accept_shared_values() {
+        let schema = AvroSchema::parse(&json!({
+            "type": "record",
+            "name": "Measure",
+            "fields": [
+                { "name": "count", "type": ["int", "long"] },
+                { "name": "ratio", "type": ["float", "double"] }
+            ]
+        }))
+        .unwrap();
+        let payload = json!({ "count": 1, "ratio": 1.5 });
+        assert_eq!(schema.validate(&payload), vec![]);
+
+        let json_schema = schema.to_json_schema();
+        assert_eq!(
+            crate::schema_format::json_schema::validate(json_schema.as_value(), &payload).unwrap(),
+            vec![]
+        );
+    }
+}
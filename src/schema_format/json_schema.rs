@@ -0,0 +1,541 @@
+//! Validation of payloads against JSON schemas. The assertion keywords shared by drafts 4 to
+//! 2020-12 are supported; `format` is treated as an annotation and only local references
+//! (`#...`) are followed.
+use crate::pointer::json_pointer;
+use crate::schema_format::{Error, ValidationError};
+use serde_json::{Map, Value};
+
+/// Maximum number of nested references followed without descending into the payload.
+const MAX_REFERENCE_DEPTH: usize = 64;
+
+/// Validates `payload` against `schema`. Fails if the schema contains references that can't be
+/// resolved or invalid patterns.
+pub fn validate(schema: &Value, payload: &Value) -> Result<Vec<ValidationError>, Error> {
+    let validator = Validator { root: schema };
+    let mut errors = Vec::new();
+    validator.validate(schema, payload, "", 0, &mut errors)?;
+    Ok(errors)
+}
+
+struct Validator<'s> {
+    root: &'s Value,
+}
+
+impl<'s> Validator<'s> {
+    fn is_valid(&self, schema: &'s Value, value: &Value, depth: usize) -> Result<bool, Error> {
+        let mut errors = Vec::new();
+        self.validate(schema, value, "", depth, &mut errors)?;
+        Ok(errors.is_empty())
+    }
+
+    fn validate(
+        &self,
+        schema: &'s Value,
+        value: &Value,
+        pointer: &str,
+        depth: usize,
+        errors: &mut Vec<ValidationError>,
+    ) -> Result<(), Error> {
+        let schema = match schema {
+            Value::Bool(true) => return Ok(()),
+            Value::Bool(false) => {
+                errors.push(ValidationError::new(pointer, "no value is allowed"));
+                return Ok(());
+            }
+            Value::Object(schema) => schema,
+            other => {
+                return Err(Error::InvalidSchema {
+                    format: "json schema",
+                    reason: format!("expected a schema, found {other}"),
+                })
+            }
+        };
+
+        if let Some(Value::String(reference)) = schema.get("$ref") {
+            if depth >= MAX_REFERENCE_DEPTH {
+                return Err(Error::InvalidSchema {
+                    format: "json schema",
+                    reason: format!("reference loop through '{reference}'"),
+                });
+            }
+            let resolved = reference
+                .strip_prefix('#')
+                .and_then(|pointer| self.root.pointer(pointer))
+                .ok_or_else(|| Error::UnresolvedType {
+                    name: reference.clone(),
+                })?;
+            self.validate(resolved, value, pointer, depth + 1, errors)?;
+        }
+
+        self.validate_generic(schema, value, pointer, errors);
+        self.validate_combinators(schema, value, pointer, depth, errors)?;
+        match value {
+            Value::Number(_) => validate_number(schema, value, pointer, errors),
+            Value::String(text) => validate_string(schema, text, pointer, errors)?,
+            Value::Array(items) => self.validate_array(schema, items, pointer, depth, errors)?,
+            Value::Object(object) => {
+                self.validate_object(schema, object, pointer, depth, errors)?
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn validate_generic(
+        &self,
+        schema: &Map<String, Value>,
+        value: &Value,
+        pointer: &str,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        match schema.get("type") {
+            Some(Value::String(type_name)) if !has_type(value, type_name) => errors.push(
+                ValidationError::new(pointer, format!("expected type '{type_name}'")),
+            ),
+            Some(Value::Array(types))
+                if !types
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .any(|type_name| has_type(value, type_name)) =>
+            {
+                let types = types
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                errors.push(ValidationError::new(
+                    pointer,
+                    format!("expected one of the types [{types}]"),
+                ))
+            }
+            _ => {}
+        }
+        if let Some(Value::Array(values)) = schema.get("enum") {
+            if !values.iter().any(|allowed| json_eq(allowed, value)) {
+                errors.push(ValidationError::new(
+                    pointer,
+                    "value is not one of the enumerated values",
+                ));
+            }
+        }
+        if let Some(constant) = schema.get("const") {
+            if !json_eq(constant, value) {
+                errors.push(ValidationError::new(
+                    pointer,
+                    format!("expected constant value {constant}"),
+                ));
+            }
+        }
+    }
+
+    fn validate_combinators(
+        &self,
+        schema: &'s Map<String, Value>,
+        value: &Value,
+        pointer: &str,
+        depth: usize,
+        errors: &mut Vec<ValidationError>,
+    ) -> Result<(), Error> {
+        if let Some(Value::Array(parts)) = schema.get("allOf") {
+            for part in parts {
+                self.validate(part, value, pointer, depth, errors)?;
+            }
+        }
+        if let Some(Value::Array(branches)) = schema.get("anyOf") {
+            let mut any = false;
+            for branch in branches {
+                any |= self.is_valid(branch, value, depth)?;
+            }
+            if !any {
+                errors.push(ValidationError::new(
+                    pointer,
+                    "value doesn't match any of the 'anyOf' schemas",
+                ));
+            }
+        }
+        if let Some(Value::Array(branches)) = schema.get("oneOf") {
+            let mut matching = 0;
+            for branch in branches {
+                matching += usize::from(self.is_valid(branch, value, depth)?);
+            }
+            if matching != 1 {
+                errors.push(ValidationError::new(
+                    pointer,
+                    format!(
+                        "value matches {matching} of the 'oneOf' schemas instead of exactly one"
+                    ),
+                ));
+            }
+        }
+        if let Some(negated) = schema.get("not") {
+            if self.is_valid(negated, value, depth)? {
+                errors.push(ValidationError::new(
+                    pointer,
+                    "value matches the 'not' schema",
+                ));
+            }
+        }
+        if let Some(condition) = schema.get("if") {
+            let branch = if self.is_valid(condition, value, depth)? {
+                schema.get("then")
+            } else {
+                schema.get("else")
+            };
+            if let Some(branch) = branch {
+                self.validate(branch, value, pointer, depth, errors)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_array(
+        &self,
+        schema: &'s Map<String, Value>,
+        items: &[Value],
+        pointer: &str,
+        depth: usize,
+        errors: &mut Vec<ValidationError>,
+    ) -> Result<(), Error> {
+        let item_pointer = |idx: usize| format!("{pointer}/{idx}");
+        // `prefixItems` (2020-12) and array valued `items` (older drafts) validate positionally
+        let (prefix, rest) = match (schema.get("prefixItems"), schema.get("items")) {
+            (Some(Value::Array(prefix)), rest) => (prefix.as_slice(), rest),
+            (None, Some(Value::Array(prefix))) => {
+                (prefix.as_slice(), schema.get("additionalItems"))
+            }
+            (_, rest) => (&[][..], rest),
+        };
+        for (idx, item) in items.iter().enumerate() {
+            let item_schema = prefix.get(idx).or(rest);
+            if let Some(item_schema) = item_schema {
+                self.validate(item_schema, item, &item_pointer(idx), depth, errors)?;
+            }
+        }
+
+        if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+            if (items.len() as u64) < min {
+                errors.push(ValidationError::new(
+                    pointer,
+                    format!("expected at least {min} items"),
+                ));
+            }
+        }
+        if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+            if items.len() as u64 > max {
+                errors.push(ValidationError::new(
+                    pointer,
+                    format!("expected at most {max} items"),
+                ));
+            }
+        }
+        if schema.get("uniqueItems") == Some(&Value::Bool(true)) {
+            let duplicated = items
+                .iter()
+                .enumerate()
+                .any(|(idx, item)| items[..idx].iter().any(|other| json_eq(item, other)));
+            if duplicated {
+                errors.push(ValidationError::new(pointer, "items are not unique"));
+            }
+        }
+        if let Some(contains) = schema.get("contains") {
+            let mut found = false;
+            for item in items {
+                found |= self.is_valid(contains, item, depth)?;
+            }
+            if !found {
+                errors.push(ValidationError::new(
+                    pointer,
+                    "no item matches the 'contains' schema",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_object(
+        &self,
+        schema: &'s Map<String, Value>,
+        object: &Map<String, Value>,
+        pointer: &str,
+        depth: usize,
+        errors: &mut Vec<ValidationError>,
+    ) -> Result<(), Error> {
+        let property_pointer = |key: &str| format!("{pointer}{}", json_pointer(&[key]));
+
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for name in required.iter().filter_map(Value::as_str) {
+                if !object.contains_key(name) {
+                    errors.push(ValidationError::new(
+                        &property_pointer(name),
+                        format!("missing required property '{name}'"),
+                    ));
+                }
+            }
+        }
+        if let Some(min) = schema.get("minProperties").and_then(Value::as_u64) {
+            if (object.len() as u64) < min {
+                errors.push(ValidationError::new(
+                    pointer,
+                    format!("expected at least {min} properties"),
+                ));
+            }
+        }
+        if let Some(max) = schema.get("maxProperties").and_then(Value::as_u64) {
+            if object.len() as u64 > max {
+                errors.push(ValidationError::new(
+                    pointer,
+                    format!("expected at most {max} properties"),
+                ));
+            }
+        }
+        if let Some(Value::Object(dependencies)) = schema.get("dependentRequired") {
+            for (name, dependent) in dependencies {
+                if !object.contains_key(name) {
+                    continue;
+                }
+                for dependent in dependent.as_array().into_iter().flatten() {
+                    if let Some(dependent) = dependent.as_str() {
+                        if !object.contains_key(dependent) {
+                            errors.push(ValidationError::new(
+                                &property_pointer(dependent),
+                                format!("property '{dependent}' is required by '{name}'"),
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let pattern_properties = schema
+            .get("patternProperties")
+            .and_then(Value::as_object)
+            .map(|patterns| {
+                patterns
+                    .iter()
+                    .map(|(pattern, schema)| Ok((compile(pattern)?, schema)))
+                    .collect::<Result<Vec<_>, Error>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        for (key, property) in object {
+            let property_pointer = property_pointer(key);
+            if let Some(names) = schema.get("propertyNames") {
+                let name = Value::String(key.clone());
+                self.validate(names, &name, &property_pointer, depth, errors)?;
+            }
+            let mut evaluated = false;
+            if let Some(property_schema) = properties.and_then(|properties| properties.get(key)) {
+                evaluated = true;
+                self.validate(property_schema, property, &property_pointer, depth, errors)?;
+            }
+            for (pattern, property_schema) in &pattern_properties {
+                if pattern.is_match(key) {
+                    evaluated = true;
+                    self.validate(property_schema, property, &property_pointer, depth, errors)?;
+                }
+            }
+            if evaluated {
+                continue;
+            }
+            match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => errors.push(ValidationError::new(
+                    &property_pointer,
+                    format!("additional property '{key}' is not allowed"),
+                )),
+                Some(additional) => {
+                    self.validate(additional, property, &property_pointer, depth, errors)?
+                }
+                None => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+fn validate_number(
+    schema: &Map<String, Value>,
+    value: &Value,
+    pointer: &str,
+    errors: &mut Vec<ValidationError>,
+) {
+    let Some(number) = value.as_f64() else {
+        return;
+    };
+    let bound = |key| schema.get(key).and_then(Value::as_f64);
+    // draft-04 expresses exclusive bounds as boolean modifiers
+    let exclusive_flag = |key| schema.get(key) == Some(&Value::Bool(true));
+
+    if let Some(minimum) = bound("minimum") {
+        if number < minimum || (exclusive_flag("exclusiveMinimum") && number == minimum) {
+            errors.push(ValidationError::new(
+                pointer,
+                format!("value is lower than {minimum}"),
+            ));
+        }
+    }
+    if let Some(minimum) = bound("exclusiveMinimum") {
+        if number <= minimum {
+            errors.push(ValidationError::new(
+                pointer,
+                format!("value must be greater than {minimum}"),
+            ));
+        }
+    }
+    if let Some(maximum) = bound("maximum") {
+        if number > maximum || (exclusive_flag("exclusiveMaximum") && number == maximum) {
+            errors.push(ValidationError::new(
+                pointer,
+                format!("value is greater than {maximum}"),
+            ));
+        }
+    }
+    if let Some(maximum) = bound("exclusiveMaximum") {
+        if number >= maximum {
+            errors.push(ValidationError::new(
+                pointer,
+                format!("value must be lower than {maximum}"),
+            ));
+        }
+    }
+    if let Some(multiple) = bound("multipleOf").filter(|multiple| *multiple > 0.0) {
+        let quotient = number / multiple;
+        if (quotient - quotient.round()).abs() > 1e-9 {
+            errors.push(ValidationError::new(
+                pointer,
+                format!("value is not a multiple of {multiple}"),
+            ));
+        }
+    }
+}
+
+fn validate_string(
+    schema: &Map<String, Value>,
+    text: &str,
+    pointer: &str,
+    errors: &mut Vec<ValidationError>,
+) -> Result<(), Error> {
+    let length = text.chars().count() as u64;
+    if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+        if length < min {
+            errors.push(ValidationError::new(
+                pointer,
+                format!("expected at least {min} characters"),
+            ));
+        }
+    }
+    if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+        if length > max {
+            errors.push(ValidationError::new(
+                pointer,
+                format!("expected at most {max} characters"),
+            ));
+        }
+    }
+    if let Some(Value::String(pattern)) = schema.get("pattern") {
+        if !compile(pattern)?.is_match(text) {
+            errors.push(ValidationError::new(
+                pointer,
+                format!("value doesn't match the pattern '{pattern}'"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn compile(pattern: &str) -> Result<regex::Regex, Error> {
+    regex::Regex::new(pattern).map_err(|err| Error::InvalidSchema {
+        format: "json schema",
+        reason: format!("invalid pattern '{pattern}': {err}"),
+    })
+}
+
+fn has_type(value: &Value, type_name: &str) -> bool {
+    match type_name {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => match value {
+            Value::Number(number) => {
+                number.is_i64()
+                    || number.is_u64()
+                    || number.as_f64().is_some_and(|f| f.fract() == 0.0)
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// JSON equality where numbers are compared by value (`1` equals `1.0`).
+fn json_eq(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Number(left), Value::Number(right)) => left.as_f64() == right.as_f64(),
+        (Value::Array(left), Value::Array(right)) => {
+            left.len() == right.len() && left.iter().zip(right).all(|(l, r)| json_eq(l, r))
+        }
+        (Value::Object(left), Value::Object(right)) => {
+            left.len() == right.len()
+                && left
+                    .iter()
+                    .all(|(key, l)| right.get(key).is_some_and(|r| json_eq(l, r)))
+        }
+        _ => left == right,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn validates_common_keywords() {
+        let schema = json!({
+            "type": "object",
+            "required": ["id", "kind"],
+            "additionalProperties": false,
+            "properties": {
+                "id": { "type": "string", "pattern": "^[a-z]+-[0-9]+$" },
+                "kind": { "enum": ["a", "b"] },
+                "count": { "type": "integer", "minimum": 1, "exclusiveMaximum": 10 },
+                "items": { "type": "array", "items": { "$ref": "#/$defs/Item" }, "uniqueItems": true },
+                "target": { "oneOf": [{ "type": "string" }, { "type": "integer" }] }
+            },
+            "$defs": {
+                "Item": { "type": "object", "required": ["sku"] }
+            }
+        });
+
+        let valid = json!({ "id": "order-1", "kind": "a", "count": 3.0, "items": [{ "sku": "x" }], "target": 1 });
+        assert_eq!(validate(&schema, &valid).unwrap(), vec![]);
+
+        let invalid =
+            json!({ "id": "Order", "count": 10, "items": [{}, {}], "target": true, "extra": 1 });
+        let pointers = validate(&schema, &invalid)
+            .unwrap()
+            .into_iter()
+            .map(|error| error.pointer)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            pointers,
+            vec![
+                "/kind",
+                "/id",
+                "/count",
+                "/items/0/sku",
+                "/items/1/sku",
+                "/items",
+                "/target",
+                "/extra",
+            ]
+        );
+
+        let unresolved = json!({ "$ref": "#/components/schemas/Missing" });
+        assert!(validate(&unresolved, &json!(1)).is_err());
+    }
+}
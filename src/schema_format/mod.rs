@@ -0,0 +1,227 @@
+//! Interpretation of [Multi Format Schema
+//! Objects](https://www.asyncapi.com/docs/reference/specification/v3.0.0#multiFormatSchemaObject)
+//! whose `schemaFormat` is not a JSON schema.
+use crate::spec::common::Either;
+use crate::spec::message::MultiFormatSchema;
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+use thiserror::Error;
+
+pub mod avro;
+pub mod json_schema;
+pub mod openapi;
+pub mod protobuf;
+pub mod raml;
+pub mod registry;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Invalid {format} schema: {reason}")]
+    InvalidSchema {
+        format: &'static str,
+        reason: String,
+    },
+    #[error("Unresolved type '{name}'")]
+    UnresolvedType { name: String },
+    #[error("Schema format '{schema_format}' can't select the type '{target}'")]
+    UnsupportedTarget {
+        schema_format: String,
+        target: String,
+    },
+    #[error("Unsupported schema format '{schema_format}'")]
+    UnsupportedFormat { schema_format: String },
+    #[error("Unsupported version '{version}' of schema format '{schema_format}'")]
+    UnsupportedVersion {
+        schema_format: String,
+        version: String,
+    },
+}
+
+/// A payload validation failure.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationError {
+    /// JSON pointer of the invalid value inside the validated payload.
+    pub pointer: String,
+    /// Description of the failure.
+    pub message: String,
+}
+
+impl ValidationError {
+    pub(crate) fn new(pointer: &str, message: impl Into<String>) -> Self {
+        Self {
+            pointer: String::from(pointer),
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let pointer = if self.pointer.is_empty() {
+            "/"
+        } else {
+            &self.pointer
+        };
+        write!(f, "{pointer}: {}", self.message)
+    }
+}
+
+/// A parsed `schemaFormat` value, e.g. `application/vnd.apache.avro+json;version=1.9.0`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemaFormat {
+    /// Media type without parameters, lowercased.
+    pub media_type: String,
+    /// Value of the `version` parameter, if any.
+    pub version: Option<String>,
+}
+
+impl SchemaFormat {
+    pub fn parse(schema_format: &str) -> Self {
+        let mut parts = schema_format.split(';');
+        let media_type = parts.next().unwrap_or_default().trim().to_lowercase();
+        let version = parts.find_map(|parameter| {
+            let (key, value) = parameter.split_once('=')?;
+            key.trim()
+                .eq_ignore_ascii_case("version")
+                .then(|| String::from(value.trim().trim_matches('"')))
+        });
+        Self {
+            media_type,
+            version,
+        }
+    }
+
+    /// Media type without the structured syntax suffix (`+json`, `+yaml`).
+    pub fn base_media_type(&self) -> &str {
+        self.media_type
+            .strip_suffix("+json")
+            .or_else(|| self.media_type.strip_suffix("+yaml"))
+            .unwrap_or(&self.media_type)
+    }
+
+    pub fn is_avro(&self) -> bool {
+        self.base_media_type() == avro::MEDIA_TYPE
+    }
+
+    pub fn is_protobuf(&self) -> bool {
+        self.base_media_type() == protobuf::MEDIA_TYPE
+    }
+
+    pub fn is_openapi(&self) -> bool {
+        self.base_media_type() == openapi::MEDIA_TYPE
+    }
+
+    pub fn is_raml(&self) -> bool {
+        self.base_media_type() == raml::MEDIA_TYPE
+    }
+
+    /// Whether the format is a JSON schema, either the AsyncAPI schema or a JSON schema draft.
+    pub fn is_json_schema(&self) -> bool {
+        matches!(
+            self.base_media_type(),
+            "application/vnd.aai.asyncapi" | "application/schema"
+        )
+    }
+}
+
+/// Converts a multi format schema to JSON schema using the handlers of the
+/// [default registry](registry::SchemaFormatRegistry::default). Protobuf schemas are converted
+/// using their first top level message, see [`to_json_schema_for`] to select another one.
+pub fn to_json_schema(schema: &MultiFormatSchema) -> Result<schemars::Schema, Error> {
+    registry::SchemaFormatRegistry::default().to_json_schema(schema)
+}
+
+/// Converts the type named `target` of a multi format schema declaring several types (e.g. a
+/// Protobuf message) to JSON schema, using the handlers of the
+/// [default registry](registry::SchemaFormatRegistry::default).
+pub fn to_json_schema_for(
+    schema: &MultiFormatSchema,
+    target: &str,
+) -> Result<schemars::Schema, Error> {
+    registry::SchemaFormatRegistry::default().to_json_schema_for(schema, target)
+}
+
+/// JSON value of a converted schema whose references to its `$defs` are replaced by the
+/// definitions, except recursive ones, for consumers only following component schemas.
+pub(crate) fn inline_definitions(schema: schemars::Schema) -> serde_json::Value {
+    let mut schema = schema.to_value();
+    let definitions = schema
+        .as_object_mut()
+        .and_then(|object| object.remove("$defs"))
+        .unwrap_or_default();
+    inline_value_definitions(&mut schema, &definitions, &mut vec![]);
+    schema
+}
+
+fn inline_value_definitions<'d>(
+    value: &mut serde_json::Value,
+    definitions: &'d serde_json::Value,
+    stack: &mut Vec<&'d str>,
+) {
+    let definition = value
+        .get("$ref")
+        .and_then(serde_json::Value::as_str)
+        .and_then(|reference| reference.strip_prefix("#/$defs/"))
+        .and_then(|name| definitions.as_object()?.get_key_value(name));
+    if let Some((name, definition)) = definition {
+        if !stack.contains(&name.as_str()) {
+            *value = definition.clone();
+            stack.push(name);
+            inline_value_definitions(value, definitions, stack);
+            stack.pop();
+        }
+        return;
+    }
+    match value {
+        serde_json::Value::Object(object) => object
+            .values_mut()
+            .for_each(|value| inline_value_definitions(value, definitions, stack)),
+        serde_json::Value::Array(values) => values
+            .iter_mut()
+            .for_each(|value| inline_value_definitions(value, definitions, stack)),
+        _ => {}
+    }
+}
+
+impl MultiFormatSchema {
+    /// Multi format schemas are structurally valid schema objects, so they are deserialized as
+    /// [`schemars::Schema`] when stored in an [`Either`]. Returns the multi format schema if the
+    /// given schema has the `schemaFormat` and `schema` properties.
+    pub fn from_schema(schema: &schemars::Schema) -> Option<Self> {
+        let object = schema.as_object()?;
+        match (object.get("schemaFormat"), object.get("schema")) {
+            (Some(serde_json::Value::String(schema_format)), Some(schema)) => Some(Self {
+                schema_format: schema_format.clone(),
+                schema: schema.clone(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns the multi format schema stored in `schema`, whichever variant it was deserialized
+    /// into.
+    pub fn from_either(
+        schema: &Either<schemars::Schema, MultiFormatSchema>,
+    ) -> Option<Cow<'_, MultiFormatSchema>> {
+        match schema {
+            Either::Left(schema) => Self::from_schema(schema).map(Cow::Owned),
+            Either::Right(multi_format) => Some(Cow::Borrowed(multi_format)),
+        }
+    }
+
+    /// Parsed `schemaFormat` of this schema.
+    pub fn format(&self) -> SchemaFormat {
+        SchemaFormat::parse(&self.schema_format)
+    }
+
+    /// JSON schema equivalent to this schema, see [`to_json_schema`].
+    pub fn to_json_schema(&self) -> Result<schemars::Schema, Error> {
+        to_json_schema(self)
+    }
+
+    /// JSON schema of the type named `target` declared by this schema, see
+    /// [`to_json_schema_for`].
+    pub fn to_json_schema_for(&self, target: &str) -> Result<schemars::Schema, Error> {
+        to_json_schema_for(self, target)
+    }
+}
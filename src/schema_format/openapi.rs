@@ -0,0 +1,268 @@
+//! Conversion of [OpenAPI 3.0 schema objects](https://spec.openapis.org/oas/v3.0.3#schema-object),
+//! declared with `schemaFormat: application/vnd.oai.openapi;version=3.0.0`, to JSON schema.
+use crate::schema_format::Error;
+use serde_json::{json, Map, Value};
+
+/// OpenAPI media type, without version and structured syntax suffix.
+pub const MEDIA_TYPE: &str = "application/vnd.oai.openapi";
+
+/// Which side of the exchange the converted schema describes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Visibility {
+    /// Keep every property, `readOnly` and `writeOnly` are kept as annotations.
+    #[default]
+    All,
+    /// The schema describes data read from the API: `writeOnly` properties are removed.
+    Read,
+    /// The schema describes data written to the API: `readOnly` properties are removed.
+    Write,
+}
+
+/// Converts an OpenAPI 3.0 schema object to JSON schema.
+///
+/// - `nullable` is translated to a `null` type (or an `anyOf` with the `null` type)
+/// - `discriminator` mappings are translated to `const` constraints on every `oneOf`/`anyOf`
+///   branch
+/// - boolean `exclusiveMinimum`/`exclusiveMaximum` are translated to their numeric form
+/// - `example` is translated to `examples`
+/// - `readOnly`/`writeOnly` properties are filtered according to `visibility`
+pub fn to_json_schema(schema: &Value, visibility: Visibility) -> Result<schemars::Schema, Error> {
+    match convert(schema, visibility)? {
+        Value::Object(object) => Ok(schemars::Schema::from(object)),
+        Value::Bool(value) => Ok(schemars::Schema::from(value)),
+        other => Err(invalid(format!("expected a schema object, found {other}"))),
+    }
+}
+
+fn convert(schema: &Value, visibility: Visibility) -> Result<Value, Error> {
+    let object = match schema {
+        Value::Object(object) => object,
+        Value::Bool(_) => return Ok(schema.clone()),
+        other => return Err(invalid(format!("expected a schema object, found {other}"))),
+    };
+    if object.contains_key("$ref") {
+        // siblings of references are ignored in OpenAPI 3.0
+        return Ok(json!({ "$ref": object["$ref"] }));
+    }
+
+    let mut converted = Map::new();
+    for (key, value) in object {
+        match key.as_str() {
+            "nullable" | "discriminator" | "example" | "exclusiveMinimum" | "exclusiveMaximum" => {}
+            "properties" => {
+                let properties = value
+                    .as_object()
+                    .ok_or_else(|| invalid("'properties' must be an object"))?;
+                let mut converted_properties = Map::new();
+                for (name, property) in properties {
+                    if is_hidden(property, visibility) {
+                        continue;
+                    }
+                    converted_properties.insert(name.clone(), convert(property, visibility)?);
+                }
+                converted.insert(key.clone(), Value::Object(converted_properties));
+            }
+            "items" | "additionalProperties" | "not" => {
+                converted.insert(key.clone(), convert(value, visibility)?);
+            }
+            "allOf" | "oneOf" | "anyOf" => {
+                let branches = value
+                    .as_array()
+                    .ok_or_else(|| invalid(format!("'{key}' must be an array")))?
+                    .iter()
+                    .map(|branch| convert(branch, visibility))
+                    .collect::<Result<Vec<_>, _>>()?;
+                converted.insert(key.clone(), Value::Array(branches));
+            }
+            _ => {
+                converted.insert(key.clone(), value.clone());
+            }
+        }
+    }
+
+    if let Some(required) = converted.get_mut("required").and_then(Value::as_array_mut) {
+        let properties = object.get("properties").and_then(Value::as_object);
+        required.retain(|name| {
+            let property = name
+                .as_str()
+                .and_then(|name| properties.and_then(|properties| properties.get(name)));
+            !property.is_some_and(|property| is_hidden(property, visibility))
+        });
+    }
+
+    for (keyword, bound) in [
+        ("exclusiveMinimum", "minimum"),
+        ("exclusiveMaximum", "maximum"),
+    ] {
+        match object.get(keyword) {
+            Some(Value::Bool(true)) => {
+                if let Some(bound) = converted.shift_remove(bound) {
+                    converted.insert(String::from(keyword), bound);
+                }
+            }
+            // already in the JSON schema form
+            Some(number @ Value::Number(_)) => {
+                converted.insert(String::from(keyword), number.clone());
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(example) = object.get("example") {
+        converted.insert(String::from("examples"), json!([example]));
+    }
+
+    if let Some(discriminator) = object.get("discriminator") {
+        apply_discriminator(&mut converted, discriminator)?;
+    }
+
+    if object.get("nullable") == Some(&Value::Bool(true)) {
+        return Ok(make_nullable(converted));
+    }
+    Ok(Value::Object(converted))
+}
+
+fn is_hidden(property: &Value, visibility: Visibility) -> bool {
+    let flag = |key| property.get(key) == Some(&Value::Bool(true));
+    match visibility {
+        Visibility::All => false,
+        Visibility::Read => flag("writeOnly"),
+        Visibility::Write => flag("readOnly"),
+    }
+}
+
+fn make_nullable(mut schema: Map<String, Value>) -> Value {
+    if let Some(Value::Array(values)) = schema.get_mut("enum") {
+        if !values.contains(&Value::Null) {
+            values.push(Value::Null);
+        }
+    }
+    match schema.shift_remove("type") {
+        Some(Value::String(type_name)) => {
+            schema.insert(String::from("type"), json!([type_name, "null"]));
+            Value::Object(schema)
+        }
+        Some(Value::Array(mut types)) => {
+            if !types.contains(&Value::from("null")) {
+                types.push(Value::from("null"));
+            }
+            schema.insert(String::from("type"), Value::Array(types));
+            Value::Object(schema)
+        }
+        _ => json!({ "anyOf": [Value::Object(schema), { "type": "null" }] }),
+    }
+}
+
+/// Adds to every referenced `oneOf`/`anyOf` branch the constraint on the discriminator property,
+/// using the explicit mapping or the name of the referenced schema.
+fn apply_discriminator(
+    schema: &mut Map<String, Value>,
+    discriminator: &Value,
+) -> Result<(), Error> {
+    let property_name = discriminator
+        .get("propertyName")
+        .and_then(Value::as_str)
+        .ok_or_else(|| invalid("discriminator without 'propertyName'"))?;
+    let mapping = discriminator.get("mapping").and_then(Value::as_object);
+    let discriminator_value = |reference: &str| {
+        mapping
+            .and_then(|mapping| {
+                mapping
+                    .iter()
+                    .find(|(_, target)| target.as_str() == Some(reference))
+                    .map(|(value, _)| value.clone())
+            })
+            .unwrap_or_else(|| String::from(reference.rsplit('/').next().unwrap_or(reference)))
+    };
+
+    for keyword in ["oneOf", "anyOf"] {
+        let Some(Value::Array(branches)) = schema.get_mut(keyword) else {
+            continue;
+        };
+        for branch in branches.iter_mut() {
+            let Some(reference) = branch.get("$ref").and_then(Value::as_str) else {
+                continue;
+            };
+            let value = discriminator_value(reference);
+            *branch = json!({
+                "allOf": [
+                    branch.clone(),
+                    {
+                        "type": "object",
+                        "properties": { property_name: { "const": value } },
+                        "required": [property_name],
+                    }
+                ]
+            });
+        }
+    }
+    Ok(())
+}
+
+fn invalid(reason: impl ToString) -> Error {
+    Error::InvalidSchema {
+        format: "openapi",
+        reason: reason.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn converts_openapi_keywords() {
+        let schema = json!({
+            "type": "object",
+            "required": ["id", "password"],
+            "properties": {
+                "id": { "type": "string", "readOnly": true },
+                "password": { "type": "string", "writeOnly": true },
+                "nickname": { "type": "string", "nullable": true, "example": "jdoe" },
+                "score": { "type": "number", "minimum": 0, "exclusiveMinimum": true },
+                "pet": {
+                    "oneOf": [
+                        { "$ref": "#/components/schemas/Cat" },
+                        { "$ref": "#/components/schemas/Dog" }
+                    ],
+                    "discriminator": {
+                        "propertyName": "kind",
+                        "mapping": { "cat": "#/components/schemas/Cat" }
+                    }
+                }
+            }
+        });
+
+        let converted = to_json_schema(&schema, Visibility::All).unwrap();
+        let converted = converted.as_value();
+        assert_eq!(
+            converted["properties"]["nickname"]["type"],
+            json!(["string", "null"])
+        );
+        assert_eq!(
+            converted["properties"]["nickname"]["examples"],
+            json!(["jdoe"])
+        );
+        assert_eq!(
+            converted["properties"]["score"],
+            json!({ "type": "number", "exclusiveMinimum": 0 })
+        );
+        let branches = &converted["properties"]["pet"]["oneOf"];
+        assert_eq!(
+            branches[0]["allOf"][1]["properties"]["kind"]["const"],
+            json!("cat")
+        );
+        assert_eq!(
+            branches[1]["allOf"][1]["properties"]["kind"]["const"],
+            json!("Dog")
+        );
+
+        let write = to_json_schema(&schema, Visibility::Write).unwrap();
+        assert!(write.as_value()["properties"].get("id").is_none());
+        assert_eq!(write.as_value()["required"], json!(["password"]));
+
+        let read = to_json_schema(&schema, Visibility::Read).unwrap();
+        assert!(read.as_value()["properties"].get("password").is_none());
+        assert_eq!(read.as_value()["required"], json!(["id"]));
+    }
+}
@@ -0,0 +1,1198 @@
+//! Support for [Protocol Buffers](https://protobuf.dev/programming-guides/proto3/) schemas,
+//! declared with `schemaFormat: application/vnd.google.protobuf;version=3` and inlined as `.proto`
+//! source text.
+//!
+//! Messages are exposed through their [proto3 JSON
+//! mapping](https://protobuf.dev/programming-guides/json/), which is also how message payloads
+//! and examples are validated.
+use crate::pointer::json_pointer;
+use crate::schema_format::{Error, ValidationError};
+use crate::spec::message::MultiFormatSchema;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+/// Protobuf media type, without version.
+pub const MEDIA_TYPE: &str = "application/vnd.google.protobuf";
+
+const FORMAT: &str = "protobuf";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScalarType {
+    Double,
+    Float,
+    Int32,
+    Int64,
+    Uint32,
+    Uint64,
+    Sint32,
+    Sint64,
+    Fixed32,
+    Fixed64,
+    Sfixed32,
+    Sfixed64,
+    Bool,
+    String,
+    Bytes,
+}
+
+impl ScalarType {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "double" => Self::Double,
+            "float" => Self::Float,
+            "int32" => Self::Int32,
+            "int64" => Self::Int64,
+            "uint32" => Self::Uint32,
+            "uint64" => Self::Uint64,
+            "sint32" => Self::Sint32,
+            "sint64" => Self::Sint64,
+            "fixed32" => Self::Fixed32,
+            "fixed64" => Self::Fixed64,
+            "sfixed32" => Self::Sfixed32,
+            "sfixed64" => Self::Sfixed64,
+            "bool" => Self::Bool,
+            "string" => Self::String,
+            "bytes" => Self::Bytes,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FieldType {
+    Scalar(ScalarType),
+    /// A message or enum, by fully qualified name (without leading dot).
+    Named(String),
+    Map {
+        key: ScalarType,
+        value: Box<FieldType>,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FieldLabel {
+    /// Proto3 field without label.
+    Singular,
+    Optional,
+    Required,
+    Repeated,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProtoField {
+    pub name: String,
+    /// Name used by the JSON mapping, `lowerCamelCase` unless overridden by the `json_name` option.
+    pub json_name: String,
+    pub number: u32,
+    pub label: FieldLabel,
+    pub field_type: FieldType,
+    /// Name of the `oneof` the field belongs to.
+    pub oneof: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProtoMessage {
+    /// Fully qualified name.
+    pub name: String,
+    pub fields: Vec<ProtoField>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProtoEnum {
+    /// Fully qualified name.
+    pub name: String,
+    pub values: Vec<(String, i32)>,
+}
+
+/// A parsed `.proto` file with all the type references resolved.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProtobufSchema {
+    package: Option<String>,
+    messages: BTreeMap<String, ProtoMessage>,
+    enums: BTreeMap<String, ProtoEnum>,
+    /// Fully qualified names of the top level messages, in declaration order.
+    top_level: Vec<String>,
+}
+
+impl ProtobufSchema {
+    /// Parses `.proto` source text. Imported files are not available, so only types declared in
+    /// the source and the well-known `google.protobuf` types can be referenced.
+    pub fn parse(source: &str) -> Result<Self, Error> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+            package: None,
+            messages: BTreeMap::new(),
+            enums: BTreeMap::new(),
+            top_level: vec![],
+        };
+        parser.parse_file()?;
+        let mut schema = Self {
+            package: parser.package,
+            messages: parser.messages,
+            enums: parser.enums,
+            top_level: parser.top_level,
+        };
+        schema.resolve_references()?;
+        Ok(schema)
+    }
+
+    /// Parses the `.proto` source of a Protobuf multi format schema.
+    pub fn from_multi_format(schema: &MultiFormatSchema) -> Result<Self, Error> {
+        if !schema.format().is_protobuf() {
+            return Err(Error::UnsupportedFormat {
+                schema_format: schema.schema_format.clone(),
+            });
+        }
+        match &schema.schema {
+            serde_json::Value::String(source) => Self::parse(source),
+            _ => Err(invalid("the schema must be inlined as a string")),
+        }
+    }
+
+    pub fn package(&self) -> Option<&str> {
+        self.package.as_deref()
+    }
+
+    /// Returns a message given its fully qualified or package relative name.
+    pub fn message(&self, name: &str) -> Option<&ProtoMessage> {
+        let name = name.trim_start_matches('.');
+        self.messages.get(name).or_else(|| {
+            let package = self.package.as_deref()?;
+            self.messages.get(&format!("{package}.{name}"))
+        })
+    }
+
+    pub fn enumeration(&self, name: &str) -> Option<&ProtoEnum> {
+        self.enums.get(name.trim_start_matches('.'))
+    }
+
+    /// Top level messages, in declaration order.
+    pub fn top_level_messages(&self) -> impl Iterator<Item = &ProtoMessage> {
+        self.top_level
+            .iter()
+            .filter_map(|name| self.messages.get(name))
+    }
+
+    /// Selects the message describing the payload: the given one, or the first top level message
+    /// declared in the file when `message` is `None`.
+    pub fn target_message(&self, message: Option<&str>) -> Result<&ProtoMessage, Error> {
+        match message {
+            Some(name) => self.message(name).ok_or_else(|| Error::UnresolvedType {
+                name: String::from(name),
+            }),
+            None => self
+                .top_level_messages()
+                .next()
+                .ok_or_else(|| invalid("the schema doesn't declare any message")),
+        }
+    }
+
+    /// Converts the target message (see [`Self::target_message`]) to a JSON schema describing its
+    /// JSON mapping. Messages and enums are stored under `$defs` by fully qualified name.
+    pub fn to_json_schema(&self, message: Option<&str>) -> Result<schemars::Schema, Error> {
+        let target = self.target_message(message)?;
+        let mut definitions = Map::new();
+        self.collect_definitions(&target.name, &mut definitions);
+        let mut root = Map::new();
+        root.insert(
+            String::from("$ref"),
+            Value::from(format!("#/$defs/{}", target.name)),
+        );
+        root.insert(String::from("$defs"), Value::Object(definitions));
+        Ok(schemars::Schema::from(root))
+    }
+
+    /// Validates a JSON payload against the JSON mapping of the target message.
+    pub fn validate(
+        &self,
+        message: Option<&str>,
+        payload: &Value,
+    ) -> Result<Vec<ValidationError>, Error> {
+        let target = self.target_message(message)?;
+        let mut errors = Vec::new();
+        self.validate_message(target, payload, "", &mut errors);
+        Ok(errors)
+    }
+
+    fn resolve_references(&mut self) -> Result<(), Error> {
+        let mut resolved = BTreeMap::new();
+        for (message_name, message) in &self.messages {
+            let mut fields = Vec::with_capacity(message.fields.len());
+            for field in &message.fields {
+                let field_type = self.resolve_field_type(message_name, &field.field_type)?;
+                fields.push(ProtoField {
+                    field_type,
+                    ..field.clone()
+                });
+            }
+            resolved.insert(message_name.clone(), fields);
+        }
+        for (message_name, fields) in resolved {
+            if let Some(message) = self.messages.get_mut(&message_name) {
+                message.fields = fields;
+            }
+        }
+        Ok(())
+    }
+
+    fn resolve_field_type(&self, scope: &str, field_type: &FieldType) -> Result<FieldType, Error> {
+        match field_type {
+            FieldType::Scalar(_) => Ok(field_type.clone()),
+            FieldType::Map { key, value } => Ok(FieldType::Map {
+                key: *key,
+                value: Box::new(self.resolve_field_type(scope, value)?),
+            }),
+            FieldType::Named(name) => self
+                .resolve_name(scope, name)
+                .map(FieldType::Named)
+                .ok_or_else(|| Error::UnresolvedType { name: name.clone() }),
+        }
+    }
+
+    /// Resolves a type name following the protobuf scoping rules: the name is searched from the
+    /// innermost scope outwards.
+    fn resolve_name(&self, scope: &str, name: &str) -> Option<String> {
+        let exists = |candidate: &str| {
+            self.messages.contains_key(candidate)
+                || self.enums.contains_key(candidate)
+                || well_known_type(candidate).is_some()
+        };
+        if let Some(qualified) = name.strip_prefix('.') {
+            return exists(qualified).then(|| String::from(qualified));
+        }
+        let mut scope = Some(scope);
+        while let Some(current) = scope {
+            let candidate = format!("{current}.{name}");
+            if exists(&candidate) {
+                return Some(candidate);
+            }
+            scope = current.rsplit_once('.').map(|(parent, _)| parent);
+        }
+        exists(name).then(|| String::from(name))
+    }
+
+    fn collect_definitions(&self, name: &str, definitions: &mut Map<String, Value>) {
+        if definitions.contains_key(name) || well_known_type(name).is_some() {
+            return;
+        }
+        if let Some(enumeration) = self.enums.get(name) {
+            // the JSON mapping accepts both the names and the numbers of the values
+            let names = enumeration
+                .values
+                .iter()
+                .map(|(value, _)| value)
+                .collect::<Vec<_>>();
+            definitions.insert(
+                String::from(name),
+                json!({
+                    "title": name,
+                    "anyOf": [
+                        { "type": "string", "enum": names },
+                        { "type": "integer" },
+                    ],
+                }),
+            );
+            return;
+        }
+        let Some(message) = self.messages.get(name) else {
+            return;
+        };
+        // insert a placeholder first so that recursive messages terminate
+        definitions.insert(String::from(name), Value::Null);
+
+        let mut properties = Map::new();
+        let mut required = Vec::new();
+        let mut oneofs = BTreeMap::<&str, Vec<&str>>::new();
+        for field in &message.fields {
+            let mut property = self.field_type_to_json_schema(&field.field_type, definitions);
+            if field.label == FieldLabel::Repeated {
+                property = json!({ "type": "array", "items": property });
+            }
+            // `null` stands for the default value of any field
+            property = nullable(property);
+            if field.label == FieldLabel::Required {
+                required.push(Value::from(field.json_name.as_str()));
+            }
+            if let Some(oneof) = &field.oneof {
+                oneofs.entry(oneof).or_default().push(&field.json_name);
+            }
+            properties.insert(field.json_name.clone(), property);
+        }
+
+        let mut schema = Map::new();
+        schema.insert(String::from("title"), Value::from(name));
+        schema.insert(String::from("type"), Value::from("object"));
+        schema.insert(String::from("properties"), Value::Object(properties));
+        if !required.is_empty() {
+            schema.insert(String::from("required"), Value::Array(required));
+        }
+        schema.insert(String::from("additionalProperties"), Value::Bool(false));
+        if !oneofs.is_empty() {
+            // at most one member of each oneof can be set
+            let constraints = oneofs
+                .values()
+                .map(|members| {
+                    // a `null` member is not set
+                    let each = members
+                        .iter()
+                        .map(|member| {
+                            json!({
+                                "required": [member],
+                                "properties": { *member: { "not": { "type": "null" } } },
+                            })
+                        })
+                        .collect::<Vec<_>>();
+                    let mut alternatives = each.clone();
+                    alternatives.push(json!({ "not": { "anyOf": each } }));
+                    json!({ "oneOf": alternatives })
+                })
+                .collect::<Vec<_>>();
+            schema.insert(String::from("allOf"), Value::Array(constraints));
+        }
+        definitions.insert(String::from(name), Value::Object(schema));
+    }
+
+    fn field_type_to_json_schema(
+        &self,
+        field_type: &FieldType,
+        definitions: &mut Map<String, Value>,
+    ) -> Value {
+        match field_type {
+            FieldType::Scalar(scalar) => scalar_to_json_schema(*scalar),
+            FieldType::Map { value, .. } => json!({
+                "type": "object",
+                "additionalProperties": self.field_type_to_json_schema(value, definitions),
+            }),
+            FieldType::Named(name) => match well_known_type(name) {
+                Some(schema) => schema,
+                None => {
+                    self.collect_definitions(name, definitions);
+                    json!({ "$ref": format!("#/$defs/{name}") })
+                }
+            },
+        }
+    }
+
+    fn validate_message(
+        &self,
+        message: &ProtoMessage,
+        value: &Value,
+        pointer: &str,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        let Value::Object(object) = value else {
+            errors.push(ValidationError::new(
+                pointer,
+                format!("expected message '{}'", message.name),
+            ));
+            return;
+        };
+        let mut set_oneofs = BTreeMap::<&str, &str>::new();
+        for (key, field_value) in object {
+            let field_pointer = format!("{pointer}{}", json_pointer(&[key]));
+            let Some(field) = message.fields.iter().find(|field| field.json_name == *key) else {
+                errors.push(ValidationError::new(
+                    &field_pointer,
+                    format!("unknown field '{key}' in message '{}'", message.name),
+                ));
+                continue;
+            };
+            if field_value.is_null() {
+                continue;
+            }
+            if let Some(oneof) = &field.oneof {
+                if let Some(other) = set_oneofs.insert(oneof, key) {
+                    errors.push(ValidationError::new(
+                        &field_pointer,
+                        format!("fields '{other}' and '{key}' of oneof '{oneof}' are both set"),
+                    ));
+                }
+            }
+            match (field.label, field_value) {
+                (FieldLabel::Repeated, Value::Array(items)) => {
+                    for (idx, item) in items.iter().enumerate() {
+                        let item_pointer = format!("{field_pointer}/{idx}");
+                        self.validate_field_type(&field.field_type, item, &item_pointer, errors);
+                    }
+                }
+                (FieldLabel::Repeated, _) => {
+                    errors.push(ValidationError::new(&field_pointer, "expected array"))
+                }
+                _ => {
+                    self.validate_field_type(&field.field_type, field_value, &field_pointer, errors)
+                }
+            }
+        }
+        for field in &message.fields {
+            if field.label == FieldLabel::Required && !object.contains_key(&field.json_name) {
+                errors.push(ValidationError::new(
+                    &format!("{pointer}{}", json_pointer(&[&field.json_name])),
+                    format!("missing required field '{}'", field.name),
+                ));
+            }
+        }
+    }
+
+    fn validate_field_type(
+        &self,
+        field_type: &FieldType,
+        value: &Value,
+        pointer: &str,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        match field_type {
+            FieldType::Scalar(scalar) => {
+                if let Some(expected) = validate_scalar(*scalar, value) {
+                    errors.push(ValidationError::new(
+                        pointer,
+                        format!("expected {expected}"),
+                    ));
+                }
+            }
+            FieldType::Map { value: values, .. } => match value {
+                Value::Object(entries) => {
+                    for (key, entry) in entries {
+                        let entry_pointer = format!("{pointer}{}", json_pointer(&[key]));
+                        self.validate_field_type(values, entry, &entry_pointer, errors);
+                    }
+                }
+                _ => errors.push(ValidationError::new(pointer, "expected object")),
+            },
+            FieldType::Named(name) => {
+                if let Some(message) = self.messages.get(name) {
+                    self.validate_message(message, value, pointer, errors);
+                } else if let Some(enumeration) = self.enums.get(name) {
+                    let valid = match value {
+                        Value::String(symbol) => {
+                            enumeration.values.iter().any(|(name, _)| name == symbol)
+                        }
+                        Value::Number(number) => number.as_i64().is_some(),
+                        _ => false,
+                    };
+                    if !valid {
+                        errors.push(ValidationError::new(
+                            pointer,
+                            format!("expected a value of enum '{name}'"),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// `schema` also accepting `null`.
+fn nullable(mut schema: Value) -> Value {
+    match schema.get_mut("type") {
+        Some(type_name @ Value::String(_)) => {
+            *type_name = json!([type_name.take(), "null"]);
+            schema
+        }
+        _ => json!({ "anyOf": [schema, { "type": "null" }] }),
+    }
+}
+
+fn scalar_to_json_schema(scalar: ScalarType) -> Value {
+    match scalar {
+        ScalarType::Double => json!({ "type": "number", "format": "double" }),
+        ScalarType::Float => json!({ "type": "number", "format": "float" }),
+        ScalarType::Int32 | ScalarType::Sint32 | ScalarType::Sfixed32 => json!({
+            "type": "integer",
+            "format": "int32",
+            "minimum": i32::MIN,
+            "maximum": i32::MAX,
+        }),
+        ScalarType::Uint32 | ScalarType::Fixed32 => json!({
+            "type": "integer",
+            "format": "uint32",
+            "minimum": 0,
+            "maximum": u32::MAX,
+        }),
+        // 64 bit integers are encoded as strings, but numbers are accepted as well
+        ScalarType::Int64 | ScalarType::Sint64 | ScalarType::Sfixed64 => {
+            json!({ "type": ["integer", "string"], "format": "int64" })
+        }
+        ScalarType::Uint64 | ScalarType::Fixed64 => {
+            json!({ "type": ["integer", "string"], "format": "uint64" })
+        }
+        ScalarType::Bool => json!({ "type": "boolean" }),
+        ScalarType::String => json!({ "type": "string" }),
+        ScalarType::Bytes => json!({ "type": "string", "contentEncoding": "base64" }),
+    }
+}
+
+/// Returns the expected type description when `value` is not a valid encoding of `scalar`.
+fn validate_scalar(scalar: ScalarType, value: &Value) -> Option<&'static str> {
+    let is_integer = |value: &Value, signed: bool| match value {
+        Value::Number(number) => number.is_u64() || (signed && number.is_i64()),
+        Value::String(text) if signed => text.parse::<i64>().is_ok(),
+        Value::String(text) => text.parse::<u64>().is_ok(),
+        _ => false,
+    };
+    let valid = match scalar {
+        ScalarType::Double | ScalarType::Float => {
+            value.is_number() || matches!(value.as_str(), Some("NaN" | "Infinity" | "-Infinity"))
+        }
+        ScalarType::Int32 | ScalarType::Sint32 | ScalarType::Sfixed32 => {
+            value.as_i64().is_some_and(|int| i32::try_from(int).is_ok())
+        }
+        ScalarType::Uint32 | ScalarType::Fixed32 => {
+            value.as_u64().is_some_and(|int| u32::try_from(int).is_ok())
+        }
+        ScalarType::Int64 | ScalarType::Sint64 | ScalarType::Sfixed64 => is_integer(value, true),
+        ScalarType::Uint64 | ScalarType::Fixed64 => is_integer(value, false),
+        ScalarType::Bool => value.is_boolean(),
+        ScalarType::String | ScalarType::Bytes => value.is_string(),
+    };
+    if valid {
+        return None;
+    }
+    Some(match scalar {
+        ScalarType::Double | ScalarType::Float => "number",
+        ScalarType::Int32 | ScalarType::Sint32 | ScalarType::Sfixed32 => "32-bit integer",
+        ScalarType::Uint32 | ScalarType::Fixed32 => "unsigned 32-bit integer",
+        ScalarType::Int64 | ScalarType::Sint64 | ScalarType::Sfixed64 => "64-bit integer",
+        ScalarType::Uint64 | ScalarType::Fixed64 => "unsigned 64-bit integer",
+        ScalarType::Bool => "boolean",
+        ScalarType::String | ScalarType::Bytes => "string",
+    })
+}
+
+/// JSON schema of the well-known types, which have a special JSON mapping.
+fn well_known_type(name: &str) -> Option<Value> {
+    let wrapped = |scalar| {
+        let mut schema = scalar_to_json_schema(scalar);
+        if let Some(object) = schema.as_object_mut() {
+            let nullable = match object.shift_remove("type") {
+                Some(Value::Array(mut types)) => {
+                    types.push(Value::from("null"));
+                    Value::Array(types)
+                }
+                Some(single) => json!([single, "null"]),
+                None => Value::Null,
+            };
+            object.insert(String::from("type"), nullable);
+        }
+        schema
+    };
+    Some(match name.strip_prefix("google.protobuf.")? {
+        "Timestamp" => json!({ "type": "string", "format": "date-time" }),
+        "Duration" => json!({ "type": "string", "pattern": "^-?[0-9]+(\\.[0-9]+)?s$" }),
+        "FieldMask" => json!({ "type": "string" }),
+        "Struct" => json!({ "type": "object" }),
+        "ListValue" => json!({ "type": "array" }),
+        "Value" => json!({}),
+        "NullValue" => json!({ "type": "null" }),
+        "Empty" => json!({ "type": "object", "additionalProperties": false }),
+        "Any" => json!({
+            "type": "object",
+            "properties": { "@type": { "type": "string" } },
+            "required": ["@type"],
+        }),
+        "DoubleValue" => wrapped(ScalarType::Double),
+        "FloatValue" => wrapped(ScalarType::Float),
+        "Int64Value" => wrapped(ScalarType::Int64),
+        "UInt64Value" => wrapped(ScalarType::Uint64),
+        "Int32Value" => wrapped(ScalarType::Int32),
+        "UInt32Value" => wrapped(ScalarType::Uint32),
+        "BoolValue" => wrapped(ScalarType::Bool),
+        "StringValue" => wrapped(ScalarType::String),
+        "BytesValue" => wrapped(ScalarType::Bytes),
+        _ => return None,
+    })
+}
+
+/// Default JSON name of a field: `lowerCamelCase` version of its name.
+fn json_name(name: &str) -> String {
+    let mut json_name = String::with_capacity(name.len());
+    let mut uppercase_next = false;
+    for c in name.chars() {
+        if c == '_' {
+            uppercase_next = true;
+        } else if uppercase_next {
+            json_name.extend(c.to_uppercase());
+            uppercase_next = false;
+        } else {
+            json_name.push(c);
+        }
+    }
+    json_name
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Identifier(String),
+    Integer(i64),
+    Float,
+    Text(String),
+    Symbol(char),
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, Error> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '/' {
+            chars.next();
+            match chars.next() {
+                Some('/') => {
+                    for c in chars.by_ref() {
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                }
+                Some('*') => {
+                    let mut previous = ' ';
+                    loop {
+                        match chars.next() {
+                            Some('/') if previous == '*' => break,
+                            Some(c) => previous = c,
+                            None => return Err(invalid("unterminated comment")),
+                        }
+                    }
+                }
+                _ => return Err(invalid("unexpected '/'")),
+            }
+        } else if c == '"' || c == '\'' {
+            chars.next();
+            let mut text = String::new();
+            loop {
+                match chars.next() {
+                    Some('\\') => text.extend(chars.next()),
+                    Some(end) if end == c => break,
+                    Some(other) => text.push(other),
+                    None => return Err(invalid("unterminated string literal")),
+                }
+            }
+            tokens.push(Token::Text(text));
+        } else if c.is_ascii_alphabetic() || c == '_' || c == '.' {
+            let mut identifier = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_ascii_alphanumeric() || c == '_' || c == '.' {
+                    identifier.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            tokens.push(Token::Identifier(identifier));
+        } else if c.is_ascii_digit() || c == '-' || c == '+' {
+            let mut number = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '+' {
+                    number.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            tokens.push(parse_number(&number)?);
+        } else {
+            tokens.push(Token::Symbol(c));
+            chars.next();
+        }
+    }
+    Ok(tokens)
+}
+
+fn parse_number(number: &str) -> Result<Token, Error> {
+    let (negative, digits) = match number.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, number.trim_start_matches('+')),
+    };
+    let parsed = if let Some(hex) = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        i64::from_str_radix(hex, 16).ok()
+    } else if digits.len() > 1
+        && digits.starts_with('0')
+        && digits.chars().all(|c| c.is_ascii_digit())
+    {
+        i64::from_str_radix(&digits[1..], 8).ok()
+    } else {
+        digits.parse::<i64>().ok()
+    };
+    match parsed {
+        Some(value) => Ok(Token::Integer(if negative { -value } else { value })),
+        None if number.parse::<f64>().is_ok() || digits == "inf" || digits == "nan" => {
+            Ok(Token::Float)
+        }
+        None => Err(invalid(format!("invalid number '{number}'"))),
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    package: Option<String>,
+    messages: BTreeMap<String, ProtoMessage>,
+    enums: BTreeMap<String, ProtoEnum>,
+    top_level: Vec<String>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Result<Token, Error> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .cloned()
+            .ok_or_else(|| invalid("unexpected end of file"))?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn identifier(&mut self) -> Result<String, Error> {
+        match self.next()? {
+            Token::Identifier(identifier) => Ok(identifier),
+            other => Err(invalid(format!("expected identifier, found {other:?}"))),
+        }
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<(), Error> {
+        match self.next()? {
+            Token::Symbol(found) if found == symbol => Ok(()),
+            other => Err(invalid(format!("expected '{symbol}', found {other:?}"))),
+        }
+    }
+
+    fn eat(&mut self, symbol: char) -> bool {
+        if self.peek() == Some(&Token::Symbol(symbol)) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Skips tokens up to and including the next `;` at the current nesting level.
+    fn skip_statement(&mut self) -> Result<(), Error> {
+        let mut depth = 0usize;
+        loop {
+            match self.next()? {
+                Token::Symbol(';') if depth == 0 => return Ok(()),
+                Token::Symbol('[' | '(' | '{') => depth += 1,
+                Token::Symbol(']' | ')' | '}') => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+    }
+
+    /// Skips a `{ ... }` block, the opening brace must be the next token.
+    fn skip_block(&mut self) -> Result<(), Error> {
+        self.expect('{')?;
+        let mut depth = 1usize;
+        while depth > 0 {
+            match self.next()? {
+                Token::Symbol('{') => depth += 1,
+                Token::Symbol('}') => depth -= 1,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn qualify(&self, scope: Option<&str>, name: &str) -> String {
+        match scope.or(self.package.as_deref()) {
+            Some(scope) => format!("{scope}.{name}"),
+            None => String::from(name),
+        }
+    }
+
+    fn parse_file(&mut self) -> Result<(), Error> {
+        while let Some(token) = self.peek().cloned() {
+            match token {
+                Token::Symbol(';') => self.position += 1,
+                Token::Identifier(keyword) => match keyword.as_str() {
+                    "syntax" | "edition" => {
+                        self.position += 1;
+                        self.expect('=')?;
+                        match self.next()? {
+                            Token::Text(syntax) if syntax == "proto2" || syntax == "proto3" => {}
+                            Token::Text(syntax) if keyword == "edition" => {
+                                return Err(invalid(format!("unsupported edition '{syntax}'")))
+                            }
+                            other => return Err(invalid(format!("unsupported syntax {other:?}"))),
+                        }
+                        self.expect(';')?;
+                    }
+                    "package" => {
+                        self.position += 1;
+                        self.package = Some(self.identifier()?);
+                        self.expect(';')?;
+                    }
+                    "import" | "option" => self.skip_statement()?,
+                    "message" => {
+                        self.position += 1;
+                        let name = self.parse_message(None)?;
+                        self.top_level.push(name);
+                    }
+                    "enum" => {
+                        self.position += 1;
+                        self.parse_enum(None)?;
+                    }
+                    "service" | "extend" => {
+                        self.position += 1;
+                        self.identifier()?;
+                        self.skip_block()?;
+                    }
+                    other => return Err(invalid(format!("unexpected '{other}'"))),
+                },
+                other => return Err(invalid(format!("unexpected {other:?}"))),
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_message(&mut self, scope: Option<&str>) -> Result<String, Error> {
+        let name = self.identifier()?;
+        let name = self.qualify(scope, &name);
+        self.expect('{')?;
+        let mut fields = Vec::new();
+        while !self.eat('}') {
+            self.parse_message_element(&name, &mut fields, None)?;
+        }
+        self.messages.insert(
+            name.clone(),
+            ProtoMessage {
+                name: name.clone(),
+                fields,
+            },
+        );
+        Ok(name)
+    }
+
+    fn parse_message_element(
+        &mut self,
+        message: &str,
+        fields: &mut Vec<ProtoField>,
+        oneof: Option<&str>,
+    ) -> Result<(), Error> {
+        let keyword = match self.peek() {
+            Some(Token::Symbol(';')) => {
+                self.position += 1;
+                return Ok(());
+            }
+            Some(Token::Identifier(keyword)) => keyword.clone(),
+            other => return Err(invalid(format!("unexpected {other:?} in '{message}'"))),
+        };
+        match keyword.as_str() {
+            "message" if oneof.is_none() => {
+                self.position += 1;
+                self.parse_message(Some(message))?;
+            }
+            "enum" if oneof.is_none() => {
+                self.position += 1;
+                self.parse_enum(Some(message))?;
+            }
+            "oneof" if oneof.is_none() => {
+                self.position += 1;
+                let name = self.identifier()?;
+                self.expect('{')?;
+                while !self.eat('}') {
+                    self.parse_message_element(message, fields, Some(&name))?;
+                }
+            }
+            "option" | "reserved" | "extensions" => self.skip_statement()?,
+            "extend" => {
+                self.position += 1;
+                self.identifier()?;
+                self.skip_block()?;
+            }
+            "group" => return Err(invalid("groups are not supported")),
+            _ => fields.push(self.parse_field(oneof)?),
+        }
+        Ok(())
+    }
+
+    fn parse_field(&mut self, oneof: Option<&str>) -> Result<ProtoField, Error> {
+        let mut type_name = self.identifier()?;
+        let label = match type_name.as_str() {
+            "repeated" => FieldLabel::Repeated,
+            "optional" => FieldLabel::Optional,
+            "required" => FieldLabel::Required,
+            _ => FieldLabel::Singular,
+        };
+        if label != FieldLabel::Singular {
+            type_name = self.identifier()?;
+        }
+
+        let field_type = if type_name == "map" && self.eat('<') {
+            let key = self.identifier()?;
+            let key = ScalarType::parse(&key)
+                .ok_or_else(|| invalid(format!("invalid map key type '{key}'")))?;
+            self.expect(',')?;
+            let value = self.identifier()?;
+            self.expect('>')?;
+            FieldType::Map {
+                key,
+                value: Box::new(
+                    ScalarType::parse(&value)
+                        .map(FieldType::Scalar)
+                        .unwrap_or(FieldType::Named(value)),
+                ),
+            }
+        } else {
+            ScalarType::parse(&type_name)
+                .map(FieldType::Scalar)
+                .unwrap_or(FieldType::Named(type_name))
+        };
+
+        let name = self.identifier()?;
+        self.expect('=')?;
+        let number = match self.next()? {
+            Token::Integer(number) if number > 0 => number as u32,
+            other => return Err(invalid(format!("invalid number {other:?} for '{name}'"))),
+        };
+        let mut custom_json_name = None;
+        if self.eat('[') {
+            loop {
+                let option = match self.next()? {
+                    Token::Identifier(option) => option,
+                    Token::Symbol('(') => {
+                        self.identifier()?;
+                        self.expect(')')?;
+                        String::new()
+                    }
+                    other => return Err(invalid(format!("invalid field option {other:?}"))),
+                };
+                self.expect('=')?;
+                let value = self.next()?;
+                if let (true, Token::Text(value)) = (option == "json_name", &value) {
+                    custom_json_name = Some(value.clone());
+                }
+                if let Token::Symbol('{') = value {
+                    // aggregate option value
+                    self.position -= 1;
+                    self.skip_block()?;
+                }
+                if self.eat(']') {
+                    break;
+                }
+                self.expect(',')?;
+            }
+        }
+        self.expect(';')?;
+
+        Ok(ProtoField {
+            json_name: custom_json_name.unwrap_or_else(|| json_name(&name)),
+            name,
+            number,
+            label,
+            field_type,
+            oneof: oneof.map(String::from),
+        })
+    }
+
+    fn parse_enum(&mut self, scope: Option<&str>) -> Result<(), Error> {
+        let name = self.identifier()?;
+        let name = self.qualify(scope, &name);
+        self.expect('{')?;
+        let mut values = Vec::new();
+        while !self.eat('}') {
+            match self.next()? {
+                Token::Symbol(';') => {}
+                Token::Identifier(keyword) if keyword == "option" || keyword == "reserved" => {
+                    self.skip_statement()?
+                }
+                Token::Identifier(value) => {
+                    self.expect('=')?;
+                    let number = match self.next()? {
+                        Token::Integer(number) => number as i32,
+                        other => return Err(invalid(format!("invalid enum value {other:?}"))),
+                    };
+                    if self.peek() == Some(&Token::Symbol('[')) {
+                        self.skip_statement()?;
+                    } else {
+                        self.expect(';')?;
+                    }
+                    values.push((value, number));
+                }
+                other => return Err(invalid(format!("unexpected {other:?} in enum '{name}'"))),
+            }
+        }
+        self.enums.insert(name.clone(), ProtoEnum { name, values });
+        Ok(())
+    }
+}
+
+fn invalid(reason: impl ToString) -> Error {
+    Error::InvalidSchema {
+        format: FORMAT,
+        reason: reason.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ORDERS: &str = r#"
+        syntax = "proto3";
+        package acme.orders.v1;
+
+        import "google/protobuf/timestamp.proto";
+
+        /* An order placed by a customer */
+        message OrderPlaced {
+          string order_id = 1;
+          repeated Line lines = 2;
+          Status status = 3;
+          google.protobuf.Timestamp placed_at = 4;
+          map<string, string> labels = 5 [json_name = "tags"];
+          oneof payment {
+            Card card = 6;
+            string voucher_code = 7;
+          }
+          int64 total_cents = 8;
+
+          message Line {
+            string sku = 1;
+            uint32 quantity = 2;
+            Line replaced_by = 3;
+          }
+        }
+
+        message Card {
+          string last_digits = 1;
+        }
+
+        enum Status {
+          STATUS_UNSPECIFIED = 0;
+          STATUS_PENDING = 1 [deprecated = true];
+          STATUS_CONFIRMED = 2;
+        }
+
+        service Orders {
+          rpc Place (OrderPlaced) returns (OrderPlaced);
+        }
+    "#;
+
+    #[test]
+    fn parses_and_resolves_types() {
+        let schema = ProtobufSchema::parse(ORDERS).unwrap();
+        assert_eq!(schema.package(), Some("acme.orders.v1"));
+
+        let order = schema.target_message(None).unwrap();
+        assert_eq!(order.name, "acme.orders.v1.OrderPlaced");
+        let lines = order.fields.iter().find(|f| f.name == "lines").unwrap();
+        assert_eq!(
+            lines.field_type,
+            FieldType::Named(String::from("acme.orders.v1.OrderPlaced.Line"))
+        );
+        let labels = order.fields.iter().find(|f| f.name == "labels").unwrap();
+        assert_eq!(labels.json_name, "tags");
+        let card = order.fields.iter().find(|f| f.name == "card").unwrap();
+        assert_eq!(card.oneof.as_deref(), Some("payment"));
+
+        assert!(schema.message("Card").is_some());
+        assert!(matches!(
+            ProtobufSchema::parse("syntax = \"proto3\"; message A { Missing b = 1; }"),
+            Err(Error::UnresolvedType { name }) if name == "Missing"
+        ));
+    }
+
+    #[test]
+    fn converts_to_json_schema() {
+        let schema = ProtobufSchema::parse(ORDERS).unwrap();
+        let json_schema = schema.to_json_schema(None).unwrap();
+        let json_schema = json_schema.as_value();
+        assert_eq!(
+            json_schema["$ref"],
+            json!("#/$defs/acme.orders.v1.OrderPlaced")
+        );
+
+        let order = &json_schema["$defs"]["acme.orders.v1.OrderPlaced"];
+        assert_eq!(
+            order["properties"]["orderId"],
+            json!({ "type": ["string", "null"] })
+        );
+        assert_eq!(
+            order["properties"]["placedAt"]["format"],
+            json!("date-time")
+        );
+        assert_eq!(
+            order["properties"]["lines"]["items"]["$ref"],
+            json!("#/$defs/acme.orders.v1.OrderPlaced.Line")
+        );
+        assert!(order["properties"]["tags"]["additionalProperties"].is_object());
+        assert_eq!(
+            json_schema["$defs"]["acme.orders.v1.Status"]["anyOf"][0]["enum"],
+            json!(["STATUS_UNSPECIFIED", "STATUS_PENDING", "STATUS_CONFIRMED"])
+        );
+
+        let card = schema.to_json_schema(Some("acme.orders.v1.Card")).unwrap();
+        assert_eq!(
+            card.as_value()["$ref"],
+            json!("#/$defs/acme.orders.v1.Card")
+        );
+    }
+
+    #[test]
+    fn validates_json_mapping() {
+        let schema = ProtobufSchema::parse(ORDERS).unwrap();
+        let valid = json!({
+            "orderId": "o-1",
+            "lines": [{ "sku": "A-1", "quantity": 2, "replacedBy": { "sku": "A-2" } }],
+            "status": "STATUS_CONFIRMED",
+            "placedAt": "2024-01-27T10:00:00Z",
+            "tags": { "channel": "web" },
+            "voucherCode": "WELCOME",
+            "totalCents": "1250"
+        });
+        assert_eq!(schema.validate(None, &valid).unwrap(), vec![]);
+        let json_schema = schema.to_json_schema(None).unwrap();
+        assert_eq!(
+            crate::schema_format::json_schema::validate(json_schema.as_value(), &valid).unwrap(),
+            vec![]
+        );
+
+        // enum numbers and `null` fields are accepted by both
+        let defaults = json!({
+            "orderId": null,
+            "lines": null,
+            "status": 2,
+            "card": null,
+            "voucherCode": "WELCOME"
+        });
+        assert_eq!(schema.validate(None, &defaults).unwrap(), vec![]);
+        assert_eq!(
+            crate::schema_format::json_schema::validate(json_schema.as_value(), &defaults).unwrap(),
+            vec![]
+        );
+
+        let invalid = json!({
+            "lines": [{ "quantity": -1 }],
+            "status": "UNKNOWN",
+            "card": { "lastDigits": "1234" },
+            "voucherCode": "WELCOME",
+            "total_cents": "1250",
+            "extra": 1
+        });
+        let pointers = schema
+            .validate(None, &invalid)
+            .unwrap()
+            .into_iter()
+            .map(|error| error.pointer)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            pointers,
+            vec![
+                "/lines/0/quantity",
+                "/status",
+                "/voucherCode",
+                "/total_cents",
+                "/extra"
+            ]
+        );
+    }
+}
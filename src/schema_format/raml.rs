@@ -0,0 +1,332 @@
+//! Conversion of [RAML 1.0 data types](https://github.com/raml-org/raml-spec/blob/master/versions/raml-10/raml-10.md#raml-data-types),
+//! declared with `schemaFormat: application/raml+yaml;version=1.0`, to JSON schema.
+use crate::schema_format::Error;
+use serde_json::{json, Map, Value};
+
+/// RAML media type, without version and structured syntax suffix.
+pub const MEDIA_TYPE: &str = "application/raml";
+
+/// Facets copied as they are, since they have the same meaning in JSON schema.
+const SHARED_FACETS: [&str; 14] = [
+    "description",
+    "default",
+    "enum",
+    "pattern",
+    "minLength",
+    "maxLength",
+    "minimum",
+    "maximum",
+    "multipleOf",
+    "minItems",
+    "maxItems",
+    "uniqueItems",
+    "minProperties",
+    "maxProperties",
+];
+
+/// Converts a RAML type declaration to JSON schema. Types referenced by name are expected to be
+/// defined in the schemas of the document components (`#/components/schemas/<name>`).
+pub fn to_json_schema(declaration: &Value) -> Result<schemars::Schema, Error> {
+    match convert_declaration(declaration)? {
+        Value::Object(object) => Ok(schemars::Schema::from(object)),
+        other => Err(invalid(format!("unexpected conversion result {other}"))),
+    }
+}
+
+fn convert_declaration(declaration: &Value) -> Result<Value, Error> {
+    let object = match declaration {
+        Value::String(expression) => return convert_expression(expression),
+        Value::Object(object) => object,
+        Value::Null => return Ok(json!({ "type": "string" })),
+        other => return Err(invalid(format!("invalid type declaration {other}"))),
+    };
+
+    let mut schema = match object.get("type").or_else(|| object.get("schema")) {
+        Some(Value::String(expression)) => convert_expression(expression)?,
+        // multiple inheritance
+        Some(Value::Array(parents)) => json!({
+            "allOf": parents
+                .iter()
+                .map(convert_declaration)
+                .collect::<Result<Vec<_>, _>>()?,
+        }),
+        Some(inline @ Value::Object(_)) => convert_declaration(inline)?,
+        Some(other) => return Err(invalid(format!("invalid type {other}"))),
+        None if object.contains_key("properties") => json!({ "type": "object" }),
+        None if object.contains_key("items") => json!({ "type": "array" }),
+        None => json!({ "type": "string" }),
+    };
+    let Value::Object(schema_object) = &mut schema else {
+        return Ok(schema);
+    };
+
+    // refinements of a named type must be combined with the referenced schema
+    if schema_object.contains_key("$ref") {
+        schema = json!({ "allOf": [schema] });
+    }
+    let Value::Object(schema_object) = &mut schema else {
+        return Ok(schema);
+    };
+
+    for facet in SHARED_FACETS {
+        if let Some(value) = object.get(facet) {
+            schema_object.insert(String::from(facet), value.clone());
+        }
+    }
+    if let Some(display_name) = object.get("displayName") {
+        schema_object.insert(String::from("title"), display_name.clone());
+    }
+    if let Some(format) = object.get("format").and_then(Value::as_str) {
+        schema_object.insert(String::from("format"), Value::from(convert_format(format)));
+    }
+    if let Some(additional) = object.get("additionalProperties") {
+        schema_object.insert(String::from("additionalProperties"), additional.clone());
+    }
+    if let Some(example) = object.get("example") {
+        schema_object.insert(String::from("examples"), json!([example_value(example)]));
+    }
+    if let Some(Value::Object(examples)) = object.get("examples") {
+        let values = examples.values().map(example_value).collect::<Vec<_>>();
+        schema_object.insert(String::from("examples"), Value::Array(values));
+    }
+    if let Some(items) = object.get("items") {
+        schema_object.insert(String::from("items"), convert_declaration(items)?);
+    }
+
+    if let Some(Value::Object(properties)) = object.get("properties") {
+        let mut converted = Map::new();
+        let mut patterns = Map::new();
+        let mut required = Vec::new();
+        for (key, property) in properties {
+            // `/regex/` declares a pattern property, `//` matches any name
+            if let Some(pattern) = key
+                .strip_prefix('/')
+                .and_then(|pattern| pattern.strip_suffix('/'))
+            {
+                let pattern = if pattern.is_empty() { ".*" } else { pattern };
+                patterns.insert(String::from(pattern), convert_declaration(property)?);
+                continue;
+            }
+            // `name?` declares an optional property, unless `required` is explicit
+            let (name, optional_marker) = match key.strip_suffix('?') {
+                Some(name) => (name, true),
+                None => (key.as_str(), false),
+            };
+            let is_required = property
+                .get("required")
+                .and_then(Value::as_bool)
+                .unwrap_or(!optional_marker);
+            if is_required {
+                required.push(Value::from(name));
+            }
+            converted.insert(String::from(name), convert_declaration(property)?);
+        }
+        schema_object.insert(String::from("properties"), Value::Object(converted));
+        if !patterns.is_empty() {
+            schema_object.insert(String::from("patternProperties"), Value::Object(patterns));
+        }
+        if !required.is_empty() {
+            schema_object.insert(String::from("required"), Value::Array(required));
+        }
+    }
+
+    if let (Some(Value::String(discriminator)), Some(value)) = (
+        object.get("discriminator"),
+        object.get("discriminatorValue"),
+    ) {
+        let properties = schema_object
+            .entry("properties")
+            .or_insert_with(|| json!({}));
+        if let Value::Object(properties) = properties {
+            properties.insert(discriminator.clone(), json!({ "const": value }));
+        }
+    }
+
+    Ok(schema)
+}
+
+/// Converts a type expression such as `string`, `Person[]`, `string | nil` or `(A | B)[]`.
+fn convert_expression(expression: &str) -> Result<Value, Error> {
+    let expression = expression.trim();
+    if expression.is_empty() {
+        return Err(invalid("empty type expression"));
+    }
+    if expression.starts_with('{') || expression.starts_with('<') {
+        return Err(invalid("inline JSON or XML schemas are not supported"));
+    }
+
+    let alternatives = split_top_level(expression, '|');
+    if alternatives.len() > 1 {
+        return Ok(json!({
+            "anyOf": alternatives
+                .into_iter()
+                .map(convert_expression)
+                .collect::<Result<Vec<_>, _>>()?,
+        }));
+    }
+    if let Some(items) = expression.strip_suffix("[]") {
+        return Ok(json!({ "type": "array", "items": convert_expression(items)? }));
+    }
+    if let Some(inner) = expression
+        .strip_prefix('(')
+        .and_then(|inner| inner.strip_suffix(')'))
+    {
+        return convert_expression(inner);
+    }
+    if let Some(nullable) = expression.strip_suffix('?') {
+        return Ok(json!({ "anyOf": [convert_expression(nullable)?, { "type": "null" }] }));
+    }
+
+    Ok(match expression {
+        "string" => json!({ "type": "string" }),
+        "number" => json!({ "type": "number" }),
+        "integer" => json!({ "type": "integer" }),
+        "boolean" => json!({ "type": "boolean" }),
+        "object" => json!({ "type": "object" }),
+        "array" => json!({ "type": "array" }),
+        "nil" => json!({ "type": "null" }),
+        "any" => json!({}),
+        "date-only" => json!({ "type": "string", "format": "date" }),
+        "time-only" => json!({ "type": "string", "format": "time" }),
+        "datetime-only" => json!({
+            "type": "string",
+            "pattern": "^\\d{4}-\\d{2}-\\d{2}T\\d{2}:\\d{2}:\\d{2}(\\.\\d+)?$",
+        }),
+        "datetime" => json!({ "type": "string", "format": "date-time" }),
+        "file" => json!({ "type": "string", "contentEncoding": "base64" }),
+        name => json!({ "$ref": format!("#/components/schemas/{name}") }),
+    })
+}
+
+/// Splits `expression` on `separator` occurrences that are not enclosed in parentheses.
+fn split_top_level(expression: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (idx, c) in expression.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            c if c == separator && depth == 0 => {
+                parts.push(&expression[start..idx]);
+                start = idx + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&expression[start..]);
+    parts
+}
+
+fn convert_format(format: &str) -> &str {
+    match format {
+        "int8" | "int16" | "int32" => "int32",
+        "int" | "int64" | "long" => "int64",
+        "rfc3339" => "date-time",
+        other => other,
+    }
+}
+
+/// Examples can be declared as values or as objects with a `value` facet.
+fn example_value(example: &Value) -> Value {
+    let is_facet = |key: &String| {
+        matches!(
+            key.as_str(),
+            "value" | "displayName" | "description" | "strict"
+        ) || key.starts_with('(')
+    };
+    match example {
+        Value::Object(object) if object.contains_key("value") && object.keys().all(is_facet) => {
+            object["value"].clone()
+        }
+        _ => example.clone(),
+    }
+}
+
+fn invalid(reason: impl ToString) -> Error {
+    Error::InvalidSchema {
+        format: "raml",
+        reason: reason.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn converts_raml_types() {
+        let declaration = json!({
+            "type": "object",
+            "displayName": "User",
+            "properties": {
+                "id": { "type": "integer", "format": "int64", "minimum": 1 },
+                "email": "string",
+                "nickname?": "string | nil",
+                "roles": "Role[]",
+                "birthday": { "type": "date-only", "required": false },
+                "address": {
+                    "type": "Address",
+                    "example": { "value": { "city": "Milan" } }
+                }
+            }
+        });
+
+        let schema = to_json_schema(&declaration).unwrap();
+        let schema = schema.as_value();
+        assert_eq!(schema["title"], json!("User"));
+        assert_eq!(
+            schema["required"],
+            json!(["id", "email", "roles", "address"])
+        );
+        assert_eq!(
+            schema["properties"]["id"],
+            json!({ "type": "integer", "format": "int64", "minimum": 1 })
+        );
+        assert_eq!(
+            schema["properties"]["nickname"],
+            json!({ "anyOf": [{ "type": "string" }, { "type": "null" }] })
+        );
+        assert_eq!(
+            schema["properties"]["roles"]["items"]["$ref"],
+            json!("#/components/schemas/Role")
+        );
+        assert_eq!(schema["properties"]["birthday"]["format"], json!("date"));
+        assert_eq!(
+            schema["properties"]["address"]["allOf"][0]["$ref"],
+            json!("#/components/schemas/Address")
+        );
+        assert_eq!(
+            schema["properties"]["address"]["examples"],
+            json!([{ "city": "Milan" }])
+        );
+    }
+
+    #[test]
+    fn converts_pattern_properties_and_property_counts() {
+        let declaration = json!({
+            "type": "object",
+            "minProperties": 1,
+            "maxProperties": 3,
+            "properties": {
+                "name": "string",
+                "/^note\\d+$/": "string",
+                "//": "integer"
+            }
+        });
+
+        let schema = to_json_schema(&declaration).unwrap();
+        let schema = schema.as_value();
+        assert_eq!(
+            schema["properties"],
+            json!({ "name": { "type": "string" } })
+        );
+        assert_eq!(
+            schema["patternProperties"],
+            json!({ "^note\\d+$": { "type": "string" }, ".*": { "type": "integer" } })
+        );
+        assert_eq!(schema["required"], json!(["name"]));
+        assert_eq!(schema["minProperties"], json!(1));
+        assert_eq!(schema["maxProperties"], json!(3));
+    }
+}
@@ -0,0 +1,430 @@
+//! Pluggable registry mapping `schemaFormat` media types to the handlers able to interpret them.
+use crate::example::ExampleGenerator;
+use crate::schema_format::avro::{self, AvroSchema};
+use crate::schema_format::protobuf::{self, ProtobufSchema};
+use crate::schema_format::{json_schema, openapi, raml, Error, SchemaFormat, ValidationError};
+use crate::spec::message::MultiFormatSchema;
+use serde_json::Value;
+
+/// Interprets the multi format schemas of one or more media types.
+pub trait SchemaFormatHandler: Send + Sync {
+    /// Media types handled, without parameters (e.g. `application/vnd.apache.avro`). Structured
+    /// syntax suffixes (`+json`, `+yaml`) are ignored when matching.
+    fn media_types(&self) -> &[&str];
+
+    /// Whether the `version` parameter of the schema format is supported. `None` means the
+    /// parameter is missing.
+    fn supports_version(&self, _version: Option<&str>) -> bool {
+        true
+    }
+
+    /// Converts the schema to JSON schema.
+    fn to_json_schema(&self, schema: &MultiFormatSchema) -> Result<schemars::Schema, Error>;
+
+    /// Validates a payload against the schema.
+    fn validate(
+        &self,
+        schema: &MultiFormatSchema,
+        payload: &Value,
+    ) -> Result<Vec<ValidationError>, Error> {
+        let json_schema = self.to_json_schema(schema)?;
+        json_schema::validate(json_schema.as_value(), payload)
+    }
+
+    /// Generates an example payload, deterministic for a given `seed`.
+    fn generate_example(&self, schema: &MultiFormatSchema, seed: u64) -> Result<Value, Error> {
+        let json_schema = self.to_json_schema(schema)?;
+        Ok(ExampleGenerator::new(seed).generate(&json_schema))
+    }
+
+    /// Converts the type named `target` to JSON schema, for formats declaring several types in a
+    /// single schema (e.g. Protobuf messages). Formats describing a single type don't support
+    /// any target.
+    fn to_json_schema_for(
+        &self,
+        schema: &MultiFormatSchema,
+        target: &str,
+    ) -> Result<schemars::Schema, Error> {
+        Err(Error::UnsupportedTarget {
+            schema_format: schema.schema_format.clone(),
+            target: String::from(target),
+        })
+    }
+
+    /// Validates a payload against the type named `target`, see [`Self::to_json_schema_for`].
+    fn validate_for(
+        &self,
+        schema: &MultiFormatSchema,
+        target: &str,
+        payload: &Value,
+    ) -> Result<Vec<ValidationError>, Error> {
+        let json_schema = self.to_json_schema_for(schema, target)?;
+        json_schema::validate(json_schema.as_value(), payload)
+    }
+
+    /// Generates an example payload of the type named `target`, see
+    /// [`Self::to_json_schema_for`].
+    fn generate_example_for(
+        &self,
+        schema: &MultiFormatSchema,
+        target: &str,
+        seed: u64,
+    ) -> Result<Value, Error> {
+        let json_schema = self.to_json_schema_for(schema, target)?;
+        Ok(ExampleGenerator::new(seed).generate(&json_schema))
+    }
+}
+
+/// Registry of [schema format handlers](SchemaFormatHandler). Handlers registered later take
+/// precedence over the ones registered before them for the same media type.
+pub struct SchemaFormatRegistry {
+    handlers: Vec<Box<dyn SchemaFormatHandler>>,
+}
+
+impl Default for SchemaFormatRegistry {
+    /// Registry with the handlers for the formats supported by this crate: AsyncAPI/JSON schema,
+    /// Avro, Protobuf, OpenAPI 3.0 and RAML 1.0.
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register(JsonSchemaHandler);
+        registry.register(AvroHandler);
+        registry.register(ProtobufHandler);
+        registry.register(OpenApiHandler);
+        registry.register(RamlHandler);
+        registry
+    }
+}
+
+impl SchemaFormatRegistry {
+    /// Registry without any handler.
+    pub fn empty() -> Self {
+        Self { handlers: vec![] }
+    }
+
+    pub fn register(&mut self, handler: impl SchemaFormatHandler + 'static) {
+        self.handlers.push(Box::new(handler));
+    }
+
+    /// Returns the handler for a `schemaFormat` value.
+    pub fn handler(&self, schema_format: &str) -> Result<&dyn SchemaFormatHandler, Error> {
+        let format = SchemaFormat::parse(schema_format);
+        let mut candidates = self
+            .handlers
+            .iter()
+            .rev()
+            .filter(|handler| {
+                handler
+                    .media_types()
+                    .iter()
+                    .any(|media_type| media_type.eq_ignore_ascii_case(format.base_media_type()))
+            })
+            .peekable();
+        if candidates.peek().is_none() {
+            return Err(Error::UnsupportedFormat {
+                schema_format: String::from(schema_format),
+            });
+        }
+        candidates
+            .find(|handler| handler.supports_version(format.version.as_deref()))
+            .map(|handler| handler.as_ref())
+            .ok_or_else(|| Error::UnsupportedVersion {
+                schema_format: String::from(schema_format),
+                version: format.version.clone().unwrap_or_default(),
+            })
+    }
+
+    pub fn to_json_schema(&self, schema: &MultiFormatSchema) -> Result<schemars::Schema, Error> {
+        self.handler(&schema.schema_format)?.to_json_schema(schema)
+    }
+
+    pub fn validate(
+        &self,
+        schema: &MultiFormatSchema,
+        payload: &Value,
+    ) -> Result<Vec<ValidationError>, Error> {
+        self.handler(&schema.schema_format)?
+            .validate(schema, payload)
+    }
+
+    pub fn generate_example(&self, schema: &MultiFormatSchema, seed: u64) -> Result<Value, Error> {
+        self.handler(&schema.schema_format)?
+            .generate_example(schema, seed)
+    }
+
+    pub fn to_json_schema_for(
+        &self,
+        schema: &MultiFormatSchema,
+        target: &str,
+    ) -> Result<schemars::Schema, Error> {
+        self.handler(&schema.schema_format)?
+            .to_json_schema_for(schema, target)
+    }
+
+    pub fn validate_for(
+        &self,
+        schema: &MultiFormatSchema,
+        target: &str,
+        payload: &Value,
+    ) -> Result<Vec<ValidationError>, Error> {
+        self.handler(&schema.schema_format)?
+            .validate_for(schema, target, payload)
+    }
+
+    pub fn generate_example_for(
+        &self,
+        schema: &MultiFormatSchema,
+        target: &str,
+        seed: u64,
+    ) -> Result<Value, Error> {
+        self.handler(&schema.schema_format)?
+            .generate_example_for(schema, target, seed)
+    }
+}
+
+/// Handler of the AsyncAPI schema and of JSON schema drafts.
+pub struct JsonSchemaHandler;
+
+impl SchemaFormatHandler for JsonSchemaHandler {
+    fn media_types(&self) -> &[&str] {
+        &["application/vnd.aai.asyncapi", "application/schema"]
+    }
+
+    fn to_json_schema(&self, schema: &MultiFormatSchema) -> Result<schemars::Schema, Error> {
+        schemars::Schema::try_from(schema.schema.clone()).map_err(|err| Error::InvalidSchema {
+            format: "json schema",
+            reason: err.to_string(),
+        })
+    }
+}
+
+/// Handler of Avro 1.x schemas.
+pub struct AvroHandler;
+
+impl SchemaFormatHandler for AvroHandler {
+    fn media_types(&self) -> &[&str] {
+        &[avro::MEDIA_TYPE]
+    }
+
+    fn supports_version(&self, version: Option<&str>) -> bool {
+        version.is_none_or(|version| version.starts_with("1."))
+    }
+
+    fn to_json_schema(&self, schema: &MultiFormatSchema) -> Result<schemars::Schema, Error> {
+        Ok(AvroSchema::parse(&schema.schema)?.to_json_schema())
+    }
+
+    fn validate(
+        &self,
+        schema: &MultiFormatSchema,
+        payload: &Value,
+    ) -> Result<Vec<ValidationError>, Error> {
+        Ok(AvroSchema::parse(&schema.schema)?.validate(payload))
+    }
+}
+
+/// Handler of proto2 and proto3 schemas. The payload is the first top level message, unless
+/// another message is selected as target.
+pub struct ProtobufHandler;
+
+impl SchemaFormatHandler for ProtobufHandler {
+    fn media_types(&self) -> &[&str] {
+        &[protobuf::MEDIA_TYPE]
+    }
+
+    fn supports_version(&self, version: Option<&str>) -> bool {
+        matches!(version, None | Some("2") | Some("3"))
+    }
+
+    fn to_json_schema(&self, schema: &MultiFormatSchema) -> Result<schemars::Schema, Error> {
+        ProtobufSchema::from_multi_format(schema)?.to_json_schema(None)
+    }
+
+    fn validate(
+        &self,
+        schema: &MultiFormatSchema,
+        payload: &Value,
+    ) -> Result<Vec<ValidationError>, Error> {
+        ProtobufSchema::from_multi_format(schema)?.validate(None, payload)
+    }
+
+    fn to_json_schema_for(
+        &self,
+        schema: &MultiFormatSchema,
+        target: &str,
+    ) -> Result<schemars::Schema, Error> {
+        ProtobufSchema::from_multi_format(schema)?.to_json_schema(Some(target))
+    }
+
+    fn validate_for(
+        &self,
+        schema: &MultiFormatSchema,
+        target: &str,
+        payload: &Value,
+    ) -> Result<Vec<ValidationError>, Error> {
+        ProtobufSchema::from_multi_format(schema)?.validate(Some(target), payload)
+    }
+}
+
+/// Handler of OpenAPI 3.0 schema objects.
+pub struct OpenApiHandler;
+
+impl SchemaFormatHandler for OpenApiHandler {
+    fn media_types(&self) -> &[&str] {
+        &[openapi::MEDIA_TYPE]
+    }
+
+    fn supports_version(&self, version: Option<&str>) -> bool {
+        version.is_none_or(|version| version.starts_with("3.0"))
+    }
+
+    fn to_json_schema(&self, schema: &MultiFormatSchema) -> Result<schemars::Schema, Error> {
+        openapi::to_json_schema(&schema.schema, openapi::Visibility::All)
+    }
+}
+
+/// Handler of RAML 1.0 data types.
+pub struct RamlHandler;
+
+impl SchemaFormatHandler for RamlHandler {
+    fn media_types(&self) -> &[&str] {
+        &[raml::MEDIA_TYPE]
+    }
+
+    fn supports_version(&self, version: Option<&str>) -> bool {
+        matches!(version, None | Some("1.0"))
+    }
+
+    fn to_json_schema(&self, schema: &MultiFormatSchema) -> Result<schemars::Schema, Error> {
+        raml::to_json_schema(&schema.schema)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    /// Example of an in-house format: JSON-LD documents described by a JSON schema with a
+    /// mandatory `@context`.
+    struct JsonLdHandler;
+
+    impl SchemaFormatHandler for JsonLdHandler {
+        fn media_types(&self) -> &[&str] {
+            &["application/vnd.acme.jsonld"]
+        }
+
+        fn to_json_schema(&self, schema: &MultiFormatSchema) -> Result<schemars::Schema, Error> {
+            Ok(schemars::Schema::try_from(json!({
+                "allOf": [schema.schema, { "required": ["@context"] }]
+            }))
+            .unwrap())
+        }
+    }
+
+    fn multi_format(schema_format: &str, schema: Value) -> MultiFormatSchema {
+        MultiFormatSchema {
+            schema_format: String::from(schema_format),
+            schema,
+        }
+    }
+
+    #[test]
+    fn dispatches_on_media_type_and_version() {
+        let registry = SchemaFormatRegistry::default();
+        let avro = multi_format(
+            "application/vnd.apache.avro+json;version=1.9.0",
+            json!({ "type": "record", "name": "Ping", "fields": [{ "name": "id", "type": "long" }] }),
+        );
+        assert_eq!(
+            registry.validate(&avro, &json!({ "id": 1 })).unwrap(),
+            vec![]
+        );
+        assert_eq!(
+            registry
+                .validate(&avro, &json!({ "id": "x" }))
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(registry.generate_example(&avro, 1).unwrap()["id"].is_i64());
+
+        let unsupported_version =
+            multi_format("application/vnd.apache.avro;version=2.0.0", json!("int"));
+        assert!(matches!(
+            registry.to_json_schema(&unsupported_version),
+            Err(Error::UnsupportedVersion { version, .. }) if version == "2.0.0"
+        ));
+
+        let unknown = multi_format("application/vnd.acme.jsonld;version=1", json!({}));
+        assert!(matches!(
+            registry.to_json_schema(&unknown),
+            Err(Error::UnsupportedFormat { .. })
+        ));
+    }
+
+    #[test]
+    fn selects_the_target_type() {
+        let registry = SchemaFormatRegistry::default();
+        let protobuf = multi_format(
+            "application/vnd.google.protobuf;version=3",
+            json!("syntax = \"proto3\"; message Ping { int32 seq = 1; } message Pong { string reply_to = 1; }"),
+        );
+        assert_eq!(
+            registry
+                .validate_for(&protobuf, "Pong", &json!({ "replyTo": "a" }))
+                .unwrap(),
+            vec![]
+        );
+        assert_eq!(
+            registry
+                .validate(&protobuf, &json!({ "replyTo": "a" }))
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            protobuf.to_json_schema_for("Pong").unwrap().as_value()["$ref"],
+            json!("#/$defs/Pong")
+        );
+        assert!(
+            registry.generate_example_for(&protobuf, "Pong", 1).unwrap()["replyTo"].is_string()
+        );
+        assert!(matches!(
+            registry.to_json_schema_for(&protobuf, "Missing"),
+            Err(Error::UnresolvedType { name }) if name == "Missing"
+        ));
+
+        let avro = multi_format("application/vnd.apache.avro;version=1.9.0", json!("int"));
+        assert!(matches!(
+            registry.to_json_schema_for(&avro, "Ping"),
+            Err(Error::UnsupportedTarget { target, .. }) if target == "Ping"
+        ));
+    }
+
+    #[test]
+    fn custom_handlers_can_be_plugged_in() {
+        let mut registry = SchemaFormatRegistry::default();
+        registry.register(JsonLdHandler);
+
+        let schema = multi_format(
+            "application/vnd.acme.jsonld;version=1",
+            json!({ "type": "object", "properties": { "name": { "type": "string" } } }),
+        );
+        assert_eq!(
+            registry
+                .validate(
+                    &schema,
+                    &json!({ "@context": "https://schema.org", "name": "x" })
+                )
+                .unwrap(),
+            vec![]
+        );
+        assert_eq!(
+            registry
+                .validate(&schema, &json!({ "name": "x" }))
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+}
@@ -54,6 +54,33 @@ impl ReferenceObject {
             reference: format!("#/channels/{channel_name}/messages/{message_name}"),
         }
     }
+
+    /// Creates a reference to a server.
+    pub fn new_server(server_name: &str) -> Self {
+        Self {
+            reference: format!("#/servers/{server_name}"),
+        }
+    }
+
+    /// Creates a reference to a security scheme.
+    pub fn new_security_scheme(scheme_name: &str) -> Self {
+        Self {
+            reference: format!("#/components/securitySchemes/{scheme_name}"),
+        }
+    }
+
+    /// Creates a reference to a parameter.
+    pub fn new_parameter(parameter_name: &str) -> Self {
+        Self::new_component("parameters", parameter_name)
+    }
+
+    /// Creates a reference to an object of the components, e.g. `new_component("correlationIds",
+    /// "requestId")`.
+    pub fn new_component(section: &str, name: &str) -> Self {
+        Self {
+            reference: format!("#/components/{section}/{name}"),
+        }
+    }
 }
 
 pub type RefOr<T> = Either<ReferenceObject, T>;
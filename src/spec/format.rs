@@ -0,0 +1,198 @@
+//! Reading of specifications from YAML or JSON documents.
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use crate::spec::{document_version, AsyncApiSpec, DocumentVersion};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Cannot read {path:?}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Not an AsyncAPI document: {reason}")]
+    NotAsyncApi { reason: String },
+    #[error("Unsupported AsyncAPI version {version}")]
+    UnsupportedVersion { version: String },
+    #[error("Malformed {format} document: {reason}")]
+    Malformed { format: Format, reason: String },
+}
+
+/// Format of a document.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+    Json,
+    Yaml,
+}
+
+impl Format {
+    /// Detects the format of `content`: JSON documents are objects, anything else is YAML.
+    pub fn detect(content: &str) -> Self {
+        if content
+            .trim_start_matches('\u{feff}')
+            .trim_start()
+            .starts_with('{')
+        {
+            Self::Json
+        } else {
+            Self::Yaml
+        }
+    }
+
+    /// Format of a file given by its extension, if known.
+    pub fn from_extension(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "yaml" | "yml" => Some(Self::Yaml),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Json => write!(f, "JSON"),
+            Self::Yaml => write!(f, "YAML"),
+        }
+    }
+}
+
+impl AsyncApiSpec {
+    /// Reads a specification from a file, whose format is given by its extension or detected
+    /// from its content.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let format = Format::from_extension(path).unwrap_or_else(|| Format::detect(&content));
+        Self::from_str_with_format(&content, format)
+    }
+
+    /// Reads a specification from a document in the given format.
+    pub fn from_str_with_format(content: &str, format: Format) -> Result<Self, Error> {
+        let malformed = |reason: String| Error::Malformed { format, reason };
+
+        let version = match format {
+            Format::Json => {
+                let document: serde_json::Value =
+                    serde_json::from_str(content).map_err(|err| malformed(err.to_string()))?;
+                if !document.is_object() {
+                    return Err(Error::NotAsyncApi {
+                        reason: String::from("the document is not an object"),
+                    });
+                }
+                document_version(&document).map_err(|err| malformed(err.to_string()))?
+            }
+            Format::Yaml => {
+                let document: serde_yaml::Value =
+                    serde_yaml::from_str(content).map_err(|err| malformed(err.to_string()))?;
+                if !document.is_mapping() {
+                    return Err(Error::NotAsyncApi {
+                        reason: String::from("the document is not a mapping"),
+                    });
+                }
+                document_version(&document).map_err(|err| malformed(err.to_string()))?
+            }
+        };
+        match version {
+            DocumentVersion::Supported => {}
+            DocumentVersion::Unsupported(version) => {
+                return Err(Error::UnsupportedVersion { version })
+            }
+            DocumentVersion::Missing => {
+                return Err(Error::NotAsyncApi {
+                    reason: String::from("missing field `asyncapi`"),
+                })
+            }
+        }
+        let spec = match format {
+            Format::Json => serde_json::from_str(content).map_err(|err| malformed(err.to_string())),
+            Format::Yaml => serde_yaml::from_str(content).map_err(|err| malformed(err.to_string())),
+        }?;
+        Ok(Self::V3_0_0(spec))
+    }
+}
+
+impl FromStr for AsyncApiSpec {
+    type Err = Error;
+
+    /// Reads a specification from a YAML or JSON document, see [`Format::detect`].
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        Self::from_str_with_format(content, Format::detect(content))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const YAML: &str = "asyncapi: 3.0.0\ninfo:\n  title: Lights\n  version: 1.0.0\n";
+    const JSON: &str = r#"{"asyncapi": "3.0.0", "info": {"title": "Lights", "version": "1.0.0"}}"#;
+
+    #[test]
+    fn read_yaml_and_json() {
+        let yaml = AsyncApiSpec::from_str(YAML).unwrap();
+        let json = JSON.parse::<AsyncApiSpec>().unwrap();
+        assert_eq!(yaml, json);
+        assert_eq!(Format::detect(JSON), Format::Json);
+        assert_eq!(Format::detect(YAML), Format::Yaml);
+
+        // an AsyncAPI document whose schemas use `not`
+        let spec = AsyncApiSpec::from_path("test-res/3.0.0/not-asyncapi.yml").unwrap();
+        let AsyncApiSpec::V3_0_0(v3) = &spec;
+        assert_eq!(v3.info.title, "Not example");
+        let json = spec.to_json_pretty().unwrap();
+        assert_eq!(AsyncApiSpec::from_str(&json).unwrap(), spec);
+        let yaml = spec.to_yaml().unwrap();
+        assert_eq!(AsyncApiSpec::from_str(&yaml).unwrap(), spec);
+    }
+
+    #[test]
+    fn distinguish_errors() {
+        let err = AsyncApiSpec::from_str("openapi: 3.1.0\ninfo: {}\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Not an AsyncAPI document: missing field `asyncapi`"
+        );
+        let err = AsyncApiSpec::from_str("- asyncapi: 3.0.0\n").unwrap_err();
+        assert!(matches!(err, Error::NotAsyncApi { .. }));
+        let err = AsyncApiSpec::from_str("[1]").unwrap_err();
+        assert!(matches!(err, Error::NotAsyncApi { .. }));
+        let err = AsyncApiSpec::from_str(r#"{"asyncapi": "3.0.0", "info": []}"#).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Malformed {
+                format: Format::Json,
+                ..
+            }
+        ));
+
+        let err = AsyncApiSpec::from_str(r#"{"asyncapi": "2.6.0", "info": {}}"#).unwrap_err();
+        assert_eq!(err.to_string(), "Unsupported AsyncAPI version 2.6.0");
+        let err = AsyncApiSpec::from_str("asyncapi: 3.0\n").unwrap_err();
+        assert_eq!(err.to_string(), "Unsupported AsyncAPI version 3.0");
+
+        let err = AsyncApiSpec::from_str("asyncapi: 3.0.0\ninfo:\n  title: Lights\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Malformed YAML document: info: missing field `version` at line 3 column 3"
+        );
+        let err = AsyncApiSpec::from_str(r#"{"asyncapi": "3.0.0", "info": {"#).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Malformed {
+                format: Format::Json,
+                ..
+            }
+        ));
+        let err = AsyncApiSpec::from_path("test-res/missing.yml").unwrap_err();
+        assert!(matches!(err, Error::Io { .. }));
+    }
+}
@@ -3,9 +3,9 @@
 //! types.
 use crate::spec::common::{Either, ExternalDocumentation, RefOr, Tag};
 use core::num::NonZeroU16;
-use std::collections::HashMap;
+use indexmap::IndexMap;
 
-pub type Messages = HashMap<String, RefOr<Message>>;
+pub type Messages = IndexMap<String, RefOr<Message>>;
 
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -123,11 +123,15 @@ impl HttpMessageBinding {
 /// Message Example Object represents an example of a Message Object and MUST contain either headers and/or payload fields.
 pub struct MessageExample {
     /// The value of this field MUST validate against the Message Object's headers field.
-    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
-    pub headers: HashMap<String, serde_json::Value>,
-    /// The value of this field MUST validate against the Message Object's payload field.
-    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
-    pub payload: HashMap<String, serde_json::Value>,
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub headers: IndexMap<String, serde_json::Value>,
+    /// The value of this field MUST validate against the Message Object's payload field. It can be of any type, as payloads are not required to be JSON objects. An explicit `null` payload is kept as `Some(Value::Null)`.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub payload: Option<serde_json::Value>,
     /// A machine-friendly name.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
@@ -136,6 +140,28 @@ pub struct MessageExample {
     pub summary: Option<String>,
 }
 
+/// Deserializes a field that is present in the document, keeping an explicit `null` as `Some`.
+fn deserialize_present<'de, D>(deserializer: D) -> Result<Option<serde_json::Value>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    <serde_json::Value as serde::Deserialize>::deserialize(deserializer).map(Some)
+}
+
+impl MessageExample {
+    /// Renders the example as pretty-printed JSON, e.g. to embed it in generated documentation.
+    pub fn to_json_pretty(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Renders the example as YAML, e.g. to embed it in generated documentation.
+    #[cfg(feature = "yaml")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "yaml")))]
+    pub fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 /// Describes a trait that MAY be applied to a Message Object. This object MAY contain any property from the Message Object, except payload and traits.
@@ -8,6 +8,9 @@ use crate::spec::server::Servers;
 pub mod channel;
 pub mod common;
 pub mod component;
+#[cfg(feature = "parse")]
+#[cfg_attr(docsrs, doc(cfg(feature = "parse")))]
+pub mod format;
 pub mod info;
 pub mod message;
 pub mod operation;
@@ -23,6 +26,40 @@ pub enum AsyncApiSpec {
     V3_0_0(AsyncApiV3Spec),
 }
 
+/// Version of a document, given by its `asyncapi` field.
+#[cfg(any(feature = "parse", feature = "loader", feature = "codegen"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum DocumentVersion {
+    /// A version deserialized by [`AsyncApiSpec`].
+    Supported,
+    /// Any other version, as written in the document.
+    Unsupported(String),
+    /// The document has no `asyncapi` field.
+    Missing,
+}
+
+/// Reads the version of a document. The version is checked before deserializing the
+/// specification, so that unsupported versions are told apart from malformed specifications.
+#[cfg(any(feature = "parse", feature = "loader", feature = "codegen"))]
+pub(crate) fn document_version<'de, D>(document: D) -> Result<DocumentVersion, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(serde::Deserialize)]
+    struct Versioned {
+        asyncapi: Option<serde_json::Value>,
+    }
+    let versioned = <Versioned as serde::Deserialize>::deserialize(document)?;
+    Ok(match versioned.asyncapi {
+        Some(serde_json::Value::String(version)) if version == "3.0.0" => {
+            DocumentVersion::Supported
+        }
+        Some(serde_json::Value::String(version)) => DocumentVersion::Unsupported(version),
+        Some(version) => DocumentVersion::Unsupported(version.to_string()),
+        None => DocumentVersion::Missing,
+    })
+}
+
 /// Root type of an AsyncAPI 3 specification.
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -48,3 +85,47 @@ pub struct AsyncApiV3Spec {
     #[serde(default)]
     pub components: Components,
 }
+
+impl AsyncApiSpec {
+    /// Renders the specification as pretty-printed JSON.
+    pub fn to_json_pretty(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Renders the specification as YAML.
+    #[cfg(feature = "yaml")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "yaml")))]
+    pub fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
+
+    /// Sorts the keys of every map of the specification, see [`AsyncApiV3Spec::sort_keys`].
+    pub fn sort_keys(&mut self) -> Result<(), serde_json::Error> {
+        match self {
+            Self::V3_0_0(spec) => spec.sort_keys(),
+        }
+    }
+}
+
+impl AsyncApiV3Spec {
+    /// Sorts the keys of every map of the specification, schemas included, for a canonical
+    /// serialization that depends neither on the source order nor on the registration order.
+    /// The specification is left unchanged if it cannot be represented as JSON.
+    pub fn sort_keys(&mut self) -> Result<(), serde_json::Error> {
+        let mut value = serde_json::to_value(&*self)?;
+        sort_value_keys(&mut value);
+        *self = serde_json::from_value(value)?;
+        Ok(())
+    }
+}
+
+fn sort_value_keys(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(object) => {
+            object.sort_keys();
+            object.values_mut().for_each(sort_value_keys);
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(sort_value_keys),
+        _ => {}
+    }
+}
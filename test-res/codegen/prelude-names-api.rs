@@ -0,0 +1,74 @@
+// @generated by asyncapiv3 from the specification 'Prelude names example' 1.0.0, do not edit.
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Option_ {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub values: Option<Vec<String>>,
+}
+
+pub type String_ = String;
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Result_ {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<Option_>,
+    pub value: String_,
+}
+
+/// Client of the operations sent by the application.
+pub struct Client<T> {
+    transport: T,
+}
+
+impl<T: asyncapiv3::runtime::Transport> Client<T> {
+    pub fn new(transport: T) -> Self {
+        Self { transport }
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    pub async fn send_user(&self, payload: &Result_) -> ::std::result::Result<(), asyncapiv3::runtime::Error<T::Error>> {
+        let address = asyncapiv3::runtime::fill_address("users", &[]);
+        let payload = serde_json::to_value(payload)
+            .map_err(|source| asyncapiv3::runtime::Error::Payload { source })?;
+        self.transport
+            .publish(&address, payload)
+            .await
+            .map_err(|source| asyncapiv3::runtime::Error::Transport { source })
+    }
+
+    pub async fn send_user_(&self, payload: &Result_) -> ::std::result::Result<(), asyncapiv3::runtime::Error<T::Error>> {
+        let address = asyncapiv3::runtime::fill_address("users", &[]);
+        let payload = serde_json::to_value(payload)
+            .map_err(|source| asyncapiv3::runtime::Error::Payload { source })?;
+        self.transport
+            .publish(&address, payload)
+            .await
+            .map_err(|source| asyncapiv3::runtime::Error::Transport { source })
+    }
+}
+
+/// Handler of the operations received by the application.
+pub trait Handler: Send + Sync {
+    fn on_user(&self, payload: Result_) -> impl std::future::Future<Output = ()> + Send;
+}
+
+/// Dispatches a message received on `address` to the matching [`Handler`] method. Returns the
+/// serialized reply of request/reply operations.
+pub async fn dispatch<H: Handler>(
+    handler: &H,
+    address: &str,
+    payload: serde_json::Value,
+) -> ::std::result::Result<Option<serde_json::Value>, asyncapiv3::runtime::DispatchError> {
+    if asyncapiv3::runtime::match_address("users", address).is_some() {
+        let payload = serde_json::from_value(payload)
+            .map_err(|source| asyncapiv3::runtime::DispatchError::Payload { source })?;
+        handler.on_user(payload).await;
+        return Ok(None);
+    }
+    Err(asyncapiv3::runtime::DispatchError::UnknownAddress {
+        address: String::from(address),
+    })
+}
@@ -0,0 +1,16 @@
+// @generated by asyncapiv3 from the specification 'Prelude names example' 1.0.0, do not edit.
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Option_ {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub values: Option<Vec<String>>,
+}
+
+pub type String_ = String;
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Result_ {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<Option_>,
+    pub value: String_,
+}
@@ -0,0 +1,122 @@
+// @generated by asyncapiv3 from the specification 'Request reply example' 1.0.0, do not edit.
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct UserSignedUpPayload {
+    pub email: String,
+    #[serde(rename = "displayName", default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EmailPayload {
+    pub to: String,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SmsPayload {
+    pub number: String,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PriceRequestPayload {
+    pub sku: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<i64>,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PriceQuotePayload {
+    pub sku: String,
+    pub price: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+pub enum NotifyMessage {
+    Email(EmailPayload),
+    Sms(SmsPayload),
+}
+
+/// Client of the operations sent by the application.
+pub struct Client<T> {
+    transport: T,
+}
+
+impl<T: asyncapiv3::runtime::Transport> Client<T> {
+    pub fn new(transport: T) -> Self {
+        Self { transport }
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    pub async fn notify(&self, payload: &NotifyMessage) -> ::std::result::Result<(), asyncapiv3::runtime::Error<T::Error>> {
+        let address = asyncapiv3::runtime::fill_address("notifications", &[]);
+        let payload = serde_json::to_value(payload)
+            .map_err(|source| asyncapiv3::runtime::Error::Payload { source })?;
+        self.transport
+            .publish(&address, payload)
+            .await
+            .map_err(|source| asyncapiv3::runtime::Error::Transport { source })
+    }
+
+    /// Notifies that a user signed up.
+    pub async fn publish_user_signed_up(&self, user_id: &str, payload: &UserSignedUpPayload) -> ::std::result::Result<(), asyncapiv3::runtime::Error<T::Error>> {
+        let address = asyncapiv3::runtime::fill_address("users/{userId}/signup", &[("userId", user_id)]);
+        let payload = serde_json::to_value(payload)
+            .map_err(|source| asyncapiv3::runtime::Error::Payload { source })?;
+        self.transport
+            .publish(&address, payload)
+            .await
+            .map_err(|source| asyncapiv3::runtime::Error::Transport { source })
+    }
+
+    pub async fn request_price(&self, payload: &PriceRequestPayload) -> ::std::result::Result<PriceQuotePayload, asyncapiv3::runtime::Error<T::Error>> {
+        let address = asyncapiv3::runtime::fill_address("pricing/requests", &[]);
+        let payload = serde_json::to_value(payload)
+            .map_err(|source| asyncapiv3::runtime::Error::Payload { source })?;
+        let reply = self
+            .transport
+            .request(&address, Some("pricing/replies"), payload)
+            .await
+            .map_err(|source| asyncapiv3::runtime::Error::Transport { source })?;
+        serde_json::from_value(reply).map_err(|source| asyncapiv3::runtime::Error::Payload { source })
+    }
+}
+
+/// Handler of the operations received by the application.
+pub trait Handler: Send + Sync {
+    fn on_user_signed_up(&self, user_id: String, payload: UserSignedUpPayload) -> impl std::future::Future<Output = ()> + Send;
+
+    /// Computes the price of a product.
+    fn quote_price(&self, payload: PriceRequestPayload) -> impl std::future::Future<Output = PriceQuotePayload> + Send;
+}
+
+/// Dispatches a message received on `address` to the matching [`Handler`] method. Returns the
+/// serialized reply of request/reply operations.
+pub async fn dispatch<H: Handler>(
+    handler: &H,
+    address: &str,
+    payload: serde_json::Value,
+) -> ::std::result::Result<Option<serde_json::Value>, asyncapiv3::runtime::DispatchError> {
+    if let Some(mut parameters) = asyncapiv3::runtime::match_address("users/{userId}/signup", address) {
+        let payload = serde_json::from_value(payload)
+            .map_err(|source| asyncapiv3::runtime::DispatchError::Payload { source })?;
+        handler.on_user_signed_up(parameters.remove("userId").unwrap_or_default(), payload).await;
+        return Ok(None);
+    }
+    if asyncapiv3::runtime::match_address("pricing/requests", address).is_some() {
+        let payload = serde_json::from_value(payload)
+            .map_err(|source| asyncapiv3::runtime::DispatchError::Payload { source })?;
+        let reply = handler.quote_price(payload).await;
+        return serde_json::to_value(reply)
+            .map(Some)
+            .map_err(|source| asyncapiv3::runtime::DispatchError::Payload { source });
+    }
+    Err(asyncapiv3::runtime::DispatchError::UnknownAddress {
+        address: String::from(address),
+    })
+}
@@ -0,0 +1,197 @@
+#![cfg(feature = "cli")]
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_asyncapiv3"))
+        .args(args)
+        .output()
+        .expect("Cannot run the command line tool")
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+/// Directory of the files written by a test, removed when dropped.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(test: &str) -> Self {
+        let directory =
+            std::env::temp_dir().join(format!("asyncapiv3-cli-{}-{test}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        Self(directory)
+    }
+
+    fn file(&self, name: &str, content: &str) -> PathBuf {
+        let path = self.0.join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+#[test]
+fn validate_specifications() {
+    let temp = TempDir::new("validate-specifications");
+    let output = run(&["validate", "test-res/3.0.0/streetlights-kafka-asyncapi.yml"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let invalid = temp.file(
+        "invalid.yml",
+        "asyncapi: 3.0.0\ninfo:\n  title: Lights\nchannels:\n  lights:\n    address: 42\n    messages: {}\n",
+    );
+    let output = run(&["--format", "json", "validate", invalid.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+    let findings: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    let pointers = findings
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|finding| finding["pointer"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(pointers, ["/info", "/channels/lights/address"]);
+
+    let output = run(&["validate", "test-res/missing.yml"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn lint_with_config() {
+    let temp = TempDir::new("lint-with-config");
+    let config = temp.file(
+        "lint.toml",
+        "[rules]\nchannel-name-kebab-case = \"error\"\n",
+    );
+    let output = run(&[
+        "lint",
+        "--config",
+        config.to_str().unwrap(),
+        "test-res/3.0.0/streetlights-kafka-asyncapi.yml",
+    ]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).contains(
+        "streetlights-kafka-asyncapi.yml:57:3: error[channel-name-kebab-case]: /channels/lightTurnOff: "
+    ));
+
+    let output = run(&["lint", "test-res/3.0.0/streetlights-kafka-asyncapi.yml"]);
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn fail_on_breaking_changes() {
+    let old = "test-res/3.0.0/streetlights-kafka-asyncapi.yml";
+    let new = "test-res/3.0.0/streetlights-mqtt-asyncapi.yml";
+    let output = run(&["diff", old, old, "--fail-on", "any"]);
+    assert_eq!(output.status.code(), Some(0));
+    let output = run(&["diff", old, new]);
+    assert_eq!(output.status.code(), Some(0));
+    let output = run(&[
+        "--format",
+        "json",
+        "diff",
+        old,
+        new,
+        "--fail-on",
+        "breaking",
+    ]);
+    assert_eq!(output.status.code(), Some(1));
+    let changes: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert!(changes
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|change| change["compatibility"] == "breaking"));
+}
+
+#[test]
+fn convert_and_bundle_specifications() {
+    let temp = TempDir::new("convert-and-bundle-specifications");
+    let json = temp.file("simple.json", "");
+    let output = run(&[
+        "convert",
+        "test-res/3.0.0/simple-asyncapi.yml",
+        "-o",
+        json.to_str().unwrap(),
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+    let content = std::fs::read_to_string(&json).unwrap();
+    assert!(content.starts_with("{\n  \"asyncapi\": \"3.0.0\""));
+
+    let v2 = temp.file(
+        "v2.yml",
+        "\
+asyncapi: 2.6.0
+info: { title: Lights, version: 1.0.0 }
+channels:
+  lights/on:
+    subscribe:
+      message:
+        payload: { type: object }
+",
+    );
+    let output = run(&["convert", v2.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(0));
+    let converted: serde_json::Value = serde_yaml::from_str(&stdout(&output)).unwrap();
+    assert_eq!(
+        converted["operations"]["lightsOnSubscribe"]["action"],
+        "send"
+    );
+
+    let output = run(&["bundle", json.to_str().unwrap(), "--to", "yaml"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).starts_with("asyncapi: 3.0.0\n"));
+
+    let output = run(&["--format", "json", "bundle", json.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(2));
+    let output = run(&["--format", "sarif", "convert", v2.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn report_as_sarif_and_junit() {
+    let temp = TempDir::new("report-as-sarif-and-junit");
+    let file = "test-res/3.0.0/streetlights-kafka-asyncapi.yml";
+    let output = run(&["--format", "sarif", "lint", file]);
+    assert_eq!(output.status.code(), Some(0));
+    let sarif: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    let sarif_run = &sarif["runs"][0];
+    let results = sarif_run["results"].as_array().unwrap();
+    assert!(!results.is_empty());
+    for result in results {
+        let index = result["ruleIndex"].as_u64().unwrap() as usize;
+        assert_eq!(
+            sarif_run["tool"]["driver"]["rules"][index]["id"],
+            result["ruleId"]
+        );
+        let location = &result["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], file);
+        assert!(location["region"]["startLine"].as_u64().unwrap() > 0);
+    }
+
+    let invalid = temp.file(
+        "invalid-junit.yml",
+        "asyncapi: 3.0.0\ninfo:\n  title: Lights\n",
+    );
+    let output = run(&[
+        "--format",
+        "junit",
+        "validate",
+        file,
+        invalid.to_str().unwrap(),
+    ]);
+    assert_eq!(output.status.code(), Some(1));
+    let junit = stdout(&output);
+    assert!(junit.contains("<testsuites name=\"asyncapiv3\" tests=\"2\" failures=\"1\">"));
+    assert!(junit.contains(&format!("<testcase name=\"{file}\" classname=\"{file}\"/>")));
+    assert!(junit.contains("<testcase name=\"invalid-specification: /info\""));
+
+    let output = run(&["--format", "sarif", "diff", file, file]);
+    assert_eq!(output.status.code(), Some(2));
+}
@@ -0,0 +1,161 @@
+#![cfg(all(feature = "codegen", feature = "runtime"))]
+use asyncapiv3::codegen::{generate_api, read_spec};
+use asyncapiv3::runtime::memory::InMemoryTransport;
+use serde_json::json;
+use std::future::Future;
+use std::path::Path;
+use std::pin::pin;
+use std::sync::Mutex;
+use std::task::{Context, Poll, Waker};
+
+/// Code generated from `test-res/3.0.0/request-reply-asyncapi.yml`, regenerated with
+/// `UPDATE_EXPECT=1`.
+#[allow(dead_code)]
+mod api {
+    include!("../test-res/codegen/request-reply-api.rs");
+}
+
+/// Code generated from `test-res/3.0.0/prelude-names-asyncapi.yml`.
+#[allow(dead_code)]
+mod prelude_names {
+    include!("../test-res/codegen/prelude-names-api.rs");
+}
+
+/// Polls a future that is expected to complete without waiting, as the in-memory transport does.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut context = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+fn check_generated(spec: &str, expected: &str) {
+    let spec = read_spec(Path::new("test-res/3.0.0").join(spec)).unwrap();
+    let generated = generate_api(&spec).unwrap();
+    let expected_path = Path::new("test-res/codegen").join(expected);
+    if std::env::var_os("UPDATE_EXPECT").is_some() {
+        std::fs::write(&expected_path, &generated).unwrap();
+    }
+    assert_eq!(generated, std::fs::read_to_string(expected_path).unwrap());
+}
+
+#[test]
+fn generated_api_is_up_to_date() {
+    check_generated("request-reply-asyncapi.yml", "request-reply-api.rs");
+    check_generated("prelude-names-asyncapi.yml", "prelude-names-api.rs");
+}
+
+#[test]
+fn generated_client_publishes_through_transport() {
+    let transport = InMemoryTransport::with_responder(|address, payload| {
+        assert_eq!(address, "pricing/requests");
+        Some(json!({ "sku": payload["sku"], "price": 9.5 }))
+    });
+    let client = api::Client::new(transport);
+
+    block_on(client.publish_user_signed_up(
+        "42",
+        &api::UserSignedUpPayload {
+            email: String::from("jdoe@example.com"),
+            display_name: None,
+        },
+    ))
+    .unwrap();
+    block_on(client.notify(&api::NotifyMessage::Sms(api::SmsPayload {
+        number: String::from("+39 000"),
+    })))
+    .unwrap();
+    let quote = block_on(client.request_price(&api::PriceRequestPayload {
+        sku: String::from("A1"),
+        quantity: Some(2),
+    }))
+    .unwrap();
+    assert_eq!(quote.sku, "A1");
+    assert_eq!(quote.price, 9.5);
+
+    let published = client_transport_messages(&client);
+    assert_eq!(published[0].0, "users/42/signup");
+    assert_eq!(published[0].1, json!({ "email": "jdoe@example.com" }));
+    assert_eq!(published[1].1, json!({ "number": "+39 000" }));
+    assert_eq!(published[2].2.as_deref(), Some("pricing/replies"));
+}
+
+fn client_transport_messages(
+    client: &api::Client<InMemoryTransport>,
+) -> Vec<(String, serde_json::Value, Option<String>)> {
+    client
+        .transport()
+        .published()
+        .into_iter()
+        .map(|message| (message.address, message.payload, message.reply_address))
+        .collect()
+}
+
+#[derive(Default)]
+struct RecordingHandler {
+    signups: Mutex<Vec<(String, String)>>,
+}
+
+impl api::Handler for RecordingHandler {
+    async fn on_user_signed_up(&self, user_id: String, payload: api::UserSignedUpPayload) {
+        self.signups.lock().unwrap().push((user_id, payload.email));
+    }
+
+    async fn quote_price(&self, payload: api::PriceRequestPayload) -> api::PriceQuotePayload {
+        api::PriceQuotePayload {
+            sku: payload.sku,
+            price: 2.0 * payload.quantity.unwrap_or(1) as f64,
+        }
+    }
+}
+
+#[test]
+fn generated_dispatch_routes_to_handler() {
+    let handler = RecordingHandler::default();
+
+    let reply = block_on(api::dispatch(
+        &handler,
+        "users/42/signup",
+        json!({ "email": "jdoe@example.com" }),
+    ))
+    .unwrap();
+    assert_eq!(reply, None);
+    assert_eq!(
+        *handler.signups.lock().unwrap(),
+        vec![(String::from("42"), String::from("jdoe@example.com"))]
+    );
+
+    let reply = block_on(api::dispatch(
+        &handler,
+        "pricing/requests",
+        json!({ "sku": "A1", "quantity": 3 }),
+    ))
+    .unwrap();
+    assert_eq!(reply, Some(json!({ "sku": "A1", "price": 6.0 })));
+
+    assert!(block_on(api::dispatch(&handler, "unknown", json!({}))).is_err());
+}
+
+#[test]
+fn generated_client_methods_are_unique() {
+    let client = prelude_names::Client::new(InMemoryTransport::new());
+    let payload = prelude_names::Result_ {
+        value: String::from("ok"),
+        error: None,
+    };
+    block_on(client.send_user(&payload)).unwrap();
+    block_on(client.send_user_(&payload)).unwrap();
+    assert_eq!(client_published_addresses(&client), ["users", "users"]);
+}
+
+fn client_published_addresses(client: &prelude_names::Client<InMemoryTransport>) -> Vec<String> {
+    client
+        .transport()
+        .published()
+        .into_iter()
+        .map(|message| message.address)
+        .collect()
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "codegen")]
+use asyncapiv3::codegen::{generate_types, read_spec};
+use std::path::Path;
+
+/// Types generated from `test-res/3.0.0/oneof-asyncapi.yml`, regenerated with `UPDATE_EXPECT=1`.
+#[allow(dead_code)]
+mod oneof {
+    include!("../test-res/codegen/oneof-types.rs");
+}
+
+#[allow(dead_code)]
+mod anyof {
+    include!("../test-res/codegen/anyof-types.rs");
+}
+
+#[allow(dead_code)]
+mod prelude_names {
+    include!("../test-res/codegen/prelude-names-types.rs");
+}
+
+fn check_generated(spec: &str, expected: &str) {
+    let spec = read_spec(Path::new("test-res/3.0.0").join(spec)).unwrap();
+    let generated = generate_types(&spec).unwrap();
+    let expected_path = Path::new("test-res/codegen").join(expected);
+    if std::env::var_os("UPDATE_EXPECT").is_some() {
+        std::fs::write(&expected_path, &generated).unwrap();
+    }
+    assert_eq!(generated, std::fs::read_to_string(expected_path).unwrap());
+}
+
+#[test]
+fn generated_types_are_up_to_date() {
+    check_generated("oneof-asyncapi.yml", "oneof-types.rs");
+    check_generated("anyof-asyncapi.yml", "anyof-types.rs");
+    check_generated("prelude-names-asyncapi.yml", "prelude-names-types.rs");
+}
+
+#[test]
+fn generated_types_deserialize_payloads() {
+    let payload: oneof::TestMessagesPayload =
+        serde_json::from_str(r#"{ "key": "value" }"#).unwrap();
+    assert_eq!(
+        payload,
+        oneof::TestMessagesPayload::ObjectWithKey(oneof::ObjectWithKey {
+            key: Some(String::from("value")),
+        })
+    );
+
+    let payload: anyof::TestMessagesPayload =
+        serde_json::from_str(r#"{ "key2": "value" }"#).unwrap();
+    assert_eq!(
+        payload,
+        anyof::TestMessagesPayload::ObjectWithKey2(anyof::ObjectWithKey2 {
+            key2: Some(String::from("value")),
+        })
+    );
+}
+
+#[test]
+fn generated_types_do_not_shadow_the_prelude() {
+    let payload: prelude_names::Result_ =
+        serde_json::from_str(r#"{ "value": "ok", "error": { "values": ["none"] } }"#).unwrap();
+    assert_eq!(
+        payload,
+        prelude_names::Result_ {
+            value: String::from("ok"),
+            error: Some(prelude_names::Option_ {
+                values: Some(vec![String::from("none")]),
+            }),
+        }
+    );
+}
@@ -0,0 +1,166 @@
+#![cfg(feature = "derive")]
+use asyncapiv3::builder::describe::{asyncapi_operation, AsyncApiMessage, MessageMetadata};
+use asyncapiv3::builder::operation::MessageFullSpec;
+use asyncapiv3::builder::AsyncApiV3Builder;
+use asyncapiv3::spec::common::Either;
+use asyncapiv3::spec::info::Info;
+use asyncapiv3::spec::operation::OperationAction;
+use asyncapiv3::spec::AsyncApiSpec;
+use schemars::JsonSchema;
+use serde_json::json;
+
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+struct Address {
+    city: String,
+}
+
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+struct TraceHeaders {
+    trace_id: String,
+}
+
+/// A user signed up.
+///
+/// Sent once per user.
+#[allow(dead_code)]
+#[derive(JsonSchema, AsyncApiMessage)]
+#[asyncapi(
+    name = "userSignedUp",
+    content_type = "application/json",
+    headers = TraceHeaders,
+    example = r#"{ "email": "jdoe@example.com", "address": { "city": "Milan" } }"#
+)]
+struct UserSignedUp {
+    email: String,
+    address: Address,
+}
+
+#[allow(dead_code)]
+#[derive(JsonSchema, AsyncApiMessage)]
+struct Welcome {
+    text: String,
+}
+
+/// Greets new users.
+#[allow(dead_code)]
+#[asyncapi_operation(channel = "userSignup", address = "user/signedup")]
+async fn on_user_signed_up(
+    _context: &str,
+    #[payload] event: UserSignedUp,
+) -> Result<Welcome, String> {
+    Ok(Welcome { text: event.email })
+}
+
+#[allow(dead_code)]
+#[asyncapi_operation(channel = "userSignup", action = "send", name = "publishUserSignedUp")]
+fn publish_user_signed_up(_event: &UserSignedUp) {}
+
+fn info() -> Info {
+    Info {
+        title: String::from("Users"),
+        version: String::from("1.0.0"),
+        description: None,
+        terms_of_service: None,
+        contact: None,
+        license: None,
+        tags: vec![],
+        external_docs: None,
+    }
+}
+
+#[test]
+fn derived_message_describes_payload_and_headers() {
+    assert_eq!(UserSignedUp::message_name(), "userSignedUp");
+    assert_eq!(Welcome::message_name(), "Welcome");
+
+    let spec = UserSignedUp::message_spec();
+    assert_eq!(
+        spec.message.content_type.as_deref(),
+        Some("application/json")
+    );
+    assert_eq!(
+        spec.message.description.as_deref(),
+        Some("A user signed up.\n\nSent once per user.")
+    );
+    assert_eq!(
+        spec.message.examples[0].payload,
+        Some(json!({ "email": "jdoe@example.com", "address": { "city": "Milan" } }))
+    );
+    let Some(Either::Right(Either::Left(payload))) = &spec.message.payload else {
+        panic!("payload should be a schema");
+    };
+    assert_eq!(
+        payload.as_value()["properties"]["address"]["$ref"],
+        json!("#/components/schemas/Address")
+    );
+    assert!(spec.message.headers.is_some());
+    assert!(spec.definitions.contains_key("Address"));
+    assert!(!spec.definitions.contains_key("UserSignedUp"));
+}
+
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+struct Parent {
+    child: Child,
+}
+
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+struct Child {
+    parent: Option<Box<Parent>>,
+}
+
+#[test]
+fn indirectly_recursive_payloads_keep_their_definition() {
+    let spec = MessageFullSpec::for_type::<Parent>(MessageMetadata::default());
+    let Some(Either::Right(Either::Left(payload))) = &spec.message.payload else {
+        panic!("payload should be a schema");
+    };
+    assert_eq!(
+        payload.as_value()["$ref"],
+        json!("#/components/schemas/Parent")
+    );
+    assert!(spec.definitions.contains_key("Parent"));
+    assert!(spec.definitions.contains_key("Child"));
+}
+
+#[test]
+fn operations_are_registered_from_handlers() {
+    let mut builder = AsyncApiV3Builder::new(info());
+    builder
+        .register_operation_descriptor(on_user_signed_up_operation())
+        .unwrap();
+    builder
+        .register_operation_descriptor(publish_user_signed_up_operation())
+        .unwrap();
+    assert!(builder
+        .register_operation_descriptor(publish_user_signed_up_operation())
+        .is_err());
+
+    let AsyncApiSpec::V3_0_0(spec) = builder.build().spec;
+    let Some(Either::Right(channel)) = spec.channels.get("userSignup") else {
+        panic!("channel should be registered");
+    };
+    assert_eq!(channel.address.as_deref(), Some("user/signedup"));
+    assert!(channel.messages.contains_key("userSignedUp"));
+    let Some(Either::Right(reply_channel)) = spec.channels.get("userSignupReply") else {
+        panic!("reply channel should be registered");
+    };
+    assert!(reply_channel.messages.contains_key("Welcome"));
+    assert!(spec.components.schemas.contains_key("Address"));
+
+    let Some(Either::Right(operation)) = spec.operations.get("on_user_signed_up") else {
+        panic!("operation should be registered");
+    };
+    assert_eq!(operation.action, OperationAction::Receive);
+    assert_eq!(operation.summary.as_deref(), Some("Greets new users."));
+    assert!(operation.reply.is_some());
+
+    let Some(Either::Right(operation)) = spec.operations.get("publishUserSignedUp") else {
+        panic!("operation should be registered");
+    };
+    assert_eq!(operation.action, OperationAction::Send);
+    assert!(operation.reply.is_none());
+}
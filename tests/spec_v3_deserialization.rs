@@ -20,6 +20,75 @@ fn deserialize_asyncapi_v3_examples() {
     }
 }
 
+#[test]
+fn serialize_asyncapi_v3_examples_in_source_order() {
+    let mut paths = fs::read_dir("./test-res/3.0.0")
+        .unwrap()
+        .map(|entry| entry.expect("Cannot read direntry").path())
+        .collect::<Vec<_>>();
+
+    paths.sort();
+
+    for path in paths {
+        let source: serde_yaml::Value =
+            serde_yaml::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let mut spec = deserialize_spec(&path).unwrap();
+        let serialized = serde_yaml::to_value(&spec).unwrap();
+        for pointer in map_pointers(&source) {
+            assert_eq!(
+                keys(&source, &pointer),
+                keys(&serialized, &pointer),
+                "{path:?} {pointer}"
+            );
+        }
+
+        let original = spec.clone();
+        spec.sort_keys().unwrap();
+        assert_eq!(spec, original);
+        let sorted = serde_yaml::to_value(&spec).unwrap();
+        for pointer in map_pointers(&source) {
+            let mut expected = keys(&source, &pointer);
+            expected.sort();
+            assert_eq!(keys(&sorted, &pointer), expected, "{path:?} {pointer}");
+        }
+    }
+}
+
+/// Pointers of the maps of a specification whose keys are names.
+fn map_pointers(spec: &serde_yaml::Value) -> Vec<String> {
+    let mut pointers = vec![
+        String::from("/servers"),
+        String::from("/channels"),
+        String::from("/operations"),
+    ];
+    for section in keys(spec, "/components") {
+        pointers.push(format!("/components/{section}"));
+    }
+    for channel in keys(spec, "/channels") {
+        pointers.push(format!("/channels/{channel}/messages"));
+        pointers.push(format!("/channels/{channel}/parameters"));
+    }
+    for schema in keys(spec, "/components/schemas") {
+        pointers.push(format!("/components/schemas/{schema}/properties"));
+    }
+    pointers
+}
+
+/// Keys of the mapping at `pointer`, in order.
+fn keys(value: &serde_yaml::Value, pointer: &str) -> Vec<String> {
+    let mut value = Some(value);
+    for segment in pointer.split('/').skip(1) {
+        value = value.and_then(|value| value.get(segment));
+    }
+    match value {
+        Some(serde_yaml::Value::Mapping(mapping)) => mapping
+            .keys()
+            .filter_map(|key| key.as_str().map(String::from))
+            .collect(),
+        _ => vec![],
+    }
+}
+
 fn deserialize_spec(entry: &Path) -> Result<AsyncApiSpec, Box<dyn std::error::Error>> {
     let file = File::open(entry)?;
     let reader = BufReader::new(file);
@@ -28,3 +97,74 @@ fn deserialize_spec(entry: &Path) -> Result<AsyncApiSpec, Box<dyn std::error::Er
     })?;
     Ok(spec)
 }
+
+#[cfg(feature = "parse")]
+#[test]
+fn read_asyncapi_v3_examples_from_path() {
+    let mut paths = fs::read_dir("./test-res/3.0.0")
+        .unwrap()
+        .map(|entry| entry.expect("Cannot read direntry").path())
+        .collect::<Vec<_>>();
+
+    paths.sort();
+
+    for path in paths {
+        let spec = AsyncApiSpec::from_path(&path).unwrap_or_else(|err| panic!("{path:?}: {err}"));
+        assert_eq!(spec, deserialize_spec(&path).unwrap());
+
+        let json = spec.to_json_pretty().unwrap();
+        assert_eq!(json.parse::<AsyncApiSpec>().unwrap(), spec);
+    }
+}
+
+#[cfg(feature = "loader")]
+#[test]
+fn load_asyncapi_v3_examples_with_positions() {
+    let mut paths = fs::read_dir("./test-res/3.0.0")
+        .unwrap()
+        .map(|entry| entry.expect("Cannot read direntry").path())
+        .collect::<Vec<_>>();
+
+    paths.sort();
+
+    for path in paths {
+        let loaded = asyncapiv3::loader::load_file(&path).unwrap_or_else(|err| panic!("{err}"));
+        assert!(loaded.locate("/info/title").is_some());
+
+        let lenient = asyncapiv3::loader::lenient::load_file(&path).unwrap();
+        assert_eq!(lenient.errors, [], "{path:?}");
+        assert_eq!(lenient.warnings, [], "{path:?}");
+    }
+}
+
+#[test]
+fn round_trip_message_example_payloads() {
+    use asyncapiv3::spec::message::MessageExample;
+
+    for payload in [
+        serde_json::json!(null),
+        serde_json::json!(42),
+        serde_json::json!("text"),
+        serde_json::json!([1, 2]),
+    ] {
+        let example = MessageExample {
+            headers: Default::default(),
+            payload: Some(payload.clone()),
+            name: None,
+            summary: None,
+        };
+
+        let json = example.to_json_pretty().unwrap();
+        let from_json: MessageExample = serde_json::from_str(&json).unwrap();
+        assert_eq!(from_json, example, "{json}");
+
+        let yaml = serde_yaml::to_string(&example).unwrap();
+        #[cfg(feature = "yaml")]
+        assert_eq!(example.to_yaml().unwrap(), yaml);
+        let from_yaml: MessageExample = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(from_yaml, example, "{yaml}");
+    }
+
+    let absent: MessageExample = serde_json::from_str(r#"{"name":"empty"}"#).unwrap();
+    assert_eq!(absent.payload, None);
+}